@@ -0,0 +1,131 @@
+use crate::{
+    ray::Ray,
+    vec3::{Point3, Vec3},
+};
+
+/// An axis-aligned bounding box.
+#[derive(Clone, Copy)]
+pub struct Aabb {
+    minimum: Point3,
+    maximum: Point3,
+}
+
+impl Aabb {
+    pub fn new(a: Point3, b: Point3) -> Self {
+        Self {
+            minimum: Point3::new(
+                a.get_x().min(b.get_x()),
+                a.get_y().min(b.get_y()),
+                a.get_z().min(b.get_z()),
+            ),
+            maximum: Point3::new(
+                a.get_x().max(b.get_x()),
+                a.get_y().max(b.get_y()),
+                a.get_z().max(b.get_z()),
+            ),
+        }
+    }
+
+    /// The box around a point with the given half extent along each axis.
+    pub fn around(center: Point3, half_extent: Vec3) -> Self {
+        Self::new(center.subtract(&half_extent), center.add(&half_extent))
+    }
+
+    pub const fn get_minimum(&self) -> Point3 {
+        self.minimum
+    }
+
+    pub const fn get_maximum(&self) -> Point3 {
+        self.maximum
+    }
+
+    pub fn centroid(&self) -> Point3 {
+        self.minimum.add(&self.maximum).divide_constant(2.0)
+    }
+
+    /// Index of the axis along which the box is longest.
+    pub fn longest_axis(&self) -> usize {
+        let size = self.maximum.subtract(&self.minimum);
+        if size.get_x() > size.get_y() && size.get_x() > size.get_z() {
+            0
+        } else if size.get_y() > size.get_z() {
+            1
+        } else {
+            2
+        }
+    }
+
+    /// Slab test against the three pairs of planes.
+    pub fn hit(&self, ray: &Ray, t_min: f64, t_max: f64) -> bool {
+        self.clip(ray, t_min, t_max).is_some()
+    }
+
+    /// The part of `[t_min, t_max]` during which the ray is inside the box.
+    pub fn clip(&self, ray: &Ray, mut t_min: f64, mut t_max: f64) -> Option<(f64, f64)> {
+        let origin = ray.get_origin();
+        let direction = ray.get_direction();
+        for axis in 0..3 {
+            let inverse = 1.0 / component(&direction, axis);
+            let mut t0 = (component(&self.minimum, axis) - component(&origin, axis)) * inverse;
+            let mut t1 = (component(&self.maximum, axis) - component(&origin, axis)) * inverse;
+            if inverse < 0.0 {
+                std::mem::swap(&mut t0, &mut t1);
+            }
+
+            t_min = t0.max(t_min);
+            t_max = t1.min(t_max);
+            if t_max < t_min {
+                return None;
+            }
+        }
+
+        Some((t_min, t_max))
+    }
+
+    pub fn contains(&self, point: &Point3) -> bool {
+        (self.minimum.get_x()..=self.maximum.get_x()).contains(&point.get_x())
+            && (self.minimum.get_y()..=self.maximum.get_y()).contains(&point.get_y())
+            && (self.minimum.get_z()..=self.maximum.get_z()).contains(&point.get_z())
+    }
+
+    /// The box grown by `margin` on every side.
+    pub fn expand(&self, margin: f64) -> Self {
+        Self::new(
+            self.minimum.add_constant(-margin),
+            self.maximum.add_constant(margin),
+        )
+    }
+
+    pub fn surrounding(&self, other: &Self) -> Self {
+        Self::new(
+            Point3::new(
+                self.minimum.get_x().min(other.minimum.get_x()),
+                self.minimum.get_y().min(other.minimum.get_y()),
+                self.minimum.get_z().min(other.minimum.get_z()),
+            ),
+            Point3::new(
+                self.maximum.get_x().max(other.maximum.get_x()),
+                self.maximum.get_y().max(other.maximum.get_y()),
+                self.maximum.get_z().max(other.maximum.get_z()),
+            ),
+        )
+    }
+}
+
+/// Half extents of a disk of the given radius perpendicular to the unit vector `axis`.
+pub fn disk_extent(axis: &Vec3, radius: f64) -> Vec3 {
+    let extent = |component: f64| radius * (1.0 - component * component).max(0.0).sqrt();
+    Vec3::new(
+        extent(axis.get_x()),
+        extent(axis.get_y()),
+        extent(axis.get_z()),
+    )
+}
+
+pub fn component(vector: &Vec3, axis: usize) -> f64 {
+    match axis {
+        0 => vector.get_x(),
+        1 => vector.get_y(),
+        _ => vector.get_z(),
+    }
+}
@@ -0,0 +1,122 @@
+use crate::vec3::Vec3;
+
+const BEZIER_ITERATIONS: usize = 16;
+
+pub trait Interpolate: Copy {
+    fn interpolate(&self, other: &Self, t: f64) -> Self;
+}
+
+impl Interpolate for f64 {
+    fn interpolate(&self, other: &Self, t: f64) -> Self {
+        self + (other - self) * t
+    }
+}
+
+impl Interpolate for Vec3 {
+    fn interpolate(&self, other: &Self, t: f64) -> Self {
+        self.add(&other.subtract(self).multiply_constant(t))
+    }
+}
+
+/// How a segment moves from one keyframe to the next.
+#[derive(Clone, Copy)]
+pub enum Easing {
+    Linear,
+    /// A cubic Bézier timing curve from (0, 0) to (1, 1) with control points `(x1, y1)` and
+    /// `(x2, y2)`, as in CSS `cubic-bezier`.
+    Bezier {
+        x1: f64,
+        y1: f64,
+        x2: f64,
+        y2: f64,
+    },
+}
+
+impl Easing {
+    fn apply(&self, s: f64) -> f64 {
+        match *self {
+            Self::Linear => s,
+            Self::Bezier { x1, y1, x2, y2 } => {
+                let x1 = x1.clamp(0.0, 1.0);
+                let x2 = x2.clamp(0.0, 1.0);
+
+                // x(t) is monotonic for control points inside [0, 1], so bisection finds t.
+                let (mut low, mut high) = (0.0, 1.0);
+                for _ in 0..BEZIER_ITERATIONS {
+                    let t = 0.5 * (low + high);
+                    if cubic_bezier(x1, x2, t) < s {
+                        low = t;
+                    } else {
+                        high = t;
+                    }
+                }
+
+                cubic_bezier(y1, y2, 0.5 * (low + high))
+            }
+        }
+    }
+}
+
+fn cubic_bezier(p1: f64, p2: f64, t: f64) -> f64 {
+    let u = 1.0 - t;
+    3.0 * u * u * t * p1 + 3.0 * u * t * t * p2 + t * t * t
+}
+
+struct Keyframe<T> {
+    frame: f64,
+    value: T,
+    easing: Easing,
+}
+
+/// A value that may change over time. Without keyframes it keeps its initial value; before the
+/// first and after the last keyframe it holds that keyframe's value.
+pub struct Animated<T> {
+    value: T,
+    keyframes: Vec<Keyframe<T>>,
+}
+
+impl<T: Interpolate> Animated<T> {
+    pub const fn new(value: T) -> Self {
+        Self {
+            value,
+            keyframes: Vec::new(),
+        }
+    }
+
+    pub fn set(&mut self, value: T) {
+        self.value = value;
+    }
+
+    /// Adds a keyframe, with `easing` used for the segment that starts at it.
+    pub fn add_keyframe(&mut self, frame: f64, value: T, easing: Easing) {
+        let index = self
+            .keyframes
+            .partition_point(|keyframe| keyframe.frame <= frame);
+        self.keyframes.insert(
+            index,
+            Keyframe {
+                frame,
+                value,
+                easing,
+            },
+        );
+    }
+
+    pub fn at(&self, frame: f64) -> T {
+        let index = self
+            .keyframes
+            .partition_point(|keyframe| keyframe.frame <= frame);
+        match (index.checked_sub(1), self.keyframes.get(index)) {
+            (None, None) => self.value,
+            (None, Some(next)) => next.value,
+            (Some(previous), None) => self.keyframes[previous].value,
+            (Some(previous), Some(next)) => {
+                let previous = &self.keyframes[previous];
+                let s = (frame - previous.frame) / (next.frame - previous.frame);
+                previous
+                    .value
+                    .interpolate(&next.value, previous.easing.apply(s))
+            }
+        }
+    }
+}
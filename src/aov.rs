@@ -0,0 +1,136 @@
+use std::{
+    fs::File,
+    io::{self, BufWriter, Write},
+    rc::Rc,
+};
+
+use crate::{
+    color::{Color, BLACK},
+    constants::INFINITY,
+    integrator::T_MIN,
+    material::Material,
+    ray::Ray,
+    scene::Scene,
+};
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum Aov {
+    Albedo,
+    Normal,
+    Depth,
+    Position,
+    MaterialId,
+    Direct,
+    Indirect,
+    SampleCount,
+}
+
+impl Aov {
+    pub fn from_name(name: &str) -> Option<Self> {
+        let aov = match name {
+            "albedo" => Self::Albedo,
+            "normal" => Self::Normal,
+            "depth" => Self::Depth,
+            "position" => Self::Position,
+            "material" => Self::MaterialId,
+            "direct" => Self::Direct,
+            "indirect" => Self::Indirect,
+            "samples" => Self::SampleCount,
+            _ => return None,
+        };
+
+        Some(aov)
+    }
+
+    pub const fn name(&self) -> &'static str {
+        match self {
+            Self::Albedo => "albedo",
+            Self::Normal => "normal",
+            Self::Depth => "depth",
+            Self::Position => "position",
+            Self::MaterialId => "material",
+            Self::Direct => "direct",
+            Self::Indirect => "indirect",
+            Self::SampleCount => "samples",
+        }
+    }
+}
+
+/// Per-sample values of the auxiliary channels, taken at the first hit of the camera ray.
+pub struct AovSample {
+    albedo: Color,
+    normal: Color,
+    depth: f64,
+    position: Color,
+    material_id: Color,
+    direct: Color,
+    indirect: Color,
+}
+
+impl AovSample {
+    pub fn new(ray: &Ray, scene: &Scene, (direct, indirect): (Color, Color)) -> Self {
+        let Some(res) = scene.get_world().hit(ray, T_MIN, INFINITY) else {
+            return Self {
+                albedo: scene.background(ray),
+                normal: BLACK,
+                depth: 0.0,
+                position: BLACK,
+                material_id: BLACK,
+                direct,
+                indirect,
+            };
+        };
+        let material = res.get_material();
+
+        Self {
+            albedo: material.albedo(&res),
+            normal: res.get_normal(),
+            depth: res.get_t() * ray.get_direction().length(),
+            position: res.get_point(),
+            material_id: material_id_color(&material),
+            direct,
+            indirect,
+        }
+    }
+
+    pub fn get(&self, aov: Aov) -> Color {
+        match aov {
+            Aov::Albedo => self.albedo,
+            Aov::Normal => self.normal,
+            Aov::Depth => Color::new(self.depth, self.depth, self.depth),
+            Aov::Position => self.position,
+            Aov::MaterialId => self.material_id,
+            Aov::Direct => self.direct,
+            Aov::Indirect => self.indirect,
+            Aov::SampleCount => BLACK,
+        }
+    }
+}
+
+pub fn material_id_color(material: &Rc<dyn Material>) -> Color {
+    let mut hash = Rc::as_ptr(material) as *const () as usize as u64;
+    hash ^= hash >> 33;
+    hash = hash.wrapping_mul(0xff51afd7ed558ccd);
+    hash ^= hash >> 33;
+    hash = hash.wrapping_mul(0xc4ceb9fe1a85ec53);
+    hash ^= hash >> 33;
+
+    let channel = |shift: u64| ((hash >> shift) & 0xff) as f64 / 255.0;
+    Color::new(channel(0), channel(8), channel(16))
+}
+
+/// Writes colors stored top row first as a little-endian PFM, which stores rows bottom to top.
+pub fn write_pfm(path: &str, width: usize, height: usize, colors: &[Color]) -> io::Result<()> {
+    let mut file = BufWriter::new(File::create(path)?);
+
+    write!(file, "PF\n{} {}\n-1.0\n", width, height)?;
+    for row in colors.chunks(width).rev() {
+        for color in row {
+            for channel in [color.get_x(), color.get_y(), color.get_z()] {
+                file.write_all(&(channel as f32).to_le_bytes())?;
+            }
+        }
+    }
+
+    Ok(())
+}
@@ -0,0 +1,96 @@
+use std::rc::Rc;
+
+use crate::{
+    color::luminance,
+    constants::PI,
+    ppm::read_ppm,
+    utils::{degrees_to_radians, sample_unit_disk},
+};
+
+/// The shape of the lens opening, sampled as points in the unit disk.
+#[derive(Clone)]
+pub enum Aperture {
+    Circular,
+    Polygon { blades: usize, rotation: f64 },
+    Mask(Rc<ApertureMask>),
+}
+
+impl Aperture {
+    pub fn polygon(blades: usize, rotation_degrees: f64) -> Self {
+        Self::Polygon {
+            blades: blades.max(3),
+            rotation: degrees_to_radians(rotation_degrees),
+        }
+    }
+
+    pub fn sample(&self, u: (f64, f64)) -> (f64, f64) {
+        match self {
+            Self::Circular => {
+                let point = sample_unit_disk(u);
+                (point.get_x(), point.get_y())
+            }
+            Self::Polygon { blades, rotation } => sample_polygon(*blades, *rotation, u),
+            Self::Mask(mask) => mask.sample(u),
+        }
+    }
+}
+
+/// Picks one of the triangles fanning out from the center, then a uniform point inside it.
+fn sample_polygon(blades: usize, rotation: f64, (u1, u2): (f64, f64)) -> (f64, f64) {
+    let scaled = u1 * blades as f64;
+    let blade = (scaled as usize).min(blades - 1);
+    let u1 = scaled - blade as f64;
+
+    let vertex = |index: usize| {
+        let angle = rotation + 2.0 * PI * index as f64 / blades as f64;
+        (angle.cos(), angle.sin())
+    };
+    let (ax, ay) = vertex(blade);
+    let (bx, by) = vertex(blade + 1);
+
+    let s = u1.sqrt();
+    let (wa, wb) = (s * (1.0 - u2), s * u2);
+    (wa * ax + wb * bx, wa * ay + wb * by)
+}
+
+/// A grayscale image over the square enclosing the unit disk, sampled in proportion to its
+/// brightness.
+pub struct ApertureMask {
+    width: usize,
+    height: usize,
+    cdf: Vec<f64>,
+}
+
+impl ApertureMask {
+    pub fn load(path: &str) -> Result<Self, String> {
+        let (width, height, colors) = read_ppm(path)?;
+        let mut cdf = Vec::with_capacity(colors.len());
+        let mut total = 0.0;
+        for color in &colors {
+            total += luminance(color).max(0.0);
+            cdf.push(total);
+        }
+        if total <= 0.0 {
+            return Err(format!("aperture mask {} is completely black", path));
+        }
+        cdf.iter_mut().for_each(|value| *value /= total);
+
+        Ok(Self { width, height, cdf })
+    }
+
+    fn sample(&self, (u1, u2): (f64, f64)) -> (f64, f64) {
+        let index = self
+            .cdf
+            .partition_point(|value| *value <= u1)
+            .min(self.cdf.len() - 1);
+        let previous = if index == 0 { 0.0 } else { self.cdf[index - 1] };
+        let jitter_x = ((u1 - previous) / (self.cdf[index] - previous)).clamp(0.0, 1.0);
+
+        let x = (index % self.width) as f64 + jitter_x;
+        let y = (index / self.width) as f64 + u2;
+        (
+            2.0 * x / self.width as f64 - 1.0,
+            1.0 - 2.0 * y / self.height as f64,
+        )
+    }
+}
@@ -0,0 +1,42 @@
+use crate::vec3::{Point3, Vec3};
+
+/// Cubic Bernstein polynomials and their derivatives at `t`.
+pub fn bernstein(t: f64) -> ([f64; 4], [f64; 4]) {
+    let s = 1.0 - t;
+    (
+        [s * s * s, 3.0 * t * s * s, 3.0 * t * t * s, t * t * t],
+        [
+            -3.0 * s * s,
+            3.0 * s * s - 6.0 * t * s,
+            6.0 * t * s - 3.0 * t * t,
+            3.0 * t * t,
+        ],
+    )
+}
+
+/// The point on a cubic curve at `t` and the derivative there.
+pub fn evaluate(points: &[Point3; 4], t: f64) -> (Point3, Vec3) {
+    let (basis, derivative) = bernstein(t);
+    points.iter().enumerate().fold(
+        (Point3::new(0.0, 0.0, 0.0), Vec3::new(0.0, 0.0, 0.0)),
+        |(point, tangent), (i, p)| {
+            (
+                point.add(&p.multiply_constant(basis[i])),
+                tangent.add(&p.multiply_constant(derivative[i])),
+            )
+        },
+    )
+}
+
+/// Splits a cubic curve in half with de Casteljau's algorithm.
+pub fn split([p0, p1, p2, p3]: [Point3; 4]) -> ([Point3; 4], [Point3; 4]) {
+    let middle = |a: &Point3, b: &Point3| a.add(b).multiply_constant(0.5);
+    let p01 = middle(&p0, &p1);
+    let p12 = middle(&p1, &p2);
+    let p23 = middle(&p2, &p3);
+    let p012 = middle(&p01, &p12);
+    let p123 = middle(&p12, &p23);
+    let p0123 = middle(&p012, &p123);
+
+    ([p0, p01, p012, p0123], [p0123, p123, p23, p3])
+}
@@ -0,0 +1,363 @@
+use std::{fs, rc::Rc};
+
+use crate::{
+    aabb::Aabb,
+    bezier::{bernstein, split},
+    hittable::{HitRecord, Hittable},
+    material::Material,
+    mesh::TriangleMesh,
+    ray::Ray,
+    stats,
+    utils::orthonormal_basis,
+    vec3::{Point3, Vec3},
+};
+
+/// Levels of subdivision before Newton's method takes over.
+const MAX_DEPTH: usize = 6;
+const NEWTON_ITERATIONS: usize = 8;
+/// Convergence tolerance relative to the size of the patch.
+const TOLERANCE: f64 = 1e-7;
+/// Step towards the middle of the patch used to find a normal where a side collapses to a
+/// point, like at the top of the teapot lid.
+const POLE_OFFSET: f64 = 1e-4;
+
+/// The 16 control points of a bicubic patch, indexed by u and then v.
+pub type ControlPoints = [[Point3; 4]; 4];
+
+/// Reads patches in the Utah teapot format: the number of patches, one line of 16
+/// comma-separated one-based vertex indices per patch, then the number of vertices and one
+/// `x, y, z` line per vertex.
+pub fn load_patches(path: &str) -> Result<Vec<ControlPoints>, String> {
+    let contents =
+        fs::read_to_string(path).map_err(|error| format!("failed to read {}: {}", path, error))?;
+    let mut lines = contents
+        .lines()
+        .enumerate()
+        .map(|(number, line)| {
+            let values = line
+                .split(|c: char| c == ',' || c.is_whitespace())
+                .filter(|token| !token.is_empty())
+                .map(str::parse::<f64>)
+                .collect::<Result<Vec<_>, _>>()
+                .map_err(|error| format!("{}:{}: {}", path, number + 1, error))?;
+            Ok((number + 1, values))
+        })
+        .filter(|line| !matches!(line, Ok((_, values)) if values.is_empty()));
+    let mut next_line = || {
+        lines
+            .next()
+            .unwrap_or_else(|| Err(format!("{}: unexpected end of file", path)))
+    };
+
+    // Counts are not trusted for preallocation, since the file may end long before them.
+    let count = |(number, values): (usize, Vec<f64>)| match values[..] {
+        [count] if count >= 0.0 && count.fract() == 0.0 => Ok(count as usize),
+        _ => Err(format!("{}:{}: expected a count", path, number)),
+    };
+
+    let patch_count = count(next_line()?)?;
+    let mut indices = Vec::new();
+    for _ in 0..patch_count {
+        let (number, values) = next_line()?;
+        if values.len() != 16 {
+            return Err(format!("{}:{}: expected 16 indices", path, number));
+        }
+        indices.push((number, values));
+    }
+
+    let vertex_count = count(next_line()?)?;
+    let mut vertices = Vec::new();
+    for _ in 0..vertex_count {
+        let (number, values) = next_line()?;
+        let [x, y, z] = values[..] else {
+            return Err(format!("{}:{}: expected x, y and z", path, number));
+        };
+        vertices.push(Point3::new(x, y, z));
+    }
+
+    indices
+        .into_iter()
+        .map(|(number, values)| {
+            let mut points = [[Point3::new(0.0, 0.0, 0.0); 4]; 4];
+            for (k, index) in values.into_iter().enumerate() {
+                // Indices start at 1.
+                let vertex = (index >= 1.0 && index.fract() == 0.0)
+                    .then(|| vertices.get(index as usize - 1))
+                    .flatten()
+                    .ok_or(format!(
+                        "{}:{}: invalid vertex index {}",
+                        path, number, index
+                    ))?;
+                points[k / 4][k % 4] = *vertex;
+            }
+            Ok(points)
+        })
+        .collect()
+}
+
+/// The point and its partial derivatives along u and v.
+fn evaluate(points: &ControlPoints, u: f64, v: f64) -> (Point3, Vec3, Vec3) {
+    let (bu, du) = bernstein(u);
+    let (bv, dv) = bernstein(v);
+    let zero = Vec3::new(0.0, 0.0, 0.0);
+
+    let mut point = zero;
+    let mut tangent_u = zero;
+    let mut tangent_v = zero;
+    for i in 0..4 {
+        for j in 0..4 {
+            let p = points[i][j];
+            point = point.add(&p.multiply_constant(bu[i] * bv[j]));
+            tangent_u = tangent_u.add(&p.multiply_constant(du[i] * bv[j]));
+            tangent_v = tangent_v.add(&p.multiply_constant(bu[i] * dv[j]));
+        }
+    }
+
+    (point, tangent_u, tangent_v)
+}
+
+fn normal(points: &ControlPoints, u: f64, v: f64) -> Vec3 {
+    let (_, tangent_u, tangent_v) = evaluate(points, u, v);
+    let normal = tangent_u.cross(&tangent_v);
+    if normal.near_zero() {
+        let (_, tangent_u, tangent_v) = evaluate(
+            points,
+            u + (0.5 - u).signum() * POLE_OFFSET,
+            v + (0.5 - v).signum() * POLE_OFFSET,
+        );
+        return tangent_u.cross(&tangent_v).unit_vector();
+    }
+
+    normal.unit_vector()
+}
+
+fn split_u(points: &ControlPoints) -> (ControlPoints, ControlPoints) {
+    let mut low = *points;
+    let mut high = *points;
+    for j in 0..4 {
+        let (a, b) = split([points[0][j], points[1][j], points[2][j], points[3][j]]);
+        for i in 0..4 {
+            low[i][j] = a[i];
+            high[i][j] = b[i];
+        }
+    }
+
+    (low, high)
+}
+
+fn split_v(points: &ControlPoints) -> (ControlPoints, ControlPoints) {
+    let mut low = *points;
+    let mut high = *points;
+    for i in 0..4 {
+        (low[i], high[i]) = split(points[i]);
+    }
+
+    (low, high)
+}
+
+fn bounds(points: &ControlPoints) -> Aabb {
+    points
+        .iter()
+        .flatten()
+        .fold(Aabb::new(points[0][0], points[0][0]), |bounds, point| {
+            bounds.surrounding(&Aabb::new(*point, *point))
+        })
+}
+
+/// A bicubic Bézier patch traced directly: the patch is moved into a frame where the ray is the
+/// z axis, subdivided while its control hull still straddles the ray, and the hit refined with
+/// Newton's method. u and v are the patch parameters.
+pub struct BezierPatch {
+    points: ControlPoints,
+    bounds: Aabb,
+    material: Rc<dyn Material>,
+}
+
+impl BezierPatch {
+    pub fn new(points: ControlPoints, material: Rc<dyn Material>) -> Self {
+        Self {
+            points,
+            bounds: bounds(&points),
+            material,
+        }
+    }
+
+    /// The closest hit within a sub-patch spanning `u` and `v`, given in ray space where x and y
+    /// are distances from the ray and z is the ray parameter.
+    fn subdivide(
+        &self,
+        ray_space: &ControlPoints,
+        patch: &ControlPoints,
+        (u, v): ((f64, f64), (f64, f64)),
+        (t_min, t_max): (f64, f64),
+        depth: usize,
+    ) -> Option<(f64, f64, f64)> {
+        let hull = bounds(patch);
+        let (minimum, maximum) = (hull.get_minimum(), hull.get_maximum());
+        let margin = TOLERANCE * self.size();
+        if minimum.get_x() > margin
+            || maximum.get_x() < -margin
+            || minimum.get_y() > margin
+            || maximum.get_y() < -margin
+            || minimum.get_z() > t_max
+            || maximum.get_z() < t_min
+        {
+            return None;
+        }
+
+        if depth == MAX_DEPTH {
+            return self.refine(
+                ray_space,
+                (u.0 + u.1) / 2.0,
+                (v.0 + v.1) / 2.0,
+                t_min,
+                t_max,
+            );
+        }
+
+        let (u_middle, v_middle) = ((u.0 + u.1) / 2.0, (v.0 + v.1) / 2.0);
+        let (low_u, high_u) = split_u(patch);
+        let mut closest: Option<(f64, f64, f64)> = None;
+        for (half, u_range) in [(low_u, (u.0, u_middle)), (high_u, (u_middle, u.1))] {
+            let (low_v, high_v) = split_v(&half);
+            for (quarter, v_range) in [(low_v, (v.0, v_middle)), (high_v, (v_middle, v.1))] {
+                let t_max = closest.map_or(t_max, |(_, _, t)| t);
+                if let Some(hit) = self.subdivide(
+                    ray_space,
+                    &quarter,
+                    (u_range, v_range),
+                    (t_min, t_max),
+                    depth + 1,
+                ) {
+                    closest = Some(hit);
+                }
+            }
+        }
+
+        closest
+    }
+
+    /// Newton's method on the distance from the ray, starting from `(u, v)`.
+    fn refine(
+        &self,
+        ray_space: &ControlPoints,
+        mut u: f64,
+        mut v: f64,
+        t_min: f64,
+        t_max: f64,
+    ) -> Option<(f64, f64, f64)> {
+        let tolerance = TOLERANCE * self.size();
+        for _ in 0..NEWTON_ITERATIONS {
+            let (point, tangent_u, tangent_v) = evaluate(ray_space, u, v);
+            let determinant =
+                tangent_u.get_x() * tangent_v.get_y() - tangent_u.get_y() * tangent_v.get_x();
+            if determinant.abs() < f64::EPSILON {
+                break;
+            }
+
+            u -= (tangent_v.get_y() * point.get_x() - tangent_v.get_x() * point.get_y())
+                / determinant;
+            v -= (tangent_u.get_x() * point.get_y() - tangent_u.get_y() * point.get_x())
+                / determinant;
+        }
+
+        if !(-TOLERANCE..=1.0 + TOLERANCE).contains(&u)
+            || !(-TOLERANCE..=1.0 + TOLERANCE).contains(&v)
+        {
+            return None;
+        }
+
+        let (u, v) = (u.clamp(0.0, 1.0), v.clamp(0.0, 1.0));
+        let (point, _, _) = evaluate(ray_space, u, v);
+        let t = point.get_z();
+        (point.get_x().hypot(point.get_y()) < tolerance && t >= t_min && t <= t_max)
+            .then_some((u, v, t))
+    }
+
+    fn size(&self) -> f64 {
+        self.bounds
+            .get_maximum()
+            .subtract(&self.bounds.get_minimum())
+            .length()
+    }
+}
+
+impl Hittable for BezierPatch {
+    fn hit(&self, ray: &Ray, t_min: f64, t_max: f64) -> Option<HitRecord> {
+        stats::record_intersection_test();
+        if !self.bounds.hit(ray, t_min, t_max) {
+            return None;
+        }
+
+        let origin = ray.get_origin();
+        let direction = ray.get_direction();
+        let (e1, e2) = orthonormal_basis(&direction.unit_vector());
+        let ray_space = self.points.map(|row| {
+            row.map(|point| {
+                let offset = point.subtract(&origin);
+                Vec3::new(
+                    offset.dot(&e1),
+                    offset.dot(&e2),
+                    offset.dot(&direction) / direction.square(),
+                )
+            })
+        });
+
+        let (u, v, t) = self.subdivide(
+            &ray_space,
+            &ray_space,
+            ((0.0, 1.0), (0.0, 1.0)),
+            (t_min, t_max),
+            0,
+        )?;
+
+        Some(HitRecord::new(
+            ray.at(t),
+            normal(&self.points, u, v),
+            t,
+            (u, v),
+            ray,
+            Rc::clone(&self.material),
+        ))
+    }
+
+    fn bounding_box(&self) -> Option<Aabb> {
+        Some(self.bounds)
+    }
+}
+
+/// Tessellates patches into a grid of `segments` by `segments` quads each, with normals and
+/// texture coordinates from the patch.
+pub fn tessellate(patches: &[ControlPoints], segments: usize) -> TriangleMesh {
+    let segments = segments.max(1);
+    let row = segments + 1;
+
+    let mut positions = Vec::new();
+    let mut normals = Vec::new();
+    let mut uvs = Vec::new();
+    let mut triangles = Vec::new();
+    for points in patches {
+        let offset = positions.len();
+        for i in 0..row {
+            for j in 0..row {
+                let (u, v) = (i as f64 / segments as f64, j as f64 / segments as f64);
+                positions.push(evaluate(points, u, v).0);
+                normals.push(normal(points, u, v));
+                uvs.push((u, v));
+            }
+        }
+
+        for i in 0..segments {
+            for j in 0..segments {
+                let a = offset + i * row + j;
+                let (b, c, d) = (a + row, a + row + 1, a + 1);
+                triangles.push([a, b, c]);
+                triangles.push([a, c, d]);
+            }
+        }
+    }
+
+    TriangleMesh::new(positions, triangles)
+        .with_normals(normals)
+        .with_uvs(uvs)
+}
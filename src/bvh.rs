@@ -0,0 +1,141 @@
+use std::rc::Rc;
+
+use crate::{
+    aabb::{component, Aabb},
+    hittable::{HitRecord, Hittable},
+    ray::Ray,
+    stats,
+};
+
+/// A bounding volume hierarchy node over bounded objects, split at the median centroid along
+/// the longest axis of the node's bounds.
+pub struct BvhNode {
+    left: Rc<dyn Hittable>,
+    right: Rc<dyn Hittable>,
+    bounds: Aabb,
+}
+
+impl BvhNode {
+    /// Builds a hierarchy over objects that all have bounding boxes, returning the object
+    /// itself when there is only one.
+    pub fn build(objects: Vec<Rc<dyn Hittable>>) -> Option<Rc<dyn Hittable>> {
+        let mut objects: Vec<_> = objects
+            .into_iter()
+            .map(|object| {
+                let bounds = object.bounding_box().expect("BVH objects must be bounded");
+                (object, bounds)
+            })
+            .collect();
+
+        if objects.is_empty() {
+            return None;
+        }
+
+        Some(Self::build_node(&mut objects))
+    }
+
+    fn build_node(objects: &mut [(Rc<dyn Hittable>, Aabb)]) -> Rc<dyn Hittable> {
+        if let [(object, _)] = objects {
+            return Rc::clone(object);
+        }
+
+        let bounds = objects[1..]
+            .iter()
+            .fold(objects[0].1, |bounds, (_, object)| {
+                bounds.surrounding(object)
+            });
+        let axis = bounds.longest_axis();
+        // A median split only needs the objects partitioned around the middle, not sorted,
+        // which keeps building over millions of triangles fast.
+        let middle = objects.len() / 2;
+        objects.select_nth_unstable_by(middle, |(_, a), (_, b)| {
+            component(&a.centroid(), axis).total_cmp(&component(&b.centroid(), axis))
+        });
+
+        let (left, right) = objects.split_at_mut(middle);
+        Rc::new(Self {
+            left: Self::build_node(left),
+            right: Self::build_node(right),
+            bounds,
+        })
+    }
+}
+
+impl Hittable for BvhNode {
+    fn hit(&self, ray: &Ray, t_min: f64, t_max: f64) -> Option<HitRecord> {
+        stats::record_bvh_node_visit();
+        if !self.bounds.hit(ray, t_min, t_max) {
+            return None;
+        }
+
+        let left = self.left.hit(ray, t_min, t_max);
+        let closest = left.as_ref().map_or(t_max, HitRecord::get_t);
+        self.right.hit(ray, t_min, closest).or(left)
+    }
+
+    fn bounding_box(&self) -> Option<Aabb> {
+        Some(self.bounds)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        color::Color,
+        constants::{INFINITY, PI},
+        hittable_list::HittableList,
+        material::Lambertian,
+        sphere::Sphere,
+        vec3::{Point3, Vec3},
+    };
+
+    #[test]
+    fn matches_list() {
+        let material = Rc::new(Lambertian::new(Color::new(0.5, 0.5, 0.5)));
+        let objects: Vec<Rc<dyn Hittable>> = (0..125)
+            .map(|i| {
+                let center = Point3::new(
+                    (i % 5) as f64 * 2.0 - 4.0,
+                    (i / 5 % 5) as f64 * 2.0 - 4.0,
+                    (i / 25) as f64 * 2.0 - 4.0,
+                );
+                let radius = 0.3 + (i % 7) as f64 * 0.1;
+                Rc::new(Sphere::new(center, radius, material.clone())) as Rc<dyn Hittable>
+            })
+            .collect();
+
+        let mut list = HittableList::new();
+        for object in &objects {
+            list.add(Rc::clone(object));
+        }
+        let bvh = BvhNode::build(objects).unwrap();
+
+        let origins = [
+            Point3::new(0.0, 0.0, 20.0),
+            Point3::new(-15.0, 7.0, 3.0),
+            Point3::new(0.1, 0.2, 0.3),
+        ];
+        let mut hits = 0;
+        for origin in origins {
+            for i in 0..40 {
+                for j in 0..40 {
+                    let theta = (i as f64 + 0.5) / 40.0 * PI;
+                    let phi = j as f64 / 40.0 * 2.0 * PI;
+                    let direction = Vec3::new(
+                        theta.sin() * phi.cos(),
+                        theta.sin() * phi.sin(),
+                        theta.cos(),
+                    );
+                    let ray = Ray::new(origin, direction);
+
+                    let expected = list.hit(&ray, 0.001, INFINITY).map(|hit| hit.get_t());
+                    let actual = bvh.hit(&ray, 0.001, INFINITY).map(|hit| hit.get_t());
+                    assert_eq!(actual, expected);
+                    hits += usize::from(actual.is_some());
+                }
+            }
+        }
+        assert!(hits > 0 && hits < origins.len() * 40 * 40);
+    }
+}
@@ -0,0 +1,85 @@
+use crate::{
+    ray::Ray,
+    sampler::Sampler,
+    vec3::{Point3, Vec3},
+};
+
+use super::{basis, Camera};
+
+const FACE_COLUMNS: usize = 3;
+const FACE_ROWS: usize = 2;
+
+/// Six 90° faces laid out in a 3x2 grid: right, left, up on the top row and down, front, back
+/// below. For stereo the left eye's grid sits above the right eye's, with the eyes offset
+/// sideways from each viewing direction as in omni-directional stereo.
+pub struct CubeMapCamera {
+    origin: Point3,
+    u: Vec3,
+    v: Vec3,
+    w: Vec3,
+    interocular: Option<f64>,
+}
+
+impl CubeMapCamera {
+    pub fn new(look_from: Point3, look_at: Point3, vup: Vec3, interocular: Option<f64>) -> Self {
+        let (u, v, w) = basis(&look_from, &look_at, &vup);
+
+        Self {
+            origin: look_from,
+            u,
+            v,
+            w,
+            interocular,
+        }
+    }
+
+    /// The forward, right and up directions of a face.
+    fn face(&self, index: usize) -> (Vec3, Vec3, Vec3) {
+        let (u, v, w) = (self.u, self.v, self.w);
+        match index {
+            0 => (u, w, v),
+            1 => (u.negative(), w.negative(), v),
+            2 => (v, u, w),
+            3 => (v.negative(), u, w.negative()),
+            4 => (w.negative(), u, v),
+            _ => (w, u.negative(), v),
+        }
+    }
+}
+
+impl Camera for CubeMapCamera {
+    fn get_ray(&self, u: f64, v: f64, _sampler: &mut dyn Sampler) -> Option<Ray> {
+        let rows = if self.interocular.is_some() {
+            2 * FACE_ROWS
+        } else {
+            FACE_ROWS
+        };
+
+        let x = u * FACE_COLUMNS as f64;
+        let y = (1.0 - v) * rows as f64;
+        let column = (x as usize).min(FACE_COLUMNS - 1);
+        let row = (y as usize).min(rows - 1);
+        let a = 2.0 * (x - column as f64) - 1.0;
+        let b = 1.0 - 2.0 * (y - row as f64);
+
+        let (forward, right, up) = self.face((row % FACE_ROWS) * FACE_COLUMNS + column);
+        let direction = forward
+            .add(&right.multiply_constant(a))
+            .add(&up.multiply_constant(b))
+            .unit_vector();
+
+        let origin = match self.interocular {
+            Some(interocular) => {
+                let side = if row < FACE_ROWS { -0.5 } else { 0.5 };
+                self.origin.add(
+                    &direction
+                        .cross(&self.v)
+                        .multiply_constant(side * interocular),
+                )
+            }
+            None => self.origin,
+        };
+
+        Some(Ray::new(origin, direction))
+    }
+}
@@ -0,0 +1,43 @@
+use crate::{
+    constants::PI,
+    ray::Ray,
+    sampler::Sampler,
+    vec3::{Point3, Vec3},
+};
+
+use super::{basis, Camera};
+
+/// A 360° latitude-longitude panorama centered on the view direction, with `vup` at the top.
+pub struct EquirectangularCamera {
+    origin: Point3,
+    u: Vec3,
+    v: Vec3,
+    w: Vec3,
+}
+
+impl EquirectangularCamera {
+    pub fn new(look_from: Point3, look_at: Point3, vup: Vec3) -> Self {
+        let (u, v, w) = basis(&look_from, &look_at, &vup);
+
+        Self {
+            origin: look_from,
+            u,
+            v,
+            w,
+        }
+    }
+}
+
+impl Camera for EquirectangularCamera {
+    fn get_ray(&self, u: f64, v: f64, _sampler: &mut dyn Sampler) -> Option<Ray> {
+        let (sin_phi, cos_phi) = ((u - 0.5) * 2.0 * PI).sin_cos();
+        let (sin_theta, cos_theta) = ((v - 0.5) * PI).sin_cos();
+        let direction = self
+            .u
+            .multiply_constant(cos_theta * sin_phi)
+            .subtract(&self.w.multiply_constant(cos_theta * cos_phi))
+            .add(&self.v.multiply_constant(sin_theta));
+
+        Some(Ray::new(self.origin, direction))
+    }
+}
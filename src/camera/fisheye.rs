@@ -0,0 +1,57 @@
+use crate::{
+    ray::Ray,
+    sampler::Sampler,
+    utils::degrees_to_radians,
+    vec3::{Point3, Vec3},
+};
+
+use super::{basis, Camera, ASPECT_RATIO};
+
+/// An equidistant fisheye: the angle from the view direction grows linearly with the distance
+/// from the image center. The image circle touches the top and bottom edges.
+pub struct FisheyeCamera {
+    origin: Point3,
+    u: Vec3,
+    v: Vec3,
+    w: Vec3,
+    half_fov: f64,
+}
+
+impl FisheyeCamera {
+    pub fn new(look_from: Point3, look_at: Point3, vup: Vec3, fov: f64) -> Self {
+        let (u, v, w) = basis(&look_from, &look_at, &vup);
+
+        Self {
+            origin: look_from,
+            u,
+            v,
+            w,
+            half_fov: degrees_to_radians(fov) / 2.0,
+        }
+    }
+}
+
+impl Camera for FisheyeCamera {
+    fn get_ray(&self, u: f64, v: f64, _sampler: &mut dyn Sampler) -> Option<Ray> {
+        let x = (2.0 * u - 1.0) * ASPECT_RATIO;
+        let y = 2.0 * v - 1.0;
+        let radius = (x * x + y * y).sqrt();
+        if radius > 1.0 {
+            return None;
+        }
+
+        let (sin, cos) = (radius * self.half_fov).sin_cos();
+        let (x, y) = if radius > 0.0 {
+            (x / radius, y / radius)
+        } else {
+            (0.0, 0.0)
+        };
+        let direction = self
+            .u
+            .multiply_constant(sin * x)
+            .add(&self.v.multiply_constant(sin * y))
+            .subtract(&self.w.multiply_constant(cos));
+
+        Some(Ray::new(self.origin, direction))
+    }
+}
@@ -0,0 +1,376 @@
+use std::rc::Rc;
+
+use crate::{
+    aperture::Aperture,
+    ray::Ray,
+    sampler::Sampler,
+    vec3::{Point3, Vec3},
+};
+pub use cube_map::CubeMapCamera;
+pub use equirectangular::EquirectangularCamera;
+pub use fisheye::FisheyeCamera;
+pub use orthographic::OrthographicCamera;
+pub use perspective::PerspectiveCamera;
+pub use realistic::{LensPrescription, RealisticCamera};
+pub use stereo::StereoCamera;
+
+mod cube_map;
+mod equirectangular;
+mod fisheye;
+mod orthographic;
+mod perspective;
+mod realistic;
+mod stereo;
+
+pub const ASPECT_RATIO: f64 = 3.0 / 2.0;
+
+const MILLIMETERS_PER_UNIT: f64 = 1000.0;
+
+/// Photographic lens parameters in millimeters, for scenes modelled in meters.
+pub struct Lens {
+    focal_length: f64,
+    f_number: f64,
+    sensor_width: f64,
+}
+
+impl Lens {
+    pub const fn new(focal_length: f64, f_number: f64, sensor_width: f64) -> Self {
+        Self {
+            focal_length,
+            f_number,
+            sensor_width,
+        }
+    }
+
+    /// Vertical field of view in degrees, with the sensor width spanning the image width.
+    pub fn vfov(&self) -> f64 {
+        let half_width = self.sensor_width / (2.0 * self.focal_length);
+        2.0 * (half_width / ASPECT_RATIO).atan().to_degrees()
+    }
+
+    /// Diameter of the entrance pupil in scene units.
+    pub fn aperture(&self) -> f64 {
+        self.focal_length / self.f_number / MILLIMETERS_PER_UNIT
+    }
+}
+
+#[derive(Clone)]
+pub enum Projection {
+    Perspective,
+    Orthographic,
+    Fisheye {
+        fov: f64,
+    },
+    Equirectangular,
+    CubeMap {
+        interocular: Option<f64>,
+    },
+    Realistic {
+        prescription: Rc<LensPrescription>,
+        sensor_width: f64,
+    },
+    Stereo {
+        views: usize,
+        interocular: f64,
+        convergence: Option<f64>,
+    },
+}
+
+impl Projection {
+    /// `convergence` defaults to the focus distance of the view.
+    pub fn from_name(
+        name: &str,
+        fisheye_fov: f64,
+        interocular: f64,
+        convergence: Option<f64>,
+        views: usize,
+    ) -> Option<Self> {
+        let projection = match name {
+            "perspective" => Self::Perspective,
+            "orthographic" => Self::Orthographic,
+            "fisheye" => Self::Fisheye { fov: fisheye_fov },
+            "equirectangular" => Self::Equirectangular,
+            "cubemap" => Self::CubeMap { interocular: None },
+            "stereo-cubemap" => Self::CubeMap {
+                interocular: Some(interocular),
+            },
+            "stereo" => Self::Stereo {
+                views: views.max(1),
+                interocular,
+                convergence,
+            },
+            _ => return None,
+        };
+
+        Some(projection)
+    }
+
+    /// Width over height of a single view, chosen so panoramas and cube faces are not stretched.
+    pub const fn aspect_ratio(&self) -> f64 {
+        match self {
+            Self::Perspective
+            | Self::Orthographic
+            | Self::Fisheye { .. }
+            | Self::Realistic { .. }
+            | Self::Stereo { .. } => ASPECT_RATIO,
+            Self::Equirectangular => 2.0,
+            Self::CubeMap { interocular: None } => 1.5,
+            Self::CubeMap {
+                interocular: Some(_),
+            } => 0.75,
+        }
+    }
+
+    /// Number of views placed side by side in the image.
+    pub const fn views(&self) -> usize {
+        match self {
+            Self::Stereo { views, .. } => *views,
+            _ => 1,
+        }
+    }
+}
+
+/// The parameters a `Camera` is built from, kept so the view can be changed and rebuilt.
+#[derive(Clone)]
+pub struct CameraView {
+    look_from: Point3,
+    look_at: Point3,
+    vup: Vec3,
+    vfov: f64,
+    aperture: f64,
+    focus_dist: f64,
+    aperture_shape: Aperture,
+    cat_eye: f64,
+    projection: Projection,
+}
+
+impl CameraView {
+    pub const fn new(
+        look_from: Point3,
+        look_at: Point3,
+        vup: Vec3,
+        vfov: f64,
+        aperture: f64,
+        focus_dist: f64,
+    ) -> Self {
+        Self {
+            look_from,
+            look_at,
+            vup,
+            vfov,
+            aperture,
+            focus_dist,
+            aperture_shape: Aperture::Circular,
+            cat_eye: 0.0,
+            projection: Projection::Perspective,
+        }
+    }
+
+    pub fn with_lens(self, lens: &Lens) -> Self {
+        Self {
+            vfov: lens.vfov(),
+            aperture: lens.aperture(),
+            ..self
+        }
+    }
+
+    pub fn with_aperture_shape(self, aperture_shape: Aperture) -> Self {
+        Self {
+            aperture_shape,
+            ..self
+        }
+    }
+
+    /// Clips the aperture by the lens barrel off axis; 0 disables it, 1 fully closes the corners.
+    pub fn with_cat_eye(self, cat_eye: f64) -> Self {
+        Self { cat_eye, ..self }
+    }
+
+    pub fn with_projection(self, projection: Projection) -> Self {
+        Self { projection, ..self }
+    }
+
+    pub const fn aspect_ratio(&self) -> f64 {
+        self.projection.aspect_ratio()
+    }
+
+    pub const fn views(&self) -> usize {
+        self.projection.views()
+    }
+
+    /// Only the perspective and realistic projections model a lens; the others are pinholes.
+    pub fn build(&self) -> Box<dyn Camera> {
+        match &self.projection {
+            Projection::Perspective => Box::new(
+                PerspectiveCamera::new(
+                    self.look_from,
+                    self.look_at,
+                    self.vup,
+                    self.vfov,
+                    self.aperture,
+                    self.focus_dist,
+                )
+                .with_aperture_shape(self.aperture_shape.clone(), self.cat_eye),
+            ),
+            Projection::Orthographic => Box::new(OrthographicCamera::new(
+                self.look_from,
+                self.look_at,
+                self.vup,
+                self.vfov,
+                self.focus_dist,
+            )),
+            Projection::Fisheye { fov } => Box::new(FisheyeCamera::new(
+                self.look_from,
+                self.look_at,
+                self.vup,
+                *fov,
+            )),
+            Projection::Equirectangular => Box::new(EquirectangularCamera::new(
+                self.look_from,
+                self.look_at,
+                self.vup,
+            )),
+            Projection::CubeMap { interocular } => Box::new(CubeMapCamera::new(
+                self.look_from,
+                self.look_at,
+                self.vup,
+                *interocular,
+            )),
+            Projection::Realistic {
+                prescription,
+                sensor_width,
+            } => Box::new(RealisticCamera::new(
+                self.look_from,
+                self.look_at,
+                self.vup,
+                prescription.clone(),
+                *sensor_width,
+                self.focus_dist,
+            )),
+            Projection::Stereo {
+                views,
+                interocular,
+                convergence,
+            } => Box::new(StereoCamera::new(
+                self.look_from,
+                self.look_at,
+                self.vup,
+                self.vfov,
+                *views,
+                *interocular,
+                convergence.unwrap_or(self.focus_dist),
+            )),
+        }
+    }
+}
+
+#[cfg(feature = "preview")]
+impl CameraView {
+    /// Rotates the eye around `look_at`, by `yaw` around `vup` and `pitch` around the right axis.
+    pub fn orbit(&self, yaw: f64, pitch: f64) -> Self {
+        let up = self.vup.unit_vector();
+        let offset = self.look_from.subtract(&self.look_at);
+        let offset = rotate(&offset, &up, yaw);
+
+        let right = up.cross(&offset).unit_vector();
+        let pitched = rotate(&offset, &right, pitch);
+        let offset = if pitched.unit_vector().dot(&up).abs() < 0.99 {
+            pitched
+        } else {
+            offset
+        };
+
+        Self {
+            look_from: self.look_at.add(&offset),
+            ..self.clone()
+        }
+    }
+
+    /// Moves eye and target together, by fractions of the distance between them.
+    pub fn pan(&self, dx: f64, dy: f64) -> Self {
+        let offset = self.look_from.subtract(&self.look_at);
+        let distance = offset.length();
+        let w = offset.unit_vector();
+        let u = self.vup.cross(&w).unit_vector();
+        let v = w.cross(&u);
+        let shift = u
+            .multiply_constant(dx * distance)
+            .add(&v.multiply_constant(dy * distance));
+
+        Self {
+            look_from: self.look_from.add(&shift),
+            look_at: self.look_at.add(&shift),
+            ..self.clone()
+        }
+    }
+
+    /// Scales the distance from the eye to `look_at`, keeping the focus plane on the target.
+    pub fn zoom(&self, factor: f64) -> Self {
+        let offset = self.look_from.subtract(&self.look_at);
+
+        Self {
+            look_from: self.look_at.add(&offset.multiply_constant(factor)),
+            focus_dist: self.focus_dist * factor,
+            ..self.clone()
+        }
+    }
+}
+
+#[cfg(feature = "preview")]
+fn rotate(vector: &Vec3, axis: &Vec3, angle: f64) -> Vec3 {
+    let (sin, cos) = angle.sin_cos();
+    vector
+        .multiply_constant(cos)
+        .add(&axis.cross(vector).multiply_constant(sin))
+        .add(&axis.multiply_constant(axis.dot(vector) * (1.0 - cos)))
+}
+
+/// A point on the lens joined to a point in the scene, and where that ray lands on the image.
+pub struct LensSample {
+    point: Point3,
+    uv: (f64, f64),
+    density: f64,
+}
+
+impl LensSample {
+    pub const fn get_point(&self) -> Point3 {
+        self.point
+    }
+
+    pub const fn get_uv(&self) -> (f64, f64) {
+        self.uv
+    }
+
+    /// Image area per unit solid angle around the ray, as for `Camera::image_density`.
+    pub const fn get_density(&self) -> f64 {
+        self.density
+    }
+}
+
+pub trait Camera {
+    /// Returns `None` when no ray leaves the camera through this image point, such as outside a
+    /// fisheye's image circle or when the lens sample is blocked by cat's-eye vignetting.
+    fn get_ray(&self, u: f64, v: f64, sampler: &mut dyn Sampler) -> Option<Ray>;
+
+    /// Samples the lens as `get_ray` does and follows the ray from there to `point` back onto
+    /// the image, for paths traced from the lights. `None` if the ray misses the lens or the
+    /// camera does not support this; the image point may lie outside the image.
+    fn sample_lens(&self, _point: &Point3, _sampler: &mut dyn Sampler) -> Option<LensSample> {
+        None
+    }
+
+    /// Image area, in `uv` coordinates, per unit solid angle around a ray leaving the lens in
+    /// `direction`, or 0 for cameras without `sample_lens`.
+    fn image_density(&self, _direction: &Vec3) -> f64 {
+        0.0
+    }
+}
+
+/// The camera's orthonormal basis: right, up and backwards from the view direction.
+fn basis(look_from: &Point3, look_at: &Point3, vup: &Vec3) -> (Vec3, Vec3, Vec3) {
+    let w = look_from.subtract(look_at).unit_vector();
+    let u = vup.cross(&w).unit_vector();
+    let v = w.cross(&u);
+
+    (u, v, w)
+}
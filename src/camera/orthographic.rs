@@ -0,0 +1,49 @@
+use crate::{
+    ray::Ray,
+    sampler::Sampler,
+    utils::degrees_to_radians,
+    vec3::{Point3, Vec3},
+};
+
+use super::{basis, Camera, ASPECT_RATIO};
+
+/// Parallel rays along the view direction. The view covers what a perspective camera with the
+/// same field of view sees at the focus distance.
+pub struct OrthographicCamera {
+    lower_left_corner: Point3,
+    horizontal: Vec3,
+    vertical: Vec3,
+    direction: Vec3,
+}
+
+impl OrthographicCamera {
+    pub fn new(look_from: Point3, look_at: Point3, vup: Vec3, vfov: f64, focus_dist: f64) -> Self {
+        let viewport_height = 2.0 * (degrees_to_radians(vfov) / 2.0).tan() * focus_dist;
+        let viewport_width = ASPECT_RATIO * viewport_height;
+
+        let (u, v, w) = basis(&look_from, &look_at, &vup);
+        let horizontal = u.multiply_constant(viewport_width);
+        let vertical = v.multiply_constant(viewport_height);
+        let lower_left_corner = look_from
+            .subtract(&horizontal.divide_constant(2.0))
+            .subtract(&vertical.divide_constant(2.0));
+
+        Self {
+            lower_left_corner,
+            horizontal,
+            vertical,
+            direction: w.negative(),
+        }
+    }
+}
+
+impl Camera for OrthographicCamera {
+    fn get_ray(&self, u: f64, v: f64, _sampler: &mut dyn Sampler) -> Option<Ray> {
+        Some(Ray::new(
+            self.lower_left_corner
+                .add(&self.horizontal.multiply_constant(u))
+                .add(&self.vertical.multiply_constant(v)),
+            self.direction,
+        ))
+    }
+}
@@ -0,0 +1,157 @@
+use crate::{
+    aperture::Aperture,
+    ray::Ray,
+    sampler::Sampler,
+    utils::degrees_to_radians,
+    vec3::{Point3, Vec3},
+};
+
+use super::{basis, Camera, LensSample, ASPECT_RATIO};
+
+/// A thin lens camera; with a zero aperture it is a pinhole.
+pub struct PerspectiveCamera {
+    origin: Point3,
+    horizontal: Vec3,
+    vertical: Vec3,
+    lower_left_corner: Point3,
+    u: Vec3,
+    v: Vec3,
+    w: Vec3,
+    lens_radius: f64,
+    aperture_shape: Aperture,
+    cat_eye: f64,
+}
+
+impl PerspectiveCamera {
+    pub fn new(
+        look_from: Point3,
+        look_at: Point3,
+        vup: Vec3,
+        vfow: f64,
+        aperture: f64,
+        focus_dist: f64,
+    ) -> Self {
+        let theta = degrees_to_radians(vfow);
+        let h = (theta / 2.0).tan();
+
+        let viewport_height = 2.0 * h;
+        let viewport_width = ASPECT_RATIO * viewport_height;
+
+        let (u, v, w) = basis(&look_from, &look_at, &vup);
+
+        let origin = look_from;
+        let horizontal = u
+            .multiply_constant(viewport_width)
+            .multiply_constant(focus_dist);
+        let vertical = v
+            .multiply_constant(viewport_height)
+            .multiply_constant(focus_dist);
+        let lower_left_corner = origin
+            .subtract(&horizontal.divide_constant(2.0))
+            .subtract(&vertical.divide_constant(2.0))
+            .subtract(&w.multiply_constant(focus_dist));
+        let lens_radius = aperture / 2.0;
+
+        Self {
+            origin,
+            horizontal,
+            vertical,
+            lower_left_corner,
+            u,
+            v,
+            w,
+            lens_radius,
+            aperture_shape: Aperture::Circular,
+            cat_eye: 0.0,
+        }
+    }
+
+    pub fn with_aperture_shape(self, aperture_shape: Aperture, cat_eye: f64) -> Self {
+        Self {
+            aperture_shape,
+            cat_eye,
+            ..self
+        }
+    }
+
+    /// The point on the lens for an aperture sample.
+    fn lens_point(&self, (x, y): (f64, f64)) -> Point3 {
+        self.origin.add(
+            &self
+                .u
+                .multiply_constant(x * self.lens_radius)
+                .add(&self.v.multiply_constant(y * self.lens_radius)),
+        )
+    }
+
+    /// Whether cat's-eye vignetting blocks the aperture sample for this image point.
+    fn is_vignetted(&self, (x, y): (f64, f64), u: f64, v: f64) -> bool {
+        if self.cat_eye <= 0.0 {
+            return false;
+        }
+
+        let dx = x - self.cat_eye * (2.0 * u - 1.0);
+        let dy = y - self.cat_eye * (2.0 * v - 1.0);
+        dx * dx + dy * dy > 1.0
+    }
+
+    fn focus_dist(&self) -> f64 {
+        self.origin.subtract(&self.lower_left_corner).dot(&self.w)
+    }
+}
+
+impl Camera for PerspectiveCamera {
+    fn get_ray(&self, u: f64, v: f64, sampler: &mut dyn Sampler) -> Option<Ray> {
+        let aperture = self.aperture_shape.sample(sampler.get_2d());
+        if self.is_vignetted(aperture, u, v) {
+            return None;
+        }
+
+        let lens = self.lens_point(aperture);
+        Some(Ray::new(
+            lens,
+            self.lower_left_corner
+                .add(&self.horizontal.multiply_constant(u))
+                .add(&self.vertical.multiply_constant(v))
+                .subtract(&lens),
+        ))
+    }
+
+    fn sample_lens(&self, point: &Point3, sampler: &mut dyn Sampler) -> Option<LensSample> {
+        let aperture = self.aperture_shape.sample(sampler.get_2d());
+        let lens = self.lens_point(aperture);
+        let direction = point.subtract(&lens);
+        let depth = -direction.dot(&self.w);
+        if depth <= 0.0 {
+            return None;
+        }
+
+        // Where the ray crosses the plane of focus, which holds the image.
+        let on_image = lens
+            .add(&direction.multiply_constant(self.focus_dist() / depth))
+            .subtract(&self.lower_left_corner);
+        let u = on_image.dot(&self.horizontal) / self.horizontal.square();
+        let v = on_image.dot(&self.vertical) / self.vertical.square();
+        if self.is_vignetted(aperture, u, v) {
+            return None;
+        }
+
+        Some(LensSample {
+            point: lens,
+            uv: (u, v),
+            density: self.image_density(&direction),
+        })
+    }
+
+    /// The image lies on the plane of focus, where `uv` area grows with the distance squared
+    /// and shrinks with the cosine cubed away from the view direction.
+    fn image_density(&self, direction: &Vec3) -> f64 {
+        let cos = -direction.unit_vector().dot(&self.w);
+        if cos <= 0.0 {
+            return 0.0;
+        }
+
+        let focus_dist = self.focus_dist();
+        focus_dist * focus_dist / (self.horizontal.length() * self.vertical.length() * cos.powi(3))
+    }
+}
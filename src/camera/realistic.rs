@@ -0,0 +1,358 @@
+use std::{fs, rc::Rc};
+
+use crate::{
+    ray::Ray,
+    sampler::Sampler,
+    utils::{refract, sample_unit_disk},
+    vec3::{Point3, Vec3},
+};
+
+use super::{basis, Camera, ASPECT_RATIO, MILLIMETERS_PER_UNIT};
+
+/// One refracting surface, or the aperture stop when the radius is zero. `ior` is the medium
+/// between this surface and the next one towards the film, and `thickness` the distance to it.
+struct LensSurface {
+    radius: f64,
+    thickness: f64,
+    ior: f64,
+    aperture_radius: f64,
+}
+
+/// Lens surfaces in scene units, ordered from the front element to the rear element.
+pub struct LensPrescription {
+    surfaces: Vec<LensSurface>,
+}
+
+impl LensPrescription {
+    /// Reads one surface per line as `radius thickness ior aperture` in millimeters, with the
+    /// aperture given as a diameter. An ior of 0 means air, a radius of 0 marks the stop and
+    /// everything after a `#` is ignored.
+    pub fn load(path: &str) -> Result<Self, String> {
+        let contents = fs::read_to_string(path)
+            .map_err(|error| format!("failed to read {}: {}", path, error))?;
+        Self::parse(path, &contents)
+    }
+
+    /// Parses the contents of a prescription file, naming it `path` in errors.
+    fn parse(path: &str, contents: &str) -> Result<Self, String> {
+        let mut surfaces = Vec::new();
+        for (number, line) in contents.lines().enumerate() {
+            let line = line.split('#').next().unwrap_or_default().trim();
+            if line.is_empty() {
+                continue;
+            }
+
+            let values = line
+                .split_whitespace()
+                .map(str::parse::<f64>)
+                .collect::<Result<Vec<_>, _>>()
+                .map_err(|error| format!("{}:{}: {}", path, number + 1, error))?;
+            let [radius, thickness, ior, aperture] = values[..] else {
+                return Err(format!(
+                    "{}:{}: expected radius, thickness, ior and aperture",
+                    path,
+                    number + 1
+                ));
+            };
+            surfaces.push(LensSurface {
+                radius: radius / MILLIMETERS_PER_UNIT,
+                thickness: thickness / MILLIMETERS_PER_UNIT,
+                ior: if ior == 0.0 { 1.0 } else { ior },
+                aperture_radius: aperture / 2.0 / MILLIMETERS_PER_UNIT,
+            });
+        }
+
+        if surfaces.is_empty() {
+            return Err(format!("{} contains no lens surfaces", path));
+        }
+
+        Ok(Self { surfaces })
+    }
+
+    fn length(&self) -> f64 {
+        self.surfaces[..self.surfaces.len() - 1]
+            .iter()
+            .map(|surface| surface.thickness)
+            .sum()
+    }
+
+    /// Traces a ray in lens space, where the optical axis is +z towards the scene and the rear
+    /// vertex sits at `rear_z`. Returns `None` when the ray is blocked or totally reflected.
+    fn trace(&self, rear_z: f64, ray: &Ray, towards_scene: bool) -> Option<Ray> {
+        let count = self.surfaces.len();
+        let mut ray = *ray;
+        let mut z = if towards_scene {
+            rear_z
+        } else {
+            rear_z + self.length()
+        };
+
+        for step in 0..count {
+            let index = if towards_scene {
+                count - 1 - step
+            } else {
+                step
+            };
+            let surface = &self.surfaces[index];
+            let scene_ior = if index == 0 {
+                1.0
+            } else {
+                self.surfaces[index - 1].ior
+            };
+
+            let (t, normal) = intersect(z, surface.radius, &ray)?;
+            let point = ray.at(t);
+            if point.get_x() * point.get_x() + point.get_y() * point.get_y()
+                > surface.aperture_radius * surface.aperture_radius
+            {
+                return None;
+            }
+
+            let direction = if surface.radius == 0.0 {
+                ray.get_direction()
+            } else {
+                let ratio = if towards_scene {
+                    surface.ior / scene_ior
+                } else {
+                    scene_ior / surface.ior
+                };
+                let unit_direction = ray.get_direction().unit_vector();
+                let cos = normal.dot(&unit_direction.negative()).min(1.0);
+                if ratio * ratio * (1.0 - cos * cos) > 1.0 {
+                    return None;
+                }
+                refract(&unit_direction, &normal, ratio)
+            };
+            ray = Ray::new(point, direction);
+
+            if towards_scene {
+                z += index
+                    .checked_sub(1)
+                    .map_or(0.0, |i| self.surfaces[i].thickness);
+            } else {
+                z -= surface.thickness;
+            }
+        }
+
+        Some(ray)
+    }
+
+    /// Traces a paraxial ray parallel to the axis and returns where the exiting ray crosses the
+    /// axis (the focal point) and where it reaches the entry height (the principal plane).
+    fn cardinal_points(&self, height: f64, towards_scene: bool) -> Option<(f64, f64)> {
+        let (z, direction) = if towards_scene {
+            (-1.0, 1.0)
+        } else {
+            (self.length() + 1.0, -1.0)
+        };
+        let ray = Ray::new(Point3::new(height, 0.0, z), Vec3::new(0.0, 0.0, direction));
+        let out = self.trace(0.0, &ray, towards_scene)?;
+
+        let origin = out.get_origin();
+        let direction = out.get_direction();
+        if direction.get_x() == 0.0 {
+            return None;
+        }
+        let focal = out.at(-origin.get_x() / direction.get_x()).get_z();
+        let principal = out
+            .at((height - origin.get_x()) / direction.get_x())
+            .get_z();
+
+        Some((focal, principal))
+    }
+
+    /// Position of the rear vertex that focuses at `focus_dist` from the film, using the thick
+    /// lens approximation. Falls back to focusing at infinity when the distance is too close.
+    fn focus(&self, focus_dist: f64, height: f64) -> Option<f64> {
+        let (rear_focal, rear_principal) = self.cardinal_points(height, false)?;
+        let (_, front_principal) = self.cardinal_points(height, true)?;
+        let focal_length = rear_principal - rear_focal;
+
+        let k = focus_dist - front_principal + rear_principal;
+        let discriminant = k * (k - 4.0 * focal_length);
+        let image_distance = if discriminant >= 0.0 {
+            (k - discriminant.sqrt()) / 2.0
+        } else {
+            focal_length
+        };
+
+        Some(image_distance - rear_principal)
+    }
+}
+
+/// Intersects the surface whose vertex is at `z`, returning the distance along the ray and the
+/// normal facing against it.
+fn intersect(z: f64, radius: f64, ray: &Ray) -> Option<(f64, Vec3)> {
+    let origin = ray.get_origin();
+    let direction = ray.get_direction();
+
+    if radius == 0.0 {
+        let t = (z - origin.get_z()) / direction.get_z();
+        let normal = Vec3::new(0.0, 0.0, -direction.get_z().signum());
+        return (t > 0.0).then_some((t, normal));
+    }
+
+    let center = Point3::new(0.0, 0.0, z - radius);
+    let oc = origin.subtract(&center);
+    let a = direction.square();
+    let half_b = oc.dot(&direction);
+    let c = oc.square() - radius * radius;
+    let discriminant = half_b * half_b - a * c;
+    if discriminant < 0.0 {
+        return None;
+    }
+
+    let sqrt = discriminant.sqrt();
+    let closer = (direction.get_z() > 0.0) == (radius < 0.0);
+    let t = if closer {
+        (-half_b - sqrt) / a
+    } else {
+        (-half_b + sqrt) / a
+    };
+    if t <= 0.0 {
+        return None;
+    }
+
+    let normal = ray.at(t).subtract(&center).divide_constant(radius.abs());
+    let normal = if normal.dot(&direction) > 0.0 {
+        normal.negative()
+    } else {
+        normal
+    };
+
+    Some((t, normal))
+}
+
+/// Traces camera rays from the film through every element of a lens prescription, so
+/// distortion, vignetting and focus breathing come from the lens itself. The film sits at
+/// `look_from` and the lens is moved along its axis to focus.
+pub struct RealisticCamera {
+    origin: Point3,
+    u: Vec3,
+    v: Vec3,
+    w: Vec3,
+    prescription: Rc<LensPrescription>,
+    rear_z: f64,
+    film_width: f64,
+    film_height: f64,
+}
+
+impl RealisticCamera {
+    pub fn new(
+        look_from: Point3,
+        look_at: Point3,
+        vup: Vec3,
+        prescription: Rc<LensPrescription>,
+        sensor_width: f64,
+        focus_dist: f64,
+    ) -> Self {
+        let (u, v, w) = basis(&look_from, &look_at, &vup);
+        let film_width = sensor_width / MILLIMETERS_PER_UNIT;
+        let film_height = film_width / ASPECT_RATIO;
+        let paraxial_height = 0.001 * film_width.hypot(film_height);
+        let rear_z = prescription
+            .focus(focus_dist, paraxial_height)
+            .unwrap_or_else(|| prescription.surfaces[prescription.surfaces.len() - 1].thickness);
+
+        Self {
+            origin: look_from,
+            u,
+            v,
+            w,
+            prescription,
+            rear_z,
+            film_width,
+            film_height,
+        }
+    }
+
+    fn to_world(&self, vector: &Vec3) -> Vec3 {
+        self.u
+            .multiply_constant(vector.get_x())
+            .add(&self.v.multiply_constant(vector.get_y()))
+            .subtract(&self.w.multiply_constant(vector.get_z()))
+    }
+}
+
+impl Camera for RealisticCamera {
+    /// Samples the rear element uniformly. The cos⁴ falloff towards the film edges is applied by
+    /// discarding samples, like rays that are blocked inside the lens.
+    fn get_ray(&self, u: f64, v: f64, sampler: &mut dyn Sampler) -> Option<Ray> {
+        let film_point = Point3::new(
+            -(u - 0.5) * self.film_width,
+            -(v - 0.5) * self.film_height,
+            0.0,
+        );
+
+        let rear = &self.prescription.surfaces[self.prescription.surfaces.len() - 1];
+        let disk = sample_unit_disk(sampler.get_2d()).multiply_constant(rear.aperture_radius);
+        let rear_point = Point3::new(disk.get_x(), disk.get_y(), self.rear_z);
+        let direction = rear_point.subtract(&film_point).unit_vector();
+        if sampler.get_1d() > direction.get_z().powi(4) {
+            return None;
+        }
+
+        let ray = self
+            .prescription
+            .trace(self.rear_z, &Ray::new(film_point, direction), true)?;
+
+        Some(Ray::new(
+            self.origin.add(&self.to_world(&ray.get_origin())),
+            self.to_world(&ray.get_direction()),
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SINGLET: &str = "# radius thickness ior aperture
+50.0   5.0  1.5  20  # front
+-50.0  2.0  0    20
+
+0      45   0    10  # stop
+";
+
+    fn assert_near(actual: f64, expected: f64) {
+        assert!(
+            (actual - expected).abs() < 1e-12,
+            "{} != {}",
+            actual,
+            expected
+        );
+    }
+
+    #[test]
+    fn singlet() {
+        let lens = LensPrescription::parse("singlet", SINGLET).unwrap();
+        let [front, rear, stop] = &lens.surfaces[..] else {
+            panic!("expected three surfaces");
+        };
+
+        assert_near(front.radius, 0.05);
+        assert_near(front.thickness, 0.005);
+        assert_near(front.ior, 1.5);
+        assert_near(front.aperture_radius, 0.01);
+        assert_near(rear.radius, -0.05);
+        assert_near(rear.ior, 1.0);
+        assert_near(stop.radius, 0.0);
+        assert_near(stop.aperture_radius, 0.005);
+        assert_near(lens.length(), 0.007);
+    }
+
+    #[test]
+    fn errors() {
+        let error = |contents| LensPrescription::parse("lens", contents).err().unwrap();
+        assert!(error("50 5 1.5 20\n50 5 x 20\n").starts_with("lens:2: "));
+        assert_eq!(
+            error("\n50 5 1.5\n"),
+            "lens:2: expected radius, thickness, ior and aperture"
+        );
+        assert_eq!(error("# empty\n"), "lens contains no lens surfaces");
+        assert!(LensPrescription::load("missing.lens")
+            .err()
+            .unwrap()
+            .starts_with("failed to read missing.lens"));
+    }
+}
@@ -0,0 +1,69 @@
+use crate::{
+    ray::Ray,
+    sampler::Sampler,
+    utils::degrees_to_radians,
+    vec3::{Point3, Vec3},
+};
+
+use super::{basis, Camera, ASPECT_RATIO};
+
+/// Several pinhole eyes spaced along the camera's `u` axis, rendered side by side left to
+/// right. The eyes share one image rectangle at the convergence distance, so objects there
+/// have zero parallax while the view axes stay parallel.
+pub struct StereoCamera {
+    eyes: Vec<Point3>,
+    lower_left_corner: Point3,
+    horizontal: Vec3,
+    vertical: Vec3,
+}
+
+impl StereoCamera {
+    pub fn new(
+        look_from: Point3,
+        look_at: Point3,
+        vup: Vec3,
+        vfov: f64,
+        views: usize,
+        interocular: f64,
+        convergence: f64,
+    ) -> Self {
+        let viewport_height = 2.0 * (degrees_to_radians(vfov) / 2.0).tan() * convergence;
+        let viewport_width = ASPECT_RATIO * viewport_height;
+
+        let (u, v, w) = basis(&look_from, &look_at, &vup);
+        let horizontal = u.multiply_constant(viewport_width);
+        let vertical = v.multiply_constant(viewport_height);
+        let lower_left_corner = look_from
+            .subtract(&horizontal.divide_constant(2.0))
+            .subtract(&vertical.divide_constant(2.0))
+            .subtract(&w.multiply_constant(convergence));
+
+        let center = (views as f64 - 1.0) / 2.0;
+        let eyes = (0..views)
+            .map(|view| look_from.add(&u.multiply_constant((view as f64 - center) * interocular)))
+            .collect();
+
+        Self {
+            eyes,
+            lower_left_corner,
+            horizontal,
+            vertical,
+        }
+    }
+}
+
+impl Camera for StereoCamera {
+    fn get_ray(&self, u: f64, v: f64, _sampler: &mut dyn Sampler) -> Option<Ray> {
+        let x = u * self.eyes.len() as f64;
+        let view = (x as usize).min(self.eyes.len() - 1);
+        let eye = self.eyes[view];
+
+        Some(Ray::new(
+            eye,
+            self.lower_left_corner
+                .add(&self.horizontal.multiply_constant(x - view as f64))
+                .add(&self.vertical.multiply_constant(v))
+                .subtract(&eye),
+        ))
+    }
+}
@@ -1,5 +1,6 @@
-use crate::vec3::Vec3;
-use std::fmt::Display;
+use std::io::{self, Read, Write};
+
+use crate::{constants::INFINITY, vec3::Vec3};
 
 pub type Color = Vec3;
 
@@ -7,37 +8,122 @@ pub const WHITE: Color = Color::new(1.0, 1.0, 1.0);
 pub const BLUE: Color = Color::new(0.5, 0.7, 1.0);
 pub const BLACK: Color = Color::new(0.0, 0.0, 0.0);
 
+const RELATIVE_ERROR_EPS: f64 = 1e-3;
+
 pub struct MultipleSamplesColor {
     color_sum: Color,
+    weight_sum: f64,
     samples_count: usize,
+    luminance_mean: f64,
+    luminance_m2: f64,
 }
 
 impl MultipleSamplesColor {
     pub const fn new() -> Self {
         Self {
             color_sum: BLACK,
+            weight_sum: 0.0,
             samples_count: 0,
+            luminance_mean: 0.0,
+            luminance_m2: 0.0,
         }
     }
 
     pub fn add(&mut self, color: &Color) {
-        self.color_sum = self.color_sum.add(color);
+        self.splat(color, 1.0);
+        self.record(color);
+    }
+
+    pub fn splat(&mut self, color: &Color, weight: f64) {
+        self.color_sum = self.color_sum.add(&color.multiply_constant(weight));
+        self.weight_sum += weight;
+    }
+
+    pub fn record(&mut self, color: &Color) {
         self.samples_count += 1;
+
+        let value = luminance(color);
+        let delta = value - self.luminance_mean;
+        self.luminance_mean += delta / self.samples_count as f64;
+        self.luminance_m2 += delta * (value - self.luminance_mean);
+    }
+
+    pub fn average(&self) -> Color {
+        if self.weight_sum <= 0.0 {
+            return BLACK;
+        }
+
+        self.color_sum.divide_constant(self.weight_sum)
+    }
+
+    pub const fn get_samples_count(&self) -> usize {
+        self.samples_count
+    }
+
+    pub fn variance(&self) -> f64 {
+        if self.samples_count < 2 {
+            return INFINITY;
+        }
+
+        self.luminance_m2 / (self.samples_count - 1) as f64
+    }
+
+    pub fn write_to(&self, writer: &mut impl Write) -> io::Result<()> {
+        for value in [
+            self.color_sum.get_x(),
+            self.color_sum.get_y(),
+            self.color_sum.get_z(),
+            self.weight_sum,
+            self.luminance_mean,
+            self.luminance_m2,
+        ] {
+            writer.write_all(&value.to_le_bytes())?;
+        }
+        writer.write_all(&(self.samples_count as u64).to_le_bytes())
+    }
+
+    pub fn read_from(reader: &mut impl Read) -> io::Result<Self> {
+        let mut buffer = [0; 8];
+        let mut next = || -> io::Result<[u8; 8]> {
+            reader.read_exact(&mut buffer)?;
+            Ok(buffer)
+        };
+        let mut values = [0.0; 6];
+        for value in &mut values {
+            *value = f64::from_le_bytes(next()?);
+        }
+        let samples_count = u64::from_le_bytes(next()?) as usize;
+
+        Ok(Self {
+            color_sum: Color::new(values[0], values[1], values[2]),
+            weight_sum: values[3],
+            samples_count,
+            luminance_mean: values[4],
+            luminance_m2: values[5],
+        })
+    }
+
+    pub fn relative_error(&self) -> f64 {
+        let standard_error = (self.variance() / self.samples_count as f64).sqrt();
+        standard_error / (self.luminance_mean + RELATIVE_ERROR_EPS)
     }
 }
 
+pub fn luminance(color: &Color) -> f64 {
+    0.2126 * color.get_x() + 0.7152 * color.get_y() + 0.0722 * color.get_z()
+}
+
 fn color_to_i(color: f64) -> usize {
     let transformed = color.clamp(0.0, 0.999) * 256.0;
     transformed as usize
 }
 
-impl Display for MultipleSamplesColor {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        let scale = 1.0 / self.samples_count as f64;
-        let r = (self.color_sum.get_x() * scale).sqrt();
-        let g = (self.color_sum.get_y() * scale).sqrt();
-        let b = (self.color_sum.get_z() * scale).sqrt();
-
-        write!(f, "{} {} {}", color_to_i(r), color_to_i(g), color_to_i(b))
-    }
+/// Formats a display-encoded color, already in [0, 1], as a PPM triplet.
+pub fn format_color(color: &Color) -> String {
+    format!(
+        "{} {} {}",
+        color_to_i(color.get_x()),
+        color_to_i(color.get_y()),
+        color_to_i(color.get_z())
+    )
 }
@@ -0,0 +1,117 @@
+use crate::{color::Color, lut::Lut, matrix::Matrix3, tonemap::ToneMapper, vec3::Vec3};
+
+const SRGB_TO_XYZ: Matrix3 = Matrix3::new([
+    [0.4124564, 0.3575761, 0.1804375],
+    [0.2126729, 0.7151522, 0.0721750],
+    [0.0193339, 0.1191920, 0.9503041],
+]);
+pub const XYZ_TO_SRGB: Matrix3 = Matrix3::new([
+    [3.2404542, -1.5371385, -0.4985314],
+    [-0.9692660, 1.8760108, 0.0415560],
+    [0.0556434, -0.2040259, 1.0572252],
+]);
+const BRADFORD: Matrix3 = Matrix3::new([
+    [0.8951, 0.2664, -0.1614],
+    [-0.7502, 1.7135, 0.0367],
+    [0.0389, -0.0685, 1.0296],
+]);
+const BRADFORD_INVERSE: Matrix3 = Matrix3::new([
+    [0.9869929, -0.1470543, 0.1599627],
+    [0.4323053, 0.5183603, 0.0492912],
+    [-0.0085287, 0.0400428, 0.9684867],
+]);
+const D65: Vec3 = Vec3::new(0.95047, 1.0, 1.08883);
+
+/// Converts linear scene radiance to display-encoded sRGB: exposure, white balance,
+/// tone mapping, the sRGB OETF and finally an optional LUT.
+pub struct ColorPipeline {
+    exposure_scale: f64,
+    white_balance: Option<Matrix3>,
+    tone_mapper: Box<dyn ToneMapper>,
+    lut: Option<Lut>,
+}
+
+impl ColorPipeline {
+    pub fn new(
+        exposure: f64,
+        white_balance: Option<f64>,
+        tone_mapper: Box<dyn ToneMapper>,
+        lut: Option<Lut>,
+    ) -> Self {
+        Self {
+            exposure_scale: exposure.exp2(),
+            white_balance: white_balance.map(white_balance_matrix),
+            tone_mapper,
+            lut,
+        }
+    }
+
+    pub fn apply(&self, color: &Color) -> Color {
+        let mut color = color.multiply_constant(self.exposure_scale);
+        if let Some(matrix) = &self.white_balance {
+            color = matrix.multiply_vector(&color);
+        }
+
+        let mapped = self.tone_mapper.map(&color.max_constant(0.0));
+        let encoded = Color::new(
+            srgb_oetf(mapped.get_x()),
+            srgb_oetf(mapped.get_y()),
+            srgb_oetf(mapped.get_z()),
+        );
+
+        self.lut.as_ref().map_or(encoded, |lut| lut.apply(&encoded))
+    }
+}
+
+pub fn srgb_oetf(value: f64) -> f64 {
+    let value = value.clamp(0.0, 1.0);
+    if value <= 0.0031308 {
+        12.92 * value
+    } else {
+        1.055 * value.powf(1.0 / 2.4) - 0.055
+    }
+}
+
+/// The inverse of `srgb_oetf`, for colors stored display encoded such as 8-bit ones in files.
+pub fn srgb_eotf(value: f64) -> f64 {
+    let value = value.clamp(0.0, 1.0);
+    if value <= 0.04045 {
+        value / 12.92
+    } else {
+        ((value + 0.055) / 1.055).powf(2.4)
+    }
+}
+
+/// Bradford adaptation in linear sRGB from a Planckian illuminant at `temperature` kelvin to D65.
+fn white_balance_matrix(temperature: f64) -> Matrix3 {
+    let (x, y) = planckian_xy(temperature);
+    let source = BRADFORD.multiply_vector(&Vec3::new(x / y, 1.0, (1.0 - x - y) / y));
+    let target = BRADFORD.multiply_vector(&D65);
+
+    XYZ_TO_SRGB
+        .multiply(&BRADFORD_INVERSE)
+        .multiply(&Matrix3::diagonal(&target.divide(&source)))
+        .multiply(&BRADFORD)
+        .multiply(&SRGB_TO_XYZ)
+}
+
+/// Kim et al. cubic spline approximation of the Planckian locus.
+fn planckian_xy(temperature: f64) -> (f64, f64) {
+    let t = temperature.clamp(1667.0, 25000.0);
+    let (t2, t3) = (t * t, t * t * t);
+    let x = if t <= 4000.0 {
+        -0.2661239e9 / t3 - 0.2343589e6 / t2 + 0.8776956e3 / t + 0.179910
+    } else {
+        -3.0258469e9 / t3 + 2.1070379e6 / t2 + 0.2226347e3 / t + 0.240390
+    };
+    let (x2, x3) = (x * x, x * x * x);
+    let y = if t <= 2222.0 {
+        -1.1063814 * x3 - 1.34811020 * x2 + 2.18555832 * x - 0.20219683
+    } else if t <= 4000.0 {
+        -0.9549476 * x3 - 1.37418593 * x2 + 2.09137015 * x - 0.16748867
+    } else {
+        3.0817580 * x3 - 5.87338670 * x2 + 3.75112997 * x - 0.37001483
+    };
+
+    (x, y)
+}
@@ -0,0 +1,117 @@
+use std::rc::Rc;
+
+use crate::{
+    aabb::{disk_extent, Aabb},
+    frame::{azimuth, Frame},
+    hittable::{first_in_range, pair_intervals, HitRecord, Hittable, Interval, Solid},
+    material::Material,
+    polynomial::solve_quadratic,
+    ray::Ray,
+    stats,
+    vec3::{Point3, Vec3},
+};
+
+/// A cone closed by a disk at `base`, narrowing to its apex at `height` along `axis`. UVs are
+/// laid out as on `Cylinder`.
+pub struct Cone {
+    frame: Frame,
+    radius: f64,
+    height: f64,
+    material: Rc<dyn Material>,
+}
+
+impl Cone {
+    pub fn new(
+        base: Point3,
+        axis: Vec3,
+        radius: f64,
+        height: f64,
+        material: Rc<dyn Material>,
+    ) -> Self {
+        Self {
+            frame: Frame::new(base, &axis),
+            radius,
+            height,
+            material,
+        }
+    }
+
+    /// Every intersection with the side and base, sorted along the whole ray.
+    fn records(&self, ray: &Ray) -> Vec<HitRecord> {
+        let origin = self.frame.to_local_point(&ray.get_origin());
+        let direction = self.frame.to_local_vector(&ray.get_direction());
+        let slope = self.radius / self.height;
+        let slope2 = slope * slope;
+        let mut hits = Vec::new();
+
+        // x² + z² = slope² (height - y)²
+        let remaining = self.height - origin.get_y();
+        let a = direction.get_x() * direction.get_x() + direction.get_z() * direction.get_z()
+            - slope2 * direction.get_y() * direction.get_y();
+        let b = 2.0
+            * (origin.get_x() * direction.get_x()
+                + origin.get_z() * direction.get_z()
+                + slope2 * remaining * direction.get_y());
+        let c = origin.get_x() * origin.get_x() + origin.get_z() * origin.get_z()
+            - slope2 * remaining * remaining;
+        for t in solve_quadratic(a, b, c) {
+            let local = origin.add(&direction.multiply_constant(t));
+            if (0.0..=self.height).contains(&local.get_y()) {
+                let normal = Vec3::new(
+                    local.get_x(),
+                    slope2 * (self.height - local.get_y()),
+                    local.get_z(),
+                );
+                hits.push((t, normal, (azimuth(&local), local.get_y() / self.height)));
+            }
+        }
+
+        if direction.get_y() != 0.0 {
+            let t = -origin.get_y() / direction.get_y();
+            let local = origin.add(&direction.multiply_constant(t));
+            let distance = local.get_x().hypot(local.get_z());
+            if distance <= self.radius {
+                let normal = Vec3::new(0.0, -1.0, 0.0);
+                hits.push((t, normal, (azimuth(&local), distance / self.radius)));
+            }
+        }
+
+        hits.sort_by(|a, b| a.0.total_cmp(&b.0));
+        hits.into_iter()
+            .map(|(t, normal, uv)| {
+                HitRecord::new(
+                    ray.at(t),
+                    self.frame.to_world_vector(&normal).unit_vector(),
+                    t,
+                    uv,
+                    ray,
+                    Rc::clone(&self.material),
+                )
+            })
+            .collect()
+    }
+}
+
+impl Hittable for Cone {
+    fn hit(&self, ray: &Ray, t_min: f64, t_max: f64) -> Option<HitRecord> {
+        stats::record_intersection_test();
+        first_in_range(self.records(ray), t_min, t_max)
+    }
+
+    fn bounding_box(&self) -> Option<Aabb> {
+        let axis = self.frame.get_axis();
+        let base = self.frame.get_origin();
+        let apex = base.add(&axis.multiply_constant(self.height));
+
+        Some(
+            Aabb::around(base, disk_extent(&axis, self.radius)).surrounding(&Aabb::new(apex, apex)),
+        )
+    }
+}
+
+impl Solid for Cone {
+    fn intervals(&self, ray: &Ray) -> Vec<Interval> {
+        stats::record_intersection_test();
+        pair_intervals(self.records(ray))
+    }
+}
@@ -0,0 +1,114 @@
+use crate::{
+    aabb::Aabb,
+    hittable::{first_in_range, HitRecord, Hittable, Interval, Solid},
+    ray::Ray,
+};
+
+#[derive(Clone, Copy)]
+pub enum CsgOperation {
+    Union,
+    Intersection,
+    Difference,
+}
+
+impl CsgOperation {
+    const fn contains(&self, in_left: bool, in_right: bool) -> bool {
+        match self {
+            Self::Union => in_left || in_right,
+            Self::Intersection => in_left && in_right,
+            Self::Difference => in_left && !in_right,
+        }
+    }
+}
+
+/// A boolean combination of two solids, itself a solid so nodes can be nested.
+pub struct Csg {
+    operation: CsgOperation,
+    left: Box<dyn Solid>,
+    right: Box<dyn Solid>,
+}
+
+impl Csg {
+    pub fn new(operation: CsgOperation, left: Box<dyn Solid>, right: Box<dyn Solid>) -> Self {
+        Self {
+            operation,
+            left,
+            right,
+        }
+    }
+}
+
+impl Hittable for Csg {
+    fn hit(&self, ray: &Ray, t_min: f64, t_max: f64) -> Option<HitRecord> {
+        let records = self
+            .intervals(ray)
+            .into_iter()
+            .flat_map(|interval| {
+                let (enter, exit) = interval.into_records();
+                [enter, exit]
+            })
+            .collect();
+        first_in_range(records, t_min, t_max)
+    }
+
+    /// Intersections and differences lie within the left operand.
+    fn bounding_box(&self) -> Option<Aabb> {
+        match self.operation {
+            CsgOperation::Union => Some(
+                self.left
+                    .bounding_box()?
+                    .surrounding(&self.right.bounding_box()?),
+            ),
+            CsgOperation::Intersection | CsgOperation::Difference => self.left.bounding_box(),
+        }
+    }
+}
+
+impl Solid for Csg {
+    /// Sweeps the boundaries of both operands in order, keeping those where being inside the
+    /// result changes. A boundary that enters the result where it exits its own solid, or the
+    /// other way round, has its face flipped.
+    fn intervals(&self, ray: &Ray) -> Vec<Interval> {
+        let mut events = Vec::new();
+        for (intervals, from_left) in [
+            (self.left.intervals(ray), true),
+            (self.right.intervals(ray), false),
+        ] {
+            for interval in intervals {
+                let (enter, exit) = interval.into_records();
+                events.push((enter, from_left, true));
+                events.push((exit, from_left, false));
+            }
+        }
+        events.sort_by(|a, b| a.0.get_t().total_cmp(&b.0.get_t()));
+
+        let mut result = Vec::new();
+        let mut enter = None;
+        let (mut in_left, mut in_right) = (false, false);
+        for (record, from_left, entering) in events {
+            let was_inside = self.operation.contains(in_left, in_right);
+            if from_left {
+                in_left = entering;
+            } else {
+                in_right = entering;
+            }
+            let inside = self.operation.contains(in_left, in_right);
+            if inside == was_inside {
+                continue;
+            }
+
+            let record = if entering == inside {
+                record
+            } else {
+                record.flip_face()
+            };
+            if inside {
+                enter = Some(record);
+            } else if let Some(enter) = enter.take() {
+                result.push(Interval::new(enter, record));
+            }
+        }
+
+        result
+    }
+}
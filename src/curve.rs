@@ -0,0 +1,232 @@
+use std::rc::Rc;
+
+use crate::{
+    aabb::Aabb,
+    bezier::{evaluate, split},
+    hittable::{HitRecord, Hittable},
+    material::Material,
+    ray::Ray,
+    stats,
+    utils::orthonormal_basis,
+    vec3::{Point3, Vec3},
+};
+
+const MAX_DEPTH: i32 = 10;
+
+/// How the width of a curve is turned into a surface.
+#[derive(Clone, Copy)]
+pub enum CurveKind {
+    /// A flat strip whose normal turns from the first to the second vector along the curve,
+    /// like a blade of grass. It gets thinner when seen edge on.
+    Ribbon { normals: [Vec3; 2] },
+    /// A strip that always faces the ray, shaded as if it were a tube. Fine enough for hair and
+    /// fur, which are far thinner than a pixel.
+    Round,
+}
+
+/// A cubic Bézier curve whose width changes linearly from one end to the other. u runs along
+/// the curve and v across it.
+pub struct Curve {
+    points: [Point3; 4],
+    widths: [f64; 2],
+    kind: CurveKind,
+    material: Rc<dyn Material>,
+}
+
+impl Curve {
+    pub fn new(
+        points: [Point3; 4],
+        widths: [f64; 2],
+        kind: CurveKind,
+        material: Rc<dyn Material>,
+    ) -> Self {
+        let kind = match kind {
+            CurveKind::Ribbon { normals } => CurveKind::Ribbon {
+                normals: normals.map(|normal| normal.unit_vector()),
+            },
+            CurveKind::Round => CurveKind::Round,
+        };
+
+        Self {
+            points,
+            widths,
+            kind,
+            material,
+        }
+    }
+
+    fn width(&self, u: f64) -> f64 {
+        self.widths[0] + (self.widths[1] - self.widths[0]) * u
+    }
+
+    fn max_width(&self) -> f64 {
+        self.widths[0].max(self.widths[1])
+    }
+
+    /// The closest hit on the part of the curve between `u0` and `u1`, whose control points are
+    /// given in ray space where x and y are distances from the ray and z is the ray parameter.
+    fn subdivide(
+        &self,
+        ray: &Ray,
+        points: [Point3; 4],
+        (u0, u1): (f64, f64),
+        (t_min, t_max): (f64, f64),
+        depth: i32,
+    ) -> Option<(f64, f64)> {
+        let radius = self.max_width() / 2.0;
+        let [p0, p1, p2, p3] = points;
+        let hull = Aabb::new(p0, p1).surrounding(&Aabb::new(p2, p3));
+        let (minimum, maximum) = (hull.get_minimum(), hull.get_maximum());
+        if minimum.get_x() > radius
+            || maximum.get_x() < -radius
+            || minimum.get_y() > radius
+            || maximum.get_y() < -radius
+            || minimum.get_z() > t_max
+            || maximum.get_z() < t_min
+        {
+            return None;
+        }
+
+        if depth > 0 {
+            let middle = (u0 + u1) / 2.0;
+            let (low, high) = split(points);
+            let closest = self.subdivide(ray, low, (u0, middle), (t_min, t_max), depth - 1);
+            let t_max = closest.map_or(t_max, |(_, t)| t);
+            return self
+                .subdivide(ray, high, (middle, u1), (t_min, t_max), depth - 1)
+                .or(closest);
+        }
+
+        self.segment(ray, &points, (u0, u1), (t_min, t_max))
+    }
+
+    /// Treats a short enough piece of the curve as straight and tests how close the ray passes.
+    fn segment(
+        &self,
+        ray: &Ray,
+        points: &[Point3; 4],
+        (u0, u1): (f64, f64),
+        (t_min, t_max): (f64, f64),
+    ) -> Option<(f64, f64)> {
+        let [p0, p1, p2, p3] = points;
+
+        // The ray has to pass between the perpendiculars at both ends of the piece.
+        if (p1.get_y() - p0.get_y()) * -p0.get_y() + p0.get_x() * (p0.get_x() - p1.get_x()) < 0.0
+            || (p2.get_y() - p3.get_y()) * -p3.get_y() + p3.get_x() * (p3.get_x() - p2.get_x())
+                < 0.0
+        {
+            return None;
+        }
+
+        let (dx, dy) = (p3.get_x() - p0.get_x(), p3.get_y() - p0.get_y());
+        let length2 = dx * dx + dy * dy;
+        if length2 == 0.0 {
+            return None;
+        }
+        let w = (-(p0.get_x() * dx + p0.get_y() * dy) / length2).clamp(0.0, 1.0);
+        let u = u0 + (u1 - u0) * w;
+
+        let mut width = self.width(u);
+        if let CurveKind::Ribbon { normals } = &self.kind {
+            let direction = ray.get_direction();
+            width *= ribbon_normal(normals, u).dot(&direction).abs() / direction.length();
+        }
+
+        let (point, _) = evaluate(points, w);
+        if point.get_x() * point.get_x() + point.get_y() * point.get_y() > width * width / 4.0 {
+            return None;
+        }
+
+        let t = point.get_z();
+        (t >= t_min && t <= t_max).then_some((u, t))
+    }
+}
+
+fn ribbon_normal([n0, n1]: &[Vec3; 2], u: f64) -> Vec3 {
+    n0.multiply_constant(1.0 - u)
+        .add(&n1.multiply_constant(u))
+        .unit_vector()
+}
+
+impl Hittable for Curve {
+    fn hit(&self, ray: &Ray, t_min: f64, t_max: f64) -> Option<HitRecord> {
+        stats::record_intersection_test();
+        let origin = ray.get_origin();
+        let direction = ray.get_direction();
+        let (e1, e2) = orthonormal_basis(&direction.unit_vector());
+        let ray_space = self.points.map(|point| {
+            let offset = point.subtract(&origin);
+            Vec3::new(
+                offset.dot(&e1),
+                offset.dot(&e2),
+                offset.dot(&direction) / direction.square(),
+            )
+        });
+
+        // Enough subdivisions that the pieces are straight to within a fraction of the width.
+        let deviation = (0..2)
+            .map(|i| {
+                let [a, b, c] = [ray_space[i], ray_space[i + 1], ray_space[i + 2]];
+                let second = a.subtract(&b.multiply_constant(2.0)).add(&c);
+                second.get_x().hypot(second.get_y())
+            })
+            .fold(0.0, f64::max);
+        let tolerance = self.max_width() / 20.0;
+        let depth = if deviation > 0.0 && tolerance > 0.0 {
+            ((6.0 * 2.0_f64.sqrt() * deviation / (8.0 * tolerance)).log2() / 2.0).ceil() as i32
+        } else {
+            0
+        };
+
+        let (u, t) = self.subdivide(
+            ray,
+            ray_space,
+            (0.0, 1.0),
+            (t_min, t_max),
+            depth.clamp(0, MAX_DEPTH),
+        )?;
+
+        let point = ray.at(t);
+        let (center, tangent) = evaluate(&self.points, u);
+        let tangent = tangent.unit_vector();
+        let half_width = self.width(u) / 2.0;
+        let (outward_normal, lateral) = match self.kind {
+            CurveKind::Ribbon { normals } => {
+                let normal = ribbon_normal(&normals, u);
+                let side = normal.cross(&tangent).unit_vector();
+                let lateral = point.subtract(&center).dot(&side) / half_width;
+                (normal, lateral)
+            }
+            CurveKind::Round => {
+                let side = direction.cross(&tangent).unit_vector();
+                let facing = side.cross(&tangent);
+                let lateral = (point.subtract(&center).dot(&side) / half_width).clamp(-1.0, 1.0);
+                let normal = side
+                    .multiply_constant(lateral)
+                    .add(&facing.multiply_constant((1.0 - lateral * lateral).sqrt()));
+                (normal, lateral)
+            }
+        };
+
+        Some(
+            HitRecord::new(
+                point,
+                outward_normal,
+                t,
+                (u, (lateral.clamp(-1.0, 1.0) + 1.0) / 2.0),
+                ray,
+                Rc::clone(&self.material),
+            )
+            .with_tangent(tangent),
+        )
+    }
+
+    fn bounding_box(&self) -> Option<Aabb> {
+        let [p0, p1, p2, p3] = self.points;
+        Some(
+            Aabb::new(p0, p1)
+                .surrounding(&Aabb::new(p2, p3))
+                .expand(self.max_width() / 2.0),
+        )
+    }
+}
@@ -0,0 +1,106 @@
+use std::rc::Rc;
+
+use crate::{
+    aabb::{disk_extent, Aabb},
+    frame::{azimuth, Frame},
+    hittable::{first_in_range, pair_intervals, HitRecord, Hittable, Interval, Solid},
+    material::Material,
+    polynomial::solve_quadratic,
+    ray::Ray,
+    stats,
+    vec3::{Point3, Vec3},
+};
+
+/// A closed cylinder from `base` along `axis` for `height`. On the side u goes around and v
+/// along the axis; on the caps v goes outwards from the center.
+pub struct Cylinder {
+    frame: Frame,
+    radius: f64,
+    height: f64,
+    material: Rc<dyn Material>,
+}
+
+impl Cylinder {
+    pub fn new(
+        base: Point3,
+        axis: Vec3,
+        radius: f64,
+        height: f64,
+        material: Rc<dyn Material>,
+    ) -> Self {
+        Self {
+            frame: Frame::new(base, &axis),
+            radius,
+            height,
+            material,
+        }
+    }
+
+    /// Every intersection with the side and caps, sorted along the whole ray.
+    fn records(&self, ray: &Ray) -> Vec<HitRecord> {
+        let origin = self.frame.to_local_point(&ray.get_origin());
+        let direction = self.frame.to_local_vector(&ray.get_direction());
+        let mut hits = Vec::new();
+
+        let a = direction.get_x() * direction.get_x() + direction.get_z() * direction.get_z();
+        let b = 2.0 * (origin.get_x() * direction.get_x() + origin.get_z() * direction.get_z());
+        let c = origin.get_x() * origin.get_x() + origin.get_z() * origin.get_z()
+            - self.radius * self.radius;
+        for t in solve_quadratic(a, b, c) {
+            let local = origin.add(&direction.multiply_constant(t));
+            if (0.0..=self.height).contains(&local.get_y()) {
+                let normal = Vec3::new(local.get_x(), 0.0, local.get_z());
+                hits.push((t, normal, (azimuth(&local), local.get_y() / self.height)));
+            }
+        }
+
+        if direction.get_y() != 0.0 {
+            for (y, normal_y) in [(0.0, -1.0), (self.height, 1.0)] {
+                let t = (y - origin.get_y()) / direction.get_y();
+                let local = origin.add(&direction.multiply_constant(t));
+                let distance = local.get_x().hypot(local.get_z());
+                if distance <= self.radius {
+                    let normal = Vec3::new(0.0, normal_y, 0.0);
+                    hits.push((t, normal, (azimuth(&local), distance / self.radius)));
+                }
+            }
+        }
+
+        hits.sort_by(|a, b| a.0.total_cmp(&b.0));
+        hits.into_iter()
+            .map(|(t, normal, uv)| {
+                HitRecord::new(
+                    ray.at(t),
+                    self.frame.to_world_vector(&normal).unit_vector(),
+                    t,
+                    uv,
+                    ray,
+                    Rc::clone(&self.material),
+                )
+            })
+            .collect()
+    }
+}
+
+impl Hittable for Cylinder {
+    fn hit(&self, ray: &Ray, t_min: f64, t_max: f64) -> Option<HitRecord> {
+        stats::record_intersection_test();
+        first_in_range(self.records(ray), t_min, t_max)
+    }
+
+    fn bounding_box(&self) -> Option<Aabb> {
+        let axis = self.frame.get_axis();
+        let extent = disk_extent(&axis, self.radius);
+        let base = self.frame.get_origin();
+        let top = base.add(&axis.multiply_constant(self.height));
+
+        Some(Aabb::around(base, extent).surrounding(&Aabb::around(top, extent)))
+    }
+}
+
+impl Solid for Cylinder {
+    fn intervals(&self, ray: &Ray) -> Vec<Interval> {
+        stats::record_intersection_test();
+        pair_intervals(self.records(ray))
+    }
+}
@@ -0,0 +1,57 @@
+use crate::color::{Color, BLACK};
+
+use super::{Denoiser, Features};
+
+pub struct JointBilateralDenoiser {
+    radius: isize,
+    sigma_spatial: f64,
+    sigma_albedo: f64,
+    sigma_normal: f64,
+}
+
+impl JointBilateralDenoiser {
+    pub const fn new(
+        radius: isize,
+        sigma_spatial: f64,
+        sigma_albedo: f64,
+        sigma_normal: f64,
+    ) -> Self {
+        Self {
+            radius,
+            sigma_spatial,
+            sigma_albedo,
+            sigma_normal,
+        }
+    }
+}
+
+impl Denoiser for JointBilateralDenoiser {
+    fn denoise(&self, features: &Features) -> Vec<Color> {
+        let mut output = Vec::with_capacity(features.irradiance.len());
+
+        for y in 0..features.height as isize {
+            for x in 0..features.width as isize {
+                let p = features.index(x, y);
+                let mut sum = BLACK;
+                let mut weight_sum = 0.0;
+
+                for dy in -self.radius..=self.radius {
+                    for dx in -self.radius..=self.radius {
+                        let q = features.index(x + dx, y + dy);
+                        let distance2 = (dx * dx + dy * dy) as f64;
+                        let weight = (-distance2 / (2.0 * self.sigma_spatial * self.sigma_spatial))
+                            .exp()
+                            * features.guide_weight(p, q, self.sigma_albedo, self.sigma_normal);
+
+                        sum = sum.add(&features.irradiance[q].multiply_constant(weight));
+                        weight_sum += weight;
+                    }
+                }
+
+                output.push(sum.divide_constant(weight_sum));
+            }
+        }
+
+        output
+    }
+}
@@ -0,0 +1,89 @@
+use crate::{
+    aov::Aov,
+    color::{Color, WHITE},
+    film::Film,
+    vec3::Vec3,
+};
+pub use bilateral::JointBilateralDenoiser;
+pub use non_local_means::NonLocalMeansDenoiser;
+
+mod bilateral;
+mod non_local_means;
+
+const ALBEDO_EPS: f64 = 0.01;
+
+pub trait Denoiser {
+    fn denoise(&self, features: &Features) -> Vec<Color>;
+}
+
+/// Albedo-demodulated radiance with its guide buffers, stored in the same order as `Film::get_colors`.
+pub struct Features {
+    width: usize,
+    height: usize,
+    irradiance: Vec<Color>,
+    albedo: Vec<Color>,
+    normal: Vec<Vec3>,
+}
+
+impl Features {
+    pub fn new(film: &Film) -> Self {
+        let count = film.get_width() * film.get_height();
+        let albedo = film
+            .get_aov(Aov::Albedo)
+            .unwrap_or_else(|| vec![WHITE; count]);
+        let normal = film
+            .get_aov(Aov::Normal)
+            .unwrap_or_else(|| vec![Vec3::new(0.0, 0.0, 0.0); count]);
+        let irradiance = film
+            .get_colors()
+            .iter()
+            .zip(&albedo)
+            .map(|(color, albedo)| color.divide(&albedo.max_constant(ALBEDO_EPS)))
+            .collect();
+
+        Self {
+            width: film.get_width(),
+            height: film.get_height(),
+            irradiance,
+            albedo,
+            normal,
+        }
+    }
+
+    fn index(&self, x: isize, y: isize) -> usize {
+        let x = x.clamp(0, self.width as isize - 1) as usize;
+        let y = y.clamp(0, self.height as isize - 1) as usize;
+        y * self.width + x
+    }
+
+    fn guide_weight(&self, p: usize, q: usize, sigma_albedo: f64, sigma_normal: f64) -> f64 {
+        let albedo = self.albedo[p].subtract(&self.albedo[q]).square();
+        let normal = self.normal[p].subtract(&self.normal[q]).square();
+        (-albedo / (2.0 * sigma_albedo * sigma_albedo)
+            - normal / (2.0 * sigma_normal * sigma_normal))
+            .exp()
+    }
+
+    fn remodulate(&self, irradiance: &[Color]) -> Vec<Color> {
+        irradiance
+            .iter()
+            .zip(&self.albedo)
+            .map(|(irradiance, albedo)| irradiance.multiply(&albedo.max_constant(ALBEDO_EPS)))
+            .collect()
+    }
+}
+
+pub fn denoise(denoiser: &dyn Denoiser, film: &Film) -> Vec<Color> {
+    let features = Features::new(film);
+    features.remodulate(&denoiser.denoise(&features))
+}
+
+pub fn from_name(name: &str) -> Option<Box<dyn Denoiser>> {
+    let denoiser: Box<dyn Denoiser> = match name {
+        "bilateral" => Box::new(JointBilateralDenoiser::new(5, 2.5, 0.1, 0.2)),
+        "nlm" => Box::new(NonLocalMeansDenoiser::new(6, 2, 0.4, 0.1, 0.2)),
+        _ => return None,
+    };
+
+    Some(denoiser)
+}
@@ -0,0 +1,90 @@
+use crate::color::{Color, BLACK};
+
+use super::{Denoiser, Features};
+
+const RELATIVE_EPS: f64 = 1e-2;
+
+pub struct NonLocalMeansDenoiser {
+    radius: isize,
+    patch_radius: isize,
+    strength: f64,
+    sigma_albedo: f64,
+    sigma_normal: f64,
+}
+
+impl NonLocalMeansDenoiser {
+    pub const fn new(
+        radius: isize,
+        patch_radius: isize,
+        strength: f64,
+        sigma_albedo: f64,
+        sigma_normal: f64,
+    ) -> Self {
+        Self {
+            radius,
+            patch_radius,
+            strength,
+            sigma_albedo,
+            sigma_normal,
+        }
+    }
+
+    /// Mean relative squared difference between the patches around `(x, y)` and `(qx, qy)`.
+    fn patch_distance(
+        &self,
+        features: &Features,
+        (x, y): (isize, isize),
+        (qx, qy): (isize, isize),
+    ) -> f64 {
+        let mut distance = 0.0;
+
+        for dy in -self.patch_radius..=self.patch_radius {
+            for dx in -self.patch_radius..=self.patch_radius {
+                let a = features.irradiance[features.index(x + dx, y + dy)];
+                let b = features.irradiance[features.index(qx + dx, qy + dy)];
+                let difference = a.subtract(&b);
+                let scale = a
+                    .multiply(&a)
+                    .add(&b.multiply(&b))
+                    .add_constant(RELATIVE_EPS);
+                distance += difference
+                    .multiply(&difference)
+                    .divide(&scale)
+                    .dot(&Color::new(1.0, 1.0, 1.0));
+            }
+        }
+
+        let size = 2 * self.patch_radius + 1;
+        distance / (3 * size * size) as f64
+    }
+}
+
+impl Denoiser for NonLocalMeansDenoiser {
+    fn denoise(&self, features: &Features) -> Vec<Color> {
+        let mut output = Vec::with_capacity(features.irradiance.len());
+
+        for y in 0..features.height as isize {
+            for x in 0..features.width as isize {
+                let p = features.index(x, y);
+                let mut sum = BLACK;
+                let mut weight_sum = 0.0;
+
+                for qy in y - self.radius..=y + self.radius {
+                    for qx in x - self.radius..=x + self.radius {
+                        let q = features.index(qx, qy);
+                        let distance = self.patch_distance(features, (x, y), (qx, qy));
+                        let weight = (-distance / (self.strength * self.strength)).exp()
+                            * features.guide_weight(p, q, self.sigma_albedo, self.sigma_normal);
+
+                        sum = sum.add(&features.irradiance[q].multiply_constant(weight));
+                        weight_sum += weight;
+                    }
+                }
+
+                output.push(sum.divide_constant(weight_sum));
+            }
+        }
+
+        output
+    }
+}
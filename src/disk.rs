@@ -0,0 +1,65 @@
+use std::rc::Rc;
+
+use crate::{
+    aabb::{disk_extent, Aabb},
+    frame::{azimuth, Frame},
+    hittable::{HitRecord, Hittable},
+    material::Material,
+    ray::Ray,
+    stats,
+    vec3::{Point3, Vec3},
+};
+
+/// A flat disk facing along `normal`, with u going around and v outwards from the center.
+pub struct Disk {
+    frame: Frame,
+    radius: f64,
+    material: Rc<dyn Material>,
+}
+
+impl Disk {
+    pub fn new(center: Point3, normal: Vec3, radius: f64, material: Rc<dyn Material>) -> Self {
+        Self {
+            frame: Frame::new(center, &normal),
+            radius,
+            material,
+        }
+    }
+}
+
+impl Hittable for Disk {
+    fn hit(&self, ray: &Ray, t_min: f64, t_max: f64) -> Option<HitRecord> {
+        stats::record_intersection_test();
+        let origin = self.frame.to_local_point(&ray.get_origin());
+        let direction = self.frame.to_local_vector(&ray.get_direction());
+        if direction.get_y() == 0.0 {
+            return None;
+        }
+
+        let t = -origin.get_y() / direction.get_y();
+        if !(t_min..=t_max).contains(&t) {
+            return None;
+        }
+        let local = origin.add(&direction.multiply_constant(t));
+        let distance = local.get_x().hypot(local.get_z());
+        if distance > self.radius {
+            return None;
+        }
+
+        Some(HitRecord::new(
+            ray.at(t),
+            self.frame.get_axis(),
+            t,
+            (azimuth(&local), distance / self.radius),
+            ray,
+            Rc::clone(&self.material),
+        ))
+    }
+
+    fn bounding_box(&self) -> Option<Aabb> {
+        Some(Aabb::around(
+            self.frame.get_origin(),
+            disk_extent(&self.frame.get_axis(), self.radius),
+        ))
+    }
+}
@@ -0,0 +1,374 @@
+use std::{
+    fs::{self, File},
+    io::{self, BufReader, BufWriter, Read, Write},
+};
+
+use crate::{
+    aov::{write_pfm, Aov, AovSample},
+    color::{Color, MultipleSamplesColor, BLACK},
+    filter::Filter,
+};
+
+const CHECKPOINT_MAGIC: &[u8; 4] = b"RTCK";
+
+pub struct Film {
+    width: usize,
+    height: usize,
+    filter: Box<dyn Filter>,
+    pixels: Vec<MultipleSamplesColor>,
+    aovs: Vec<(Aov, Vec<MultipleSamplesColor>)>,
+    /// Light from paths traced from the lights that reached the lens, which lands anywhere on
+    /// the image and is averaged over every light path traced rather than per pixel.
+    splats: Vec<Color>,
+    light_paths: usize,
+}
+
+impl Film {
+    pub fn new(width: usize, height: usize, filter: Box<dyn Filter>) -> Self {
+        Self {
+            width,
+            height,
+            filter,
+            pixels: (0..width * height)
+                .map(|_| MultipleSamplesColor::new())
+                .collect(),
+            aovs: Vec::new(),
+            splats: vec![BLACK; width * height],
+            light_paths: 0,
+        }
+    }
+
+    /// Discards all accumulated samples, keeping the enabled AOVs.
+    #[cfg(feature = "preview")]
+    pub fn clear(&mut self) {
+        for buffer in
+            std::iter::once(&mut self.pixels).chain(self.aovs.iter_mut().map(|(_, buffer)| buffer))
+        {
+            buffer.fill_with(MultipleSamplesColor::new);
+        }
+        self.splats.fill(BLACK);
+        self.light_paths = 0;
+    }
+
+    pub fn enable_aov(&mut self, aov: Aov) {
+        if aov == Aov::SampleCount || self.aovs.iter().any(|(enabled, _)| *enabled == aov) {
+            return;
+        }
+
+        let buffer = (0..self.width * self.height)
+            .map(|_| MultipleSamplesColor::new())
+            .collect();
+        self.aovs.push((aov, buffer));
+    }
+
+    pub fn has_aovs(&self) -> bool {
+        !self.aovs.is_empty()
+    }
+
+    pub const fn get_width(&self) -> usize {
+        self.width
+    }
+
+    pub const fn get_height(&self) -> usize {
+        self.height
+    }
+
+    pub fn get_pixels(&self) -> &[MultipleSamplesColor] {
+        &self.pixels
+    }
+
+    pub fn get_colors(&self) -> Vec<Color> {
+        // Each light path's splats estimate the whole image, rather than one pixel.
+        let splat_scale = match self.light_paths {
+            0 => 0.0,
+            light_paths => (self.width * self.height) as f64 / light_paths as f64,
+        };
+        self.pixels
+            .iter()
+            .zip(&self.splats)
+            .map(|(pixel, splat)| pixel.average().add(&splat.multiply_constant(splat_scale)))
+            .collect()
+    }
+
+    pub fn get_pixel(&self, i: usize, j: usize) -> &MultipleSamplesColor {
+        &self.pixels[self.index(i, j)]
+    }
+
+    pub fn uv(&self, i: usize, j: usize, (du, dv): (f64, f64)) -> (f64, f64) {
+        (
+            (j as f64 + du) / (self.width - 1) as f64,
+            (i as f64 + dv) / (self.height - 1) as f64,
+        )
+    }
+
+    pub fn add_sample(&mut self, i: usize, j: usize, (du, dv): (f64, f64), color: &Color) {
+        let index = self.index(i, j);
+        self.pixels[index].record(color);
+
+        let x = j as f64 + du;
+        let y = i as f64 + dv;
+        let radius = self.filter.radius();
+        let x_range = pixel_range(x, radius, self.width);
+        let y_range = pixel_range(y, radius, self.height);
+
+        for pi in y_range {
+            for pj in x_range.clone() {
+                let weight = self
+                    .filter
+                    .evaluate(x - (pj as f64 + 0.5), y - (pi as f64 + 0.5));
+                if weight != 0.0 {
+                    let index = self.index(pi, pj);
+                    self.pixels[index].splat(color, weight);
+                }
+            }
+        }
+    }
+
+    /// Adds light that reached the image at `u`, `v` from a path traced from a light. Light
+    /// outside the image is dropped.
+    pub fn add_splat(&mut self, (u, v): (f64, f64), color: &Color) {
+        let x = u * (self.width - 1) as f64;
+        let y = v * (self.height - 1) as f64;
+        if x < 0.0 || y < 0.0 || x >= self.width as f64 || y >= self.height as f64 {
+            return;
+        }
+
+        let index = self.index(y as usize, x as usize);
+        self.splats[index] = self.splats[index].add(color);
+    }
+
+    /// Counts the light paths traced, whose splats are averaged over all of them.
+    pub fn add_light_paths(&mut self, count: usize) {
+        self.light_paths += count;
+    }
+
+    /// The density of uniformly sampled image points per unit area of `uv` coordinates,
+    /// which run slightly past 1 at the far edges.
+    pub fn uv_density(&self) -> f64 {
+        ((self.width - 1) * (self.height - 1)) as f64 / (self.width * self.height) as f64
+    }
+
+    pub fn add_pixel(&mut self, i: usize, j: usize, color: &Color) {
+        let index = self.index(i, j);
+        self.pixels[index].add(color);
+    }
+
+    pub fn add_aovs(&mut self, i: usize, j: usize, sample: &AovSample) {
+        let index = self.index(i, j);
+        for (aov, buffer) in &mut self.aovs {
+            buffer[index].add(&sample.get(*aov));
+        }
+    }
+
+    /// Averaged values of an AOV in the same order as `get_pixels`, if it was enabled.
+    pub fn get_aov(&self, aov: Aov) -> Option<Vec<Color>> {
+        if aov == Aov::SampleCount {
+            return Some(
+                self.pixels
+                    .iter()
+                    .map(|pixel| {
+                        let count = pixel.get_samples_count() as f64;
+                        Color::new(count, count, count)
+                    })
+                    .collect(),
+            );
+        }
+
+        self.aovs
+            .iter()
+            .find(|(enabled, _)| *enabled == aov)
+            .map(|(_, buffer)| buffer.iter().map(MultipleSamplesColor::average).collect())
+    }
+
+    pub fn write_aov(&self, aov: Aov, path: &str) -> io::Result<()> {
+        let colors = self
+            .get_aov(aov)
+            .ok_or_else(|| io::Error::other("AOV was not enabled"))?;
+        write_pfm(path, self.width, self.height, &colors)
+    }
+
+    /// Saves the accumulation buffers, including AOVs, so the render can be resumed.
+    pub fn save_checkpoint(&self, path: &str) -> io::Result<()> {
+        let temporary = format!("{}.tmp", path);
+        let mut file = BufWriter::new(File::create(&temporary)?);
+
+        file.write_all(CHECKPOINT_MAGIC)?;
+        for value in [self.width, self.height, self.aovs.len()] {
+            file.write_all(&(value as u64).to_le_bytes())?;
+        }
+        for (aov, _) in &self.aovs {
+            writeln!(file, "{}", aov.name())?;
+        }
+        for buffer in
+            std::iter::once(&self.pixels).chain(self.aovs.iter().map(|(_, buffer)| buffer))
+        {
+            for pixel in buffer {
+                pixel.write_to(&mut file)?;
+            }
+        }
+        file.write_all(&(self.light_paths as u64).to_le_bytes())?;
+        for splat in &self.splats {
+            for value in [splat.get_x(), splat.get_y(), splat.get_z()] {
+                file.write_all(&value.to_le_bytes())?;
+            }
+        }
+
+        file.into_inner()?.sync_all()?;
+        fs::rename(temporary, path)
+    }
+
+    /// Replaces the accumulation buffers with a checkpoint written by `save_checkpoint`.
+    pub fn load_checkpoint(&mut self, path: &str) -> io::Result<()> {
+        let invalid = |message: String| io::Error::new(io::ErrorKind::InvalidData, message);
+        let mut file = BufReader::new(File::open(path)?);
+
+        let mut magic = [0; 4];
+        file.read_exact(&mut magic)?;
+        if &magic != CHECKPOINT_MAGIC {
+            return Err(invalid(format!("{} is not a checkpoint", path)));
+        }
+
+        let mut read_u64 = || -> io::Result<usize> {
+            let mut buffer = [0; 8];
+            file.read_exact(&mut buffer)?;
+            Ok(u64::from_le_bytes(buffer) as usize)
+        };
+        let (width, height, aov_count) = (read_u64()?, read_u64()?, read_u64()?);
+        if (width, height) != (self.width, self.height) {
+            return Err(invalid(format!(
+                "checkpoint is {}x{} but the film is {}x{}",
+                width, height, self.width, self.height
+            )));
+        }
+
+        let mut aovs = Vec::with_capacity(aov_count);
+        for _ in 0..aov_count {
+            let mut name = Vec::new();
+            let mut byte = [0];
+            loop {
+                file.read_exact(&mut byte)?;
+                if byte[0] == b'\n' {
+                    break;
+                }
+                name.push(byte[0]);
+            }
+            let name = String::from_utf8_lossy(&name).into_owned();
+            aovs.push(
+                Aov::from_name(&name).ok_or_else(|| invalid(format!("unknown aov {}", name)))?,
+            );
+        }
+
+        let count = self.width * self.height;
+        let mut read_buffer = || -> io::Result<Vec<MultipleSamplesColor>> {
+            (0..count)
+                .map(|_| MultipleSamplesColor::read_from(&mut file))
+                .collect()
+        };
+        self.pixels = read_buffer()?;
+        for aov in aovs {
+            let buffer = read_buffer()?;
+            match self.aovs.iter_mut().find(|(enabled, _)| *enabled == aov) {
+                Some((_, existing)) => *existing = buffer,
+                None => self.aovs.push((aov, buffer)),
+            }
+        }
+
+        let mut buffer = [0; 8];
+        file.read_exact(&mut buffer)?;
+        self.light_paths = u64::from_le_bytes(buffer) as usize;
+        let mut read_f64 = || -> io::Result<f64> {
+            file.read_exact(&mut buffer)?;
+            Ok(f64::from_le_bytes(buffer))
+        };
+        for splat in &mut self.splats {
+            *splat = Color::new(read_f64()?, read_f64()?, read_f64()?);
+        }
+
+        Ok(())
+    }
+
+    fn index(&self, i: usize, j: usize) -> usize {
+        (self.height - 1 - i) * self.width + j
+    }
+}
+
+fn pixel_range(position: f64, radius: f64, size: usize) -> std::ops::Range<usize> {
+    let start = (position - radius - 0.5).floor().max(0.0) as usize;
+    let end = ((position + radius - 0.5).floor() as usize + 1).min(size);
+    start..end
+}
+
+#[cfg(test)]
+mod tests {
+    use std::env;
+
+    use super::*;
+    use crate::{
+        filter::BoxFilter,
+        ray::Ray,
+        scene::{Background, Scene},
+        vec3::{Point3, Vec3},
+    };
+
+    fn film(width: usize, height: usize) -> Film {
+        Film::new(width, height, Box::new(BoxFilter::new(0.5)))
+    }
+
+    fn checkpoint_path(name: &str) -> String {
+        env::temp_dir()
+            .join(format!("{}-{}.rtck", name, std::process::id()))
+            .to_string_lossy()
+            .into_owned()
+    }
+
+    fn assert_same_colors(actual: &[Color], expected: &[Color]) {
+        assert_eq!(actual.len(), expected.len());
+        for (actual, expected) in actual.iter().zip(expected) {
+            assert_eq!(
+                [actual.get_x(), actual.get_y(), actual.get_z()],
+                [expected.get_x(), expected.get_y(), expected.get_z()]
+            );
+        }
+    }
+
+    #[test]
+    fn checkpoint_round_trip() {
+        let mut saved = film(3, 2);
+        saved.enable_aov(Aov::Direct);
+        saved.add_sample(0, 0, (0.5, 0.5), &Color::new(0.25, 0.5, 1.0));
+        saved.add_sample(0, 0, (0.5, 0.5), &Color::new(0.75, 0.5, 0.0));
+        saved.add_pixel(1, 2, &Color::new(2.0, 3.0, 4.0));
+        let scene = Scene::new(Background::Solid(BLACK));
+        let ray = Ray::new(Point3::new(0.0, 0.0, 0.0), Vec3::new(0.0, 0.0, -1.0));
+        let components = (Color::new(0.1, 0.2, 0.3), BLACK);
+        saved.add_aovs(1, 2, &AovSample::new(&ray, &scene, components));
+        saved.add_splat((0.75, 0.25), &Color::new(6.0, 0.0, 3.0));
+        saved.add_light_paths(4);
+
+        let path = checkpoint_path("checkpoint_round_trip");
+        saved.save_checkpoint(&path).unwrap();
+        let mut loaded = film(3, 2);
+        let result = loaded.load_checkpoint(&path);
+        fs::remove_file(&path).unwrap();
+        result.unwrap();
+
+        for (loaded, saved) in loaded.get_pixels().iter().zip(saved.get_pixels()) {
+            assert_eq!(loaded.get_samples_count(), saved.get_samples_count());
+        }
+        assert_same_colors(&loaded.get_colors(), &saved.get_colors());
+        assert_same_colors(
+            &loaded.get_aov(Aov::Direct).unwrap(),
+            &saved.get_aov(Aov::Direct).unwrap(),
+        );
+    }
+
+    #[test]
+    fn checkpoint_of_other_size_is_rejected() {
+        let path = checkpoint_path("checkpoint_of_other_size_is_rejected");
+        film(3, 2).save_checkpoint(&path).unwrap();
+        let result = film(2, 3).load_checkpoint(&path);
+        fs::remove_file(&path).unwrap();
+
+        assert_eq!(result.unwrap_err().kind(), io::ErrorKind::InvalidData);
+    }
+}
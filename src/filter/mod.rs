@@ -0,0 +1,30 @@
+pub use box_filter::BoxFilter;
+pub use gaussian::GaussianFilter;
+pub use lanczos::LanczosFilter;
+pub use mitchell::MitchellFilter;
+pub use tent::TentFilter;
+
+mod box_filter;
+mod gaussian;
+mod lanczos;
+mod mitchell;
+mod tent;
+
+pub trait Filter {
+    fn radius(&self) -> f64;
+
+    fn evaluate(&self, x: f64, y: f64) -> f64;
+}
+
+pub fn from_name(name: &str) -> Option<Box<dyn Filter>> {
+    let filter: Box<dyn Filter> = match name {
+        "box" => Box::new(BoxFilter::new(0.5)),
+        "tent" => Box::new(TentFilter::new(1.0)),
+        "gaussian" => Box::new(GaussianFilter::new(1.5, 0.5)),
+        "mitchell" => Box::new(MitchellFilter::new(2.0, 1.0 / 3.0, 1.0 / 3.0)),
+        "lanczos" => Box::new(LanczosFilter::new(3.0)),
+        _ => return None,
+    };
+
+    Some(filter)
+}
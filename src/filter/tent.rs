@@ -0,0 +1,21 @@
+use super::Filter;
+
+pub struct TentFilter {
+    radius: f64,
+}
+
+impl TentFilter {
+    pub const fn new(radius: f64) -> Self {
+        Self { radius }
+    }
+}
+
+impl Filter for TentFilter {
+    fn radius(&self) -> f64 {
+        self.radius
+    }
+
+    fn evaluate(&self, x: f64, y: f64) -> f64 {
+        (self.radius - x.abs()).max(0.0) * (self.radius - y.abs()).max(0.0)
+    }
+}
@@ -0,0 +1,55 @@
+use crate::{
+    constants::PI,
+    utils::orthonormal_basis,
+    vec3::{Point3, Vec3},
+};
+
+/// An orthonormal frame whose local y axis is a shape's axis, so shapes can be intersected in
+/// a canonical orientation. Distances along a ray are the same in both spaces.
+pub struct Frame {
+    origin: Point3,
+    x: Vec3,
+    y: Vec3,
+    z: Vec3,
+}
+
+impl Frame {
+    pub fn new(origin: Point3, axis: &Vec3) -> Self {
+        let y = axis.unit_vector();
+        let (x, z) = orthonormal_basis(&y);
+
+        Self { origin, x, y, z }
+    }
+
+    pub const fn get_origin(&self) -> Point3 {
+        self.origin
+    }
+
+    pub const fn get_axis(&self) -> Vec3 {
+        self.y
+    }
+
+    pub fn to_local_point(&self, point: &Point3) -> Point3 {
+        self.to_local_vector(&point.subtract(&self.origin))
+    }
+
+    pub fn to_local_vector(&self, vector: &Vec3) -> Vec3 {
+        Vec3::new(
+            vector.dot(&self.x),
+            vector.dot(&self.y),
+            vector.dot(&self.z),
+        )
+    }
+
+    pub fn to_world_vector(&self, vector: &Vec3) -> Vec3 {
+        self.x
+            .multiply_constant(vector.get_x())
+            .add(&self.y.multiply_constant(vector.get_y()))
+            .add(&self.z.multiply_constant(vector.get_z()))
+    }
+}
+
+/// Angle around the local y axis as a texture coordinate in [0, 1].
+pub fn azimuth(local: &Point3) -> f64 {
+    (local.get_z().atan2(local.get_x()) + PI) / (2.0 * PI)
+}
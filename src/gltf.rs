@@ -0,0 +1,790 @@
+use std::{fs, path::Path, rc::Rc};
+
+use crate::{
+    aabb::Aabb,
+    camera::{CameraView, Projection},
+    color::{Color, BLACK},
+    constants::PI,
+    hittable::Hittable,
+    json::Json,
+    light::SphereLight,
+    material::{Dielectric, DiffuseLight, Lambertian, Material, Metal},
+    matrix::Matrix4,
+    mesh::{Mesh, TriangleMesh},
+    scene::{Background, Scene},
+    vec3::{Point3, Vec3},
+};
+
+const GLB_MAGIC: &[u8] = b"glTF";
+const JSON_CHUNK: u32 = 0x4e4f_534a;
+const BINARY_CHUNK: u32 = 0x004e_4942;
+
+const TRIANGLES: usize = 4;
+const TRIANGLE_STRIP: usize = 5;
+const TRIANGLE_FAN: usize = 6;
+
+/// Extensions that are understood well enough to be listed as required by a file.
+const SUPPORTED_EXTENSIONS: [&str; 4] = [
+    "KHR_lights_punctual",
+    "KHR_materials_emissive_strength",
+    "KHR_materials_ior",
+    "KHR_materials_transmission",
+];
+
+/// Radius of the spheres standing in for point lights, relative to the size of the scene.
+const LIGHT_RADIUS: f64 = 0.005;
+/// Directional lights become a sphere this far away, relative to the size of the scene, and
+/// about as wide in the sky as the sun.
+const SUN_DISTANCE: f64 = 1000.0;
+const SUN_ANGULAR_RADIUS: f64 = 0.005;
+
+static EMPTY: Json = Json::Object(Vec::new());
+
+/// A glTF 2.0 file, either `.gltf` JSON with its buffers in separate files or data URIs, or
+/// binary `.glb`.
+///
+/// Every mesh instance in the default scene is flattened into world space. Materials take
+/// their factors from the metallic-roughness model, ignoring textures: emissive materials
+/// become lights, transmissive ones dielectrics, mostly metallic ones metal and the rest
+/// lambertian. The first camera found is used, or one framing the whole scene. Lights from
+/// `KHR_lights_punctual` become spheres, small ones for point lights and distant ones for
+/// directional lights; spot lights are rejected, since a sphere can't limit its cone.
+pub struct Gltf {
+    path: String,
+    json: Json,
+    buffers: Vec<Vec<u8>>,
+}
+
+/// What a walk through the node hierarchy found, in world space.
+#[derive(Default)]
+struct Instances {
+    meshes: Vec<(TriangleMesh, Option<usize>)>,
+    camera: Option<(Matrix4, usize)>,
+    lights: Vec<(Matrix4, usize)>,
+    /// Nodes form a strict tree, so each one is reached at most once.
+    visited: Vec<bool>,
+}
+
+impl Gltf {
+    pub fn load(path: &str) -> Result<Self, String> {
+        let bytes =
+            fs::read(path).map_err(|error| format!("failed to read {}: {}", path, error))?;
+        let error = |message: String| format!("{}: {}", path, message);
+
+        let (text, binary) = if bytes.starts_with(GLB_MAGIC) {
+            split_glb(&bytes).map_err(error)?
+        } else {
+            (&bytes[..], None)
+        };
+        let text = std::str::from_utf8(text)
+            .map_err(|_| error("the JSON is not valid UTF-8".to_string()))?;
+        let json = Json::parse(text).map_err(error)?;
+
+        let version = json
+            .get("asset")
+            .and_then(|asset| asset.get("version"))
+            .and_then(Json::as_str)
+            .unwrap_or_default();
+        if !version.starts_with("2.") {
+            return Err(error(format!("unsupported glTF version {}", version)));
+        }
+        for extension in array(&json, "extensionsRequired") {
+            let name = extension.as_str().unwrap_or_default();
+            if !SUPPORTED_EXTENSIONS.contains(&name) {
+                return Err(error(format!("unsupported extension {}", name)));
+            }
+        }
+
+        let directory = Path::new(path).parent().unwrap_or(Path::new(""));
+        let buffers = array(&json, "buffers")
+            .iter()
+            .enumerate()
+            .map(|(index, buffer)| {
+                let length = required_index(buffer, "byteLength")?;
+                let data = match buffer.get("uri").and_then(Json::as_str) {
+                    Some(uri) => match uri.strip_prefix("data:") {
+                        Some(data) => decode_data_uri(data)?,
+                        None => {
+                            let file = directory.join(percent_decode(uri));
+                            fs::read(&file).map_err(|error| {
+                                format!("failed to read {}: {}", file.display(), error)
+                            })?
+                        }
+                    },
+                    None if index == 0 => binary
+                        .ok_or("buffer 0 has no uri and there is no binary chunk")?
+                        .to_vec(),
+                    None => return Err(format!("buffer {} has no uri", index)),
+                };
+                if data.len() < length {
+                    return Err(format!("buffer {} is shorter than its byteLength", index));
+                }
+                Ok(data)
+            })
+            .collect::<Result<_, String>>()
+            .map_err(error)?;
+
+        Ok(Self {
+            path: path.to_string(),
+            json,
+            buffers,
+        })
+    }
+
+    /// Builds the default scene, or the first one, or every root node if the file lists no
+    /// scenes.
+    pub fn scene(&self) -> Result<(Scene, CameraView), String> {
+        self.build()
+            .map_err(|message| format!("{}: {}", self.path, message))
+    }
+
+    fn build(&self) -> Result<(Scene, CameraView), String> {
+        let roots: Vec<usize> = match index(&self.json, "scene")?
+            .or((!array(&self.json, "scenes").is_empty()).then_some(0))
+        {
+            Some(scene) => array(self.element("scenes", scene)?, "nodes")
+                .iter()
+                .map(|node| node.as_usize().ok_or("invalid node index".to_string()))
+                .collect::<Result<_, _>>()?,
+            None => {
+                let nodes = array(&self.json, "nodes");
+                let children: Vec<usize> = nodes
+                    .iter()
+                    .flat_map(|node| array(node, "children"))
+                    .filter_map(Json::as_usize)
+                    .collect();
+                (0..nodes.len())
+                    .filter(|node| !children.contains(node))
+                    .collect()
+            }
+        };
+
+        let mut instances = Instances {
+            visited: vec![false; array(&self.json, "nodes").len()],
+            ..Instances::default()
+        };
+        for root in roots {
+            self.visit(root, &Matrix4::identity(), &mut instances)?;
+        }
+
+        let materials = array(&self.json, "materials")
+            .iter()
+            .map(material)
+            .collect::<Result<Vec<_>, _>>()?;
+        let default_material = material(&EMPTY)?;
+
+        let mut objects: Vec<Box<dyn Hittable>> = Vec::new();
+        let mut bounds: Option<Aabb> = None;
+        for (mesh, material) in instances.meshes {
+            let material = match material {
+                Some(material) => materials
+                    .get(material)
+                    .ok_or(format!("no material {}", material))?,
+                None => &default_material,
+            };
+            let mesh = Mesh::new(mesh, Rc::clone(material));
+            if let Some(mesh_bounds) = mesh.bounding_box() {
+                bounds =
+                    Some(bounds.map_or(mesh_bounds, |bounds| bounds.surrounding(&mesh_bounds)));
+            }
+            objects.push(Box::new(mesh));
+        }
+
+        let (center, radius) = bounds.map_or((Point3::new(0.0, 0.0, 0.0), 1.0), |bounds| {
+            let (minimum, maximum) = (bounds.get_minimum(), bounds.get_maximum());
+            (
+                bounds.centroid(),
+                (maximum.subtract(&minimum).length() / 2.0).max(f64::EPSILON),
+            )
+        });
+
+        // Without lights of its own the scene is lit by the sky.
+        let mut scene = Scene::new(if instances.lights.is_empty() {
+            Background::Sky
+        } else {
+            Background::Solid(BLACK)
+        });
+        for object in objects {
+            scene.add(object);
+        }
+        for (transform, light) in instances.lights {
+            scene.add_light(Box::new(self.light(light, &transform, &center, radius)?));
+        }
+
+        let view = match instances.camera {
+            Some((transform, camera)) => self.camera(camera, &transform, &center)?,
+            None => {
+                let vfov: f64 = 40.0;
+                let distance = radius / (vfov.to_radians() / 2.0).sin();
+                CameraView::new(
+                    center.add(&Vec3::new(0.0, 0.0, distance)),
+                    center,
+                    Vec3::new(0.0, 1.0, 0.0),
+                    vfov,
+                    0.0,
+                    distance,
+                )
+            }
+        };
+
+        Ok((scene, view))
+    }
+
+    fn element(&self, collection: &str, index: usize) -> Result<&Json, String> {
+        array(&self.json, collection)
+            .get(index)
+            .ok_or(format!("invalid index {} into {}", index, collection))
+    }
+
+    fn visit(
+        &self,
+        node_index: usize,
+        parent: &Matrix4,
+        instances: &mut Instances,
+    ) -> Result<(), String> {
+        let node = self.element("nodes", node_index)?;
+        if std::mem::replace(&mut instances.visited[node_index], true) {
+            return Err(format!("node {} has more than one parent", node_index));
+        }
+        let local = match node.get("matrix") {
+            // Stored column by column.
+            Some(_) => {
+                let values: [f64; 16] = numbers(node, "matrix", [0.0; 16])?;
+                Matrix4::new(std::array::from_fn(|row| {
+                    std::array::from_fn(|column| values[column * 4 + row])
+                }))
+            }
+            None => Matrix4::from_translation_rotation_scale(
+                &vector(numbers(node, "translation", [0.0; 3])?),
+                numbers(node, "rotation", [0.0, 0.0, 0.0, 1.0])?,
+                &vector(numbers(node, "scale", [1.0; 3])?),
+            ),
+        };
+        let transform = parent.multiply(&local);
+
+        if let Some(mesh) = index(node, "mesh")? {
+            for primitive in array(self.element("meshes", mesh)?, "primitives") {
+                if let Some(triangles) = self.primitive(primitive, &transform)? {
+                    instances
+                        .meshes
+                        .push((triangles, index(primitive, "material")?));
+                }
+            }
+        }
+        if let Some(camera) = index(node, "camera")? {
+            instances.camera.get_or_insert((transform, camera));
+        }
+        if let Some(light) = node
+            .get("extensions")
+            .and_then(|extensions| extensions.get("KHR_lights_punctual"))
+        {
+            instances
+                .lights
+                .push((transform, required_index(light, "light")?));
+        }
+
+        for child in array(node, "children") {
+            let child = child.as_usize().ok_or("invalid child index")?;
+            self.visit(child, &transform, instances)?;
+        }
+
+        Ok(())
+    }
+
+    /// The primitive's triangles in world space, or `None` if it is drawn as points or lines
+    /// or scaled down to nothing.
+    fn primitive(
+        &self,
+        primitive: &Json,
+        transform: &Matrix4,
+    ) -> Result<Option<TriangleMesh>, String> {
+        let Some(normal_matrix) = transform
+            .linear()
+            .inverse()
+            .map(|inverse| inverse.transpose())
+        else {
+            return Ok(None);
+        };
+        let attributes = primitive.get("attributes").unwrap_or(&EMPTY);
+
+        let positions: Vec<Point3> = self
+            .read::<3>(required_index(attributes, "POSITION")?)?
+            .into_iter()
+            .map(|position| transform.transform_point(&vector(position)))
+            .collect();
+        let vertices: Vec<usize> = match index(primitive, "indices")? {
+            Some(indices) => self
+                .read::<1>(indices)?
+                .into_iter()
+                .map(|[index]| index as usize)
+                .collect(),
+            None => (0..positions.len()).collect(),
+        };
+        if vertices.iter().any(|vertex| *vertex >= positions.len()) {
+            return Err("vertex index out of range".to_string());
+        }
+
+        let mut triangles: Vec<[usize; 3]> = match index(primitive, "mode")?.unwrap_or(TRIANGLES) {
+            TRIANGLES => vertices
+                .chunks_exact(3)
+                .map(|triangle| [triangle[0], triangle[1], triangle[2]])
+                .collect(),
+            // Every other triangle of a strip is reversed to keep the winding consistent.
+            TRIANGLE_STRIP => vertices
+                .windows(3)
+                .enumerate()
+                .map(|(k, window)| match k % 2 {
+                    0 => [window[0], window[1], window[2]],
+                    _ => [window[1], window[0], window[2]],
+                })
+                .collect(),
+            TRIANGLE_FAN => (1..vertices.len().saturating_sub(1))
+                .map(|k| [vertices[0], vertices[k], vertices[k + 1]])
+                .collect(),
+            _ => return Ok(None),
+        };
+        if triangles.is_empty() {
+            return Ok(None);
+        }
+        // A mirroring transform turns the triangles inside out.
+        if transform.linear().determinant() < 0.0 {
+            for triangle in &mut triangles {
+                triangle.swap(1, 2);
+            }
+        }
+
+        let vertex_count = positions.len();
+        let mut mesh = TriangleMesh::new(positions, triangles);
+        if let Some(normals) = index(attributes, "NORMAL")? {
+            let normals: Vec<Vec3> = self
+                .read::<3>(normals)?
+                .into_iter()
+                .map(|normal| normal_matrix.multiply_vector(&vector(normal)).unit_vector())
+                .collect();
+            if normals.len() != vertex_count {
+                return Err("NORMAL and POSITION have different counts".to_string());
+            }
+            mesh = mesh.with_normals(normals);
+        }
+        if let Some(uvs) = index(attributes, "TEXCOORD_0")? {
+            let uvs: Vec<(f64, f64)> = self
+                .read::<2>(uvs)?
+                .into_iter()
+                .map(|[u, v]| (u, v))
+                .collect();
+            if uvs.len() != vertex_count {
+                return Err("TEXCOORD_0 and POSITION have different counts".to_string());
+            }
+            mesh = mesh.with_uvs(uvs);
+        }
+
+        Ok(Some(mesh))
+    }
+
+    /// The elements of an accessor with `N` components each, converted to floating point.
+    fn read<const N: usize>(&self, accessor_index: usize) -> Result<Vec<[f64; N]>, String> {
+        let accessor = self.element("accessors", accessor_index)?;
+        let error = |message: &str| format!("accessor {} {}", accessor_index, message);
+
+        let components = match accessor.get("type").and_then(Json::as_str) {
+            Some("SCALAR") => 1,
+            Some("VEC2") => 2,
+            Some("VEC3") => 3,
+            Some("VEC4") => 4,
+            _ => return Err(error("has an unsupported type")),
+        };
+        if components != N {
+            return Err(error(&format!(
+                "has {} components, expected {}",
+                components, N
+            )));
+        }
+        if accessor.get("sparse").is_some() {
+            return Err(error("is sparse, which is not supported"));
+        }
+
+        let component_type = required_index(accessor, "componentType")?;
+        let (size, read): (usize, fn(&[u8]) -> f64) = match component_type {
+            5120 => (1, |bytes| f64::from(bytes[0] as i8)),
+            5121 => (1, |bytes| f64::from(bytes[0])),
+            5122 => (2, |bytes| {
+                f64::from(i16::from_le_bytes([bytes[0], bytes[1]]))
+            }),
+            5123 => (2, |bytes| {
+                f64::from(u16::from_le_bytes([bytes[0], bytes[1]]))
+            }),
+            5125 => (4, |bytes| {
+                f64::from(u32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]))
+            }),
+            5126 => (4, |bytes| {
+                f64::from(f32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]))
+            }),
+            _ => return Err(error("has an unsupported component type")),
+        };
+        // Normalized integers map onto 0 to 1, or -1 to 1 if signed.
+        let scale = match (
+            accessor.get("normalized").and_then(Json::as_bool),
+            component_type,
+        ) {
+            (Some(true), 5120) => Some(127.0),
+            (Some(true), 5121) => Some(255.0),
+            (Some(true), 5122) => Some(32767.0),
+            (Some(true), 5123) => Some(65535.0),
+            _ => None,
+        };
+
+        let count = required_index(accessor, "count")?;
+        // Without a buffer view every element is zero. Real data never has more elements than
+        // the buffers have bytes, which keeps a bogus count from exhausting memory.
+        let Some(view_index) = index(accessor, "bufferView")? else {
+            if count > self.buffers.iter().map(Vec::len).sum() {
+                return Err(error("is out of range"));
+            }
+            return Ok(vec![[0.0; N]; count]);
+        };
+        let view = self.element("bufferViews", view_index)?;
+        let buffer = self
+            .buffers
+            .get(required_index(view, "buffer")?)
+            .ok_or(format!("buffer view {} has no buffer", view_index))?;
+        let view_offset = index(view, "byteOffset")?.unwrap_or(0);
+        let data = view_offset
+            .checked_add(required_index(view, "byteLength")?)
+            .and_then(|view_end| buffer.get(view_offset..view_end))
+            .ok_or(format!("buffer view {} is out of range", view_index))?;
+
+        let element_size = size * N;
+        let stride = index(view, "byteStride")?.unwrap_or(element_size);
+        let offset = index(accessor, "byteOffset")?.unwrap_or(0);
+        let end = match count {
+            0 => Some(0),
+            _ => (count - 1)
+                .checked_mul(stride)
+                .and_then(|last| last.checked_add(offset))
+                .and_then(|last| last.checked_add(element_size)),
+        };
+        if end.is_none_or(|end| end > data.len()) {
+            return Err(error("is out of range"));
+        }
+
+        Ok((0..count)
+            .map(|element| {
+                let start = offset + element * stride;
+                std::array::from_fn(|component| {
+                    let value = read(&data[start + component * size..]);
+                    scale.map_or(value, |scale| (value / scale).max(-1.0))
+                })
+            })
+            .collect())
+    }
+
+    fn camera(
+        &self,
+        camera_index: usize,
+        transform: &Matrix4,
+        center: &Point3,
+    ) -> Result<CameraView, String> {
+        let camera = self.element("cameras", camera_index)?;
+        let look_from = transform.transform_point(&Point3::new(0.0, 0.0, 0.0));
+        let forward = transform
+            .transform_vector(&Vec3::new(0.0, 0.0, -1.0))
+            .unit_vector();
+        let vup = transform
+            .transform_vector(&Vec3::new(0.0, 1.0, 0.0))
+            .unit_vector();
+        // Focused on the middle of the scene, for when depth of field is turned on.
+        let focus_dist = center.subtract(&look_from).length().max(f64::EPSILON);
+        let look_at = look_from.add(&forward.multiply_constant(focus_dist));
+
+        match camera.get("type").and_then(Json::as_str) {
+            Some("perspective") => {
+                let perspective = camera.get("perspective").unwrap_or(&EMPTY);
+                let yfov = number(perspective, "yfov", 0.0)?;
+                if yfov <= 0.0 || yfov >= PI {
+                    return Err(format!("camera {} has an invalid yfov", camera_index));
+                }
+                Ok(CameraView::new(
+                    look_from,
+                    look_at,
+                    vup,
+                    yfov.to_degrees(),
+                    0.0,
+                    focus_dist,
+                ))
+            }
+            Some("orthographic") => {
+                let orthographic = camera.get("orthographic").unwrap_or(&EMPTY);
+                let ymag = number(orthographic, "ymag", 0.0)?;
+                if ymag <= 0.0 {
+                    return Err(format!("camera {} has an invalid ymag", camera_index));
+                }
+                let vfov = 2.0 * (ymag / focus_dist).atan().to_degrees();
+                Ok(
+                    CameraView::new(look_from, look_at, vup, vfov, 0.0, focus_dist)
+                        .with_projection(Projection::Orthographic),
+                )
+            }
+            _ => Err(format!("camera {} has an unknown type", camera_index)),
+        }
+    }
+
+    fn light(
+        &self,
+        light_index: usize,
+        transform: &Matrix4,
+        center: &Point3,
+        scene_radius: f64,
+    ) -> Result<SphereLight, String> {
+        let light = self
+            .json
+            .get("extensions")
+            .and_then(|extensions| extensions.get("KHR_lights_punctual"))
+            .map_or(&[][..], |lights| array(lights, "lights"))
+            .get(light_index)
+            .ok_or(format!("no light {}", light_index))?;
+        let color = vector(numbers(light, "color", [1.0; 3])?);
+        let intensity = number(light, "intensity", 1.0)?;
+
+        match light.get("type").and_then(Json::as_str) {
+            // A sphere of radiance L has an intensity of L times its cross section.
+            Some("point") => {
+                let position = transform.transform_point(&Point3::new(0.0, 0.0, 0.0));
+                let radius = LIGHT_RADIUS * scene_radius;
+                let emission = color.multiply_constant(intensity / (PI * radius * radius));
+                Ok(SphereLight::new(position, radius, emission))
+            }
+            // Illuminance in lux, from a sun whose solid angle is about pi times its angular
+            // radius squared.
+            Some("directional") => {
+                let direction = transform
+                    .transform_vector(&Vec3::new(0.0, 0.0, -1.0))
+                    .unit_vector();
+                let distance = SUN_DISTANCE * scene_radius;
+                let position = center.subtract(&direction.multiply_constant(distance));
+                let solid_angle = PI * SUN_ANGULAR_RADIUS * SUN_ANGULAR_RADIUS;
+                let emission = color.multiply_constant(intensity / solid_angle);
+                Ok(SphereLight::new(
+                    position,
+                    distance * SUN_ANGULAR_RADIUS,
+                    emission,
+                ))
+            }
+            Some("spot") => Err(format!(
+                "light {} is a spot light, which isn't supported",
+                light_index
+            )),
+            _ => Err(format!("light {} has an unknown type", light_index)),
+        }
+    }
+}
+
+fn material(material: &Json) -> Result<Rc<dyn Material>, String> {
+    let pbr = material.get("pbrMetallicRoughness").unwrap_or(&EMPTY);
+    let extensions = material.get("extensions").unwrap_or(&EMPTY);
+    let extension = |name: &str| extensions.get(name).unwrap_or(&EMPTY);
+
+    let [r, g, b, _] = numbers(pbr, "baseColorFactor", [1.0; 4])?;
+    let base_color = Color::new(r, g, b);
+    let emission =
+        vector(numbers(material, "emissiveFactor", [0.0; 3])?).multiply_constant(number(
+            extension("KHR_materials_emissive_strength"),
+            "emissiveStrength",
+            1.0,
+        )?);
+    let transmission = number(
+        extension("KHR_materials_transmission"),
+        "transmissionFactor",
+        0.0,
+    )?;
+
+    Ok(if emission.square() > 0.0 {
+        Rc::new(DiffuseLight::new(emission))
+    } else if transmission >= 0.5 {
+        Rc::new(Dielectric::new(number(
+            extension("KHR_materials_ior"),
+            "ior",
+            1.5,
+        )?))
+    } else if number(pbr, "metallicFactor", 1.0)? >= 0.5 {
+        Rc::new(Metal::new(base_color, number(pbr, "roughnessFactor", 1.0)?))
+    } else {
+        Rc::new(Lambertian::new(base_color))
+    })
+}
+
+/// The array called `key`, empty if it is missing.
+fn array<'a>(json: &'a Json, key: &str) -> &'a [Json] {
+    json.get(key).and_then(Json::as_array).unwrap_or_default()
+}
+
+fn index(json: &Json, key: &str) -> Result<Option<usize>, String> {
+    json.get(key)
+        .map(|value| {
+            value
+                .as_usize()
+                .ok_or(format!("{} is not a valid index or count", key))
+        })
+        .transpose()
+}
+
+fn required_index(json: &Json, key: &str) -> Result<usize, String> {
+    index(json, key)?.ok_or(format!("missing {}", key))
+}
+
+fn number(json: &Json, key: &str, default: f64) -> Result<f64, String> {
+    json.get(key).map_or(Ok(default), |value| {
+        value.as_f64().ok_or(format!("{} is not a number", key))
+    })
+}
+
+fn numbers<const N: usize>(json: &Json, key: &str, default: [f64; N]) -> Result<[f64; N], String> {
+    let Some(value) = json.get(key) else {
+        return Ok(default);
+    };
+
+    let invalid = || format!("{} is not an array of {} numbers", key, N);
+    let values = value
+        .as_array()
+        .filter(|values| values.len() == N)
+        .ok_or_else(invalid)?;
+    let mut numbers = [0.0; N];
+    for (number, value) in numbers.iter_mut().zip(values) {
+        *number = value.as_f64().ok_or_else(invalid)?;
+    }
+
+    Ok(numbers)
+}
+
+fn vector([x, y, z]: [f64; 3]) -> Vec3 {
+    Vec3::new(x, y, z)
+}
+
+/// The JSON and binary chunks of a GLB file.
+fn split_glb(bytes: &[u8]) -> Result<(&[u8], Option<&[u8]>), String> {
+    let word = |offset: usize| {
+        bytes
+            .get(offset..offset + 4)
+            .map(|word| u32::from_le_bytes([word[0], word[1], word[2], word[3]]) as usize)
+            .ok_or("truncated GLB file".to_string())
+    };
+    if word(4)? != 2 {
+        return Err(format!("unsupported GLB version {}", word(4)?));
+    }
+
+    let length = word(8)?.min(bytes.len());
+    let mut offset = 12;
+    let mut chunks = Vec::new();
+    while offset + 8 <= length {
+        let chunk_length = word(offset)?;
+        let chunk_type = word(offset + 4)? as u32;
+        let data = bytes
+            .get(offset + 8..offset + 8 + chunk_length)
+            .ok_or("truncated GLB chunk".to_string())?;
+        chunks.push((chunk_type, data));
+        offset += 8 + chunk_length;
+    }
+
+    match chunks[..] {
+        [(JSON_CHUNK, json), (BINARY_CHUNK, binary), ..] => Ok((json, Some(binary))),
+        [(JSON_CHUNK, json), ..] => Ok((json, None)),
+        _ => Err("GLB file does not start with a JSON chunk".to_string()),
+    }
+}
+
+/// The contents of a `data:` URI, given without the scheme.
+fn decode_data_uri(uri: &str) -> Result<Vec<u8>, String> {
+    let (header, data) = uri.split_once(',').ok_or("invalid data URI")?;
+    if !header.ends_with(";base64") {
+        return Err("data URIs must be base64 encoded".to_string());
+    }
+
+    let mut bytes = Vec::with_capacity(data.len() * 3 / 4);
+    let mut bits = 0u32;
+    let mut count = 0;
+    for character in data.bytes().take_while(|character| *character != b'=') {
+        let value = match character {
+            b'A'..=b'Z' => character - b'A',
+            b'a'..=b'z' => character - b'a' + 26,
+            b'0'..=b'9' => character - b'0' + 52,
+            b'+' | b'-' => 62,
+            b'/' | b'_' => 63,
+            _ => return Err("invalid base64 in data URI".to_string()),
+        };
+        bits = bits << 6 | u32::from(value);
+        count += 6;
+        if count >= 8 {
+            count -= 8;
+            bytes.push((bits >> count) as u8);
+        }
+    }
+
+    Ok(bytes)
+}
+
+/// Relative URIs may escape characters such as spaces in file names.
+fn percent_decode(uri: &str) -> String {
+    let bytes = uri.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        let escaped = bytes
+            .get(i + 1..i + 3)
+            .filter(|_| bytes[i] == b'%')
+            .and_then(|digits| std::str::from_utf8(digits).ok())
+            .and_then(|digits| u8::from_str_radix(digits, 16).ok());
+        match escaped {
+            Some(byte) => {
+                decoded.push(byte);
+                i += 3;
+            }
+            None => {
+                decoded.push(bytes[i]);
+                i += 1;
+            }
+        }
+    }
+
+    String::from_utf8_lossy(&decoded).into_owned()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{env, fs, process};
+
+    use super::*;
+
+    fn load(name: &str, contents: &str) -> Result<(Scene, CameraView), String> {
+        let path = env::temp_dir().join(format!("gltf_{}_{}.gltf", name, process::id()));
+        fs::write(&path, contents).unwrap();
+        let result = Gltf::load(&path.to_string_lossy()).and_then(|gltf| gltf.scene());
+        fs::remove_file(&path).unwrap();
+        result
+    }
+
+    fn lights(light_type: &str) -> String {
+        format!(
+            r#"{{
+                "asset": {{"version": "2.0"}},
+                "extensionsUsed": ["KHR_lights_punctual"],
+                "extensions": {{"KHR_lights_punctual": {{"lights": [{{"type": "{}"}}]}}}},
+                "nodes": [{{"extensions": {{"KHR_lights_punctual": {{"light": 0}}}}}}]
+            }}"#,
+            light_type
+        )
+    }
+
+    #[test]
+    fn point_lights_become_spheres() {
+        assert!(load("point", &lights("point")).is_ok());
+    }
+
+    #[test]
+    fn spot_lights_are_rejected() {
+        let error = load("spot", &lights("spot")).err().unwrap();
+        assert!(
+            error.ends_with("light 0 is a spot light, which isn't supported"),
+            "{}",
+            error
+        );
+    }
+}
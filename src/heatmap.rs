@@ -0,0 +1,38 @@
+use std::{
+    fs::File,
+    io::{self, BufWriter, Write},
+};
+
+use crate::{color::MultipleSamplesColor, film::Film};
+
+pub fn write_heatmap(path: &str, film: &Film) -> io::Result<()> {
+    let mut file = BufWriter::new(File::create(path)?);
+    let pixels = film.get_pixels();
+    let max_samples = pixels
+        .iter()
+        .map(MultipleSamplesColor::get_samples_count)
+        .max()
+        .unwrap_or(1)
+        .max(1);
+
+    writeln!(file, "P3")?;
+    writeln!(file, "{} {}", film.get_width(), film.get_height())?;
+    writeln!(file, "255")?;
+
+    for pixel in pixels {
+        let t = pixel.get_samples_count() as f64 / max_samples as f64;
+        let (r, g, b) = ramp(t);
+        writeln!(file, "{} {} {}", r, g, b)?;
+    }
+
+    Ok(())
+}
+
+fn ramp(t: f64) -> (u8, u8, u8) {
+    let channel = |value: f64| (value.clamp(0.0, 1.0) * 255.0) as u8;
+    (
+        channel(2.0 * t - 0.5),
+        channel(1.0 - (2.0 * t - 1.0).abs()),
+        channel(1.5 - 2.0 * t),
+    )
+}
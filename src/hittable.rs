@@ -1,6 +1,8 @@
 use std::rc::Rc;
 
 use crate::{
+    aabb::Aabb,
+    color::Color,
     material::Material,
     ray::Ray,
     vec3::{Point3, Vec3},
@@ -10,8 +12,12 @@ pub struct HitRecord {
     point: Point3,
     normal: Vec3,
     t: f64,
+    u: f64,
+    v: f64,
     material: Rc<dyn Material>,
     front_face: bool,
+    tangent: Option<Vec3>,
+    color: Option<Color>,
 }
 
 impl HitRecord {
@@ -19,6 +25,7 @@ impl HitRecord {
         point: Point3,
         outward_normal: Vec3,
         t: f64,
+        (u, v): (f64, f64),
         ray: &Ray,
         material: Rc<dyn Material>,
     ) -> Self {
@@ -32,8 +39,28 @@ impl HitRecord {
             point,
             normal,
             t,
+            u,
+            v,
             material,
             front_face,
+            tangent: None,
+            color: None,
+        }
+    }
+
+    /// Records the direction along a fiber, for materials like hair that are oriented by it.
+    pub fn with_tangent(self, tangent: Vec3) -> Self {
+        Self {
+            tangent: Some(tangent),
+            ..self
+        }
+    }
+
+    /// Records the color interpolated from a mesh's vertices, which tints lambertian materials.
+    pub fn with_color(self, color: Color) -> Self {
+        Self {
+            color: Some(color),
+            ..self
         }
     }
 
@@ -49,6 +76,14 @@ impl HitRecord {
         self.t
     }
 
+    pub const fn get_u(&self) -> f64 {
+        self.u
+    }
+
+    pub const fn get_v(&self) -> f64 {
+        self.v
+    }
+
     pub fn get_material(&self) -> Rc<dyn Material> {
         Rc::clone(&self.material)
     }
@@ -56,8 +91,83 @@ impl HitRecord {
     pub const fn get_front_face(&self) -> bool {
         self.front_face
     }
+
+    pub const fn get_tangent(&self) -> Option<Vec3> {
+        self.tangent
+    }
+
+    pub const fn get_color(&self) -> Option<Color> {
+        self.color
+    }
+
+    /// The same hit carried to `point`, with `normal` facing the same side as before.
+    pub fn transformed(self, point: Point3, normal: Vec3) -> Self {
+        Self {
+            point,
+            normal,
+            ..self
+        }
+    }
+
+    /// Swaps in another material, for geometry shared between materials.
+    pub fn with_material(self, material: Rc<dyn Material>) -> Self {
+        Self { material, ..self }
+    }
+
+    /// The same hit on a surface whose inside and outside are swapped, as where a CSG
+    /// difference carves one solid out of another.
+    pub fn flip_face(self) -> Self {
+        Self {
+            front_face: !self.front_face,
+            ..self
+        }
+    }
 }
 
 pub trait Hittable {
     fn hit(&self, ray: &Ray, t_min: f64, t_max: f64) -> Option<HitRecord>;
+
+    /// `None` for unbounded objects such as infinite planes.
+    fn bounding_box(&self) -> Option<Aabb>;
+}
+
+/// The first of the records, sorted by `t`, that lies within [t_min, t_max].
+pub fn first_in_range(records: Vec<HitRecord>, t_min: f64, t_max: f64) -> Option<HitRecord> {
+    let range = t_min..=t_max;
+    records
+        .into_iter()
+        .find(|record| range.contains(&record.get_t()))
+}
+
+/// A stretch of a ray inside a solid, with the hits where it enters and exits.
+pub struct Interval {
+    enter: HitRecord,
+    exit: HitRecord,
+}
+
+impl Interval {
+    pub const fn new(enter: HitRecord, exit: HitRecord) -> Self {
+        Self { enter, exit }
+    }
+
+    pub fn into_records(self) -> (HitRecord, HitRecord) {
+        (self.enter, self.exit)
+    }
+}
+
+/// A closed object that can report every interval of a ray inside it, which CSG needs.
+pub trait Solid: Hittable {
+    /// Sorted, disjoint intervals over the whole line of the ray, including negative `t`.
+    fn intervals(&self, ray: &Ray) -> Vec<Interval>;
+}
+
+/// Pairs the sorted surface hits of a closed shape into intervals, entering at every other hit.
+pub fn pair_intervals(records: Vec<HitRecord>) -> Vec<Interval> {
+    let mut records = records.into_iter();
+    let mut intervals = Vec::new();
+    while let (Some(enter), Some(exit)) = (records.next(), records.next()) {
+        intervals.push(Interval::new(enter, exit));
+    }
+
+    intervals
 }
@@ -1,10 +1,13 @@
+use std::rc::Rc;
+
 use crate::{
+    aabb::Aabb,
     hittable::{HitRecord, Hittable},
     ray::Ray,
 };
 
 pub struct HittableList {
-    objects: Vec<Box<dyn Hittable>>,
+    objects: Vec<Rc<dyn Hittable>>,
 }
 
 impl HittableList {
@@ -12,11 +15,12 @@ impl HittableList {
         Self { objects: vec![] }
     }
 
+    #[allow(dead_code)]
     pub fn clear(&mut self) {
         self.objects.clear();
     }
 
-    pub fn add(&mut self, object: Box<dyn Hittable>) {
+    pub fn add(&mut self, object: Rc<dyn Hittable>) {
         self.objects.push(object);
     }
 }
@@ -37,4 +41,13 @@ impl Hittable for HittableList {
             }
         })
     }
+
+    /// `None` when the list is empty or holds an unbounded object.
+    fn bounding_box(&self) -> Option<Aabb> {
+        let (first, rest) = self.objects.split_first()?;
+        rest.iter()
+            .try_fold(first.bounding_box()?, |bounds, object| {
+                Some(bounds.surrounding(&object.bounding_box()?))
+            })
+    }
 }
@@ -0,0 +1,45 @@
+use crate::{
+    color::{Color, WHITE},
+    constants::INFINITY,
+    ray::Ray,
+    sampler::Sampler,
+    scene::Scene,
+    utils::sample_cosine_direction,
+};
+
+use super::{Integrator, T_MIN};
+
+pub struct AmbientOcclusionIntegrator {
+    samples: usize,
+    max_distance: f64,
+}
+
+impl AmbientOcclusionIntegrator {
+    pub const fn new(samples: usize, max_distance: f64) -> Self {
+        Self {
+            samples,
+            max_distance,
+        }
+    }
+}
+
+impl Integrator for AmbientOcclusionIntegrator {
+    fn radiance(&self, ray: &Ray, scene: &Scene, sampler: &mut dyn Sampler) -> Color {
+        let Some(res) = scene.get_world().hit(ray, T_MIN, INFINITY) else {
+            return WHITE;
+        };
+
+        let unoccluded = (0..self.samples)
+            .filter(|_| {
+                let direction = sample_cosine_direction(&res.get_normal(), sampler.get_2d());
+                let probe = Ray::new(res.get_point(), direction.unit_vector());
+                scene
+                    .get_world()
+                    .hit(&probe, T_MIN, self.max_distance)
+                    .is_none()
+            })
+            .count();
+
+        WHITE.multiply_constant(unoccluded as f64 / self.samples as f64)
+    }
+}
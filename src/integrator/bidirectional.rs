@@ -0,0 +1,599 @@
+use crate::{
+    camera::Camera,
+    color::{Color, BLACK, WHITE},
+    constants::{INFINITY, PI},
+    film::Film,
+    hittable::HitRecord,
+    progress::ProgressTracker,
+    ray::Ray,
+    sampler::Sampler,
+    scene::Scene,
+    settings::RenderSettings,
+    utils::sample_cosine_direction,
+    vec3::{Point3, Vec3},
+};
+
+use super::{sample_pixels, Renderer, T_MIN};
+
+enum VertexKind {
+    /// With the density of camera rays per unit solid angle towards the next vertex, which is
+    /// also the camera's importance.
+    Camera(f64),
+    Light(Color),
+    Surface(HitRecord),
+}
+
+struct Vertex {
+    kind: VertexKind,
+    point: Point3,
+    normal: Option<Vec3>,
+    wo: Vec3,
+    beta: Color,
+    pdf_fwd: f64,
+    pdf_rev: f64,
+    delta: bool,
+}
+
+impl Vertex {
+    fn camera(origin: Point3, density: f64) -> Self {
+        Self {
+            kind: VertexKind::Camera(density),
+            point: origin,
+            normal: None,
+            wo: Vec3::empty(),
+            beta: WHITE,
+            pdf_fwd: 1.0,
+            pdf_rev: 0.0,
+            delta: false,
+        }
+    }
+
+    fn light(emission: Color, point: Point3, normal: Vec3, beta: Color, pdf_fwd: f64) -> Self {
+        Self {
+            kind: VertexKind::Light(emission),
+            point,
+            normal: Some(normal),
+            wo: normal,
+            beta,
+            pdf_fwd,
+            pdf_rev: 0.0,
+            delta: false,
+        }
+    }
+
+    fn surface(hit_record: HitRecord, ray: &Ray, beta: Color) -> Self {
+        Self {
+            point: hit_record.get_point(),
+            normal: Some(hit_record.get_normal()),
+            wo: ray.get_direction().unit_vector().negative(),
+            beta,
+            pdf_fwd: 0.0,
+            pdf_rev: 0.0,
+            delta: hit_record.get_material().is_specular(),
+            kind: VertexKind::Surface(hit_record),
+        }
+    }
+
+    fn f(&self, next: &Vertex) -> Color {
+        let wi = next.point.subtract(&self.point).unit_vector();
+        match &self.kind {
+            VertexKind::Surface(hit_record) => {
+                hit_record.get_material().eval(hit_record, &self.wo, &wi)
+            }
+            _ => BLACK,
+        }
+    }
+
+    fn emitted(&self, towards: &Vertex) -> Color {
+        match &self.kind {
+            VertexKind::Light(emission) => {
+                let direction = towards.point.subtract(&self.point);
+                if self.normal.map_or(0.0, |normal| normal.dot(&direction)) > 0.0 {
+                    *emission
+                } else {
+                    BLACK
+                }
+            }
+            VertexKind::Surface(hit_record) => hit_record.get_material().emitted(hit_record),
+            VertexKind::Camera(_) => BLACK,
+        }
+    }
+
+    fn convert_density(&self, pdf: f64, next: &Vertex) -> f64 {
+        let w = next.point.subtract(&self.point);
+        let inv_dist2 = 1.0 / w.square();
+        let cos = next.normal.map_or(1.0, |normal| {
+            normal.dot(&w.multiply_constant(inv_dist2.sqrt())).abs()
+        });
+
+        pdf * cos * inv_dist2
+    }
+
+    fn pdf(&self, prev: Option<&Vertex>, next: &Vertex) -> f64 {
+        match &self.kind {
+            VertexKind::Light(_) => self.pdf_light(next),
+            VertexKind::Surface(hit_record) => {
+                let wo = prev.map_or(self.wo, |prev| {
+                    prev.point.subtract(&self.point).unit_vector()
+                });
+                let wi = next.point.subtract(&self.point).unit_vector();
+                let pdf = hit_record.get_material().pdf(hit_record, &wo, &wi);
+                self.convert_density(pdf, next)
+            }
+            VertexKind::Camera(density) => self.convert_density(*density, next),
+        }
+    }
+
+    fn pdf_light(&self, next: &Vertex) -> f64 {
+        let w = next.point.subtract(&self.point).unit_vector();
+        let cos = self.normal.map_or(0.0, |normal| normal.dot(&w)).max(0.0);
+        self.convert_density(cos / PI, next)
+    }
+
+    fn pdf_light_origin(&self, scene: &Scene) -> f64 {
+        match &self.kind {
+            VertexKind::Surface(hit_record) => scene.light_pdf(&hit_record.get_material()),
+            _ => 0.0,
+        }
+    }
+}
+
+pub struct BidirectionalIntegrator {
+    max_depth: usize,
+}
+
+impl BidirectionalIntegrator {
+    pub const fn new(max_depth: usize) -> Self {
+        Self { max_depth }
+    }
+
+    /// `uv_density` is the density of image points per unit of `uv` area, which turns the
+    /// camera's image density into a density of rays.
+    fn camera_subpath(
+        &self,
+        ray: &Ray,
+        scene: &Scene,
+        camera: &dyn Camera,
+        uv_density: f64,
+        sampler: &mut dyn Sampler,
+    ) -> (Vec<Vertex>, Color) {
+        let density = camera.image_density(&ray.get_direction()) * uv_density;
+        let mut path = vec![Vertex::camera(ray.get_origin(), density)];
+        let escaped = random_walk(
+            scene,
+            *ray,
+            WHITE,
+            density,
+            self.max_depth,
+            &mut path,
+            sampler,
+        );
+        (path, escaped)
+    }
+
+    fn light_subpath(&self, scene: &Scene, sampler: &mut dyn Sampler) -> Vec<Vertex> {
+        let Some((light, pdf_choice)) = scene.sample_light(sampler) else {
+            return vec![];
+        };
+
+        let (point, normal) = light.sample_point(sampler);
+        let direction = sample_cosine_direction(&normal, sampler.get_2d());
+        let pdf_pos = pdf_choice * light.pdf_point();
+        let pdf_dir = normal.dot(&direction.unit_vector()) / PI;
+        let emission = light.get_emission();
+
+        let vertex = Vertex::light(
+            emission,
+            point,
+            normal,
+            emission.divide_constant(pdf_pos),
+            pdf_pos,
+        );
+        let beta = vertex
+            .beta
+            .multiply_constant(normal.dot(&direction.unit_vector()) / pdf_dir);
+
+        let mut path = vec![vertex];
+        if pdf_dir > 0.0 && self.max_depth > 1 {
+            let ray = Ray::new(point, direction);
+            random_walk(
+                scene,
+                ray,
+                beta,
+                pdf_dir,
+                self.max_depth - 1,
+                &mut path,
+                sampler,
+            );
+        }
+
+        path
+    }
+
+    fn connect(
+        &self,
+        scene: &Scene,
+        light_path: &[Vertex],
+        camera_path: &[Vertex],
+        s: usize,
+        t: usize,
+        sampler: &mut dyn Sampler,
+    ) -> Color {
+        let pt = &camera_path[t - 1];
+        let mut sampled = None;
+
+        let contribution = if s == 0 {
+            pt.beta.multiply(&pt.emitted(&camera_path[t - 2]))
+        } else if s == 1 {
+            if pt.delta {
+                return BLACK;
+            }
+            let Some((light, pdf_choice)) = scene.sample_light(sampler) else {
+                return BLACK;
+            };
+
+            let (point, normal) = light.sample_point(sampler);
+            let pdf_pos = pdf_choice * light.pdf_point();
+            let emission = light.get_emission();
+            let vertex = Vertex::light(
+                emission,
+                point,
+                normal,
+                emission.divide_constant(pdf_pos),
+                pdf_pos,
+            );
+
+            let contribution = pt
+                .beta
+                .multiply(&pt.f(&vertex))
+                .multiply(&vertex.emitted(pt))
+                .divide_constant(pdf_pos)
+                .multiply_constant(geometry(scene, pt, &vertex));
+            sampled = Some(vertex);
+            contribution
+        } else {
+            let qs = &light_path[s - 1];
+            if qs.delta || pt.delta {
+                return BLACK;
+            }
+
+            qs.beta
+                .multiply(&qs.f(pt))
+                .multiply(&pt.f(qs))
+                .multiply(&pt.beta)
+                .multiply_constant(geometry(scene, qs, pt))
+        };
+
+        if contribution.near_zero() {
+            return BLACK;
+        }
+
+        contribution.multiply_constant(mis_weight(
+            scene,
+            light_path,
+            camera_path,
+            sampled.as_ref(),
+            s,
+            t,
+        ))
+    }
+
+    /// Joins the first `s` vertices of the light path to `lens`, a camera vertex sampled
+    /// towards the end of the light path (t = 1).
+    fn connect_to_lens(
+        &self,
+        scene: &Scene,
+        light_path: &[Vertex],
+        camera_path: &[Vertex],
+        s: usize,
+        lens: &Vertex,
+    ) -> Color {
+        let VertexKind::Camera(importance) = lens.kind else {
+            unreachable!()
+        };
+
+        let qs = &light_path[s - 1];
+        let radiance = if s == 1 {
+            qs.emitted(lens).divide_constant(qs.pdf_fwd)
+        } else {
+            qs.beta.multiply(&qs.f(lens))
+        };
+        let contribution = radiance.multiply_constant(geometry(scene, qs, lens) * importance);
+        if contribution.near_zero() {
+            return BLACK;
+        }
+
+        contribution.multiply_constant(mis_weight(scene, light_path, camera_path, Some(lens), s, 1))
+    }
+
+    /// The direct and indirect light along `ray`, splatting light paths that reach the lens
+    /// onto `film`.
+    fn radiance_components(
+        &self,
+        ray: &Ray,
+        scene: &Scene,
+        camera: &dyn Camera,
+        film: &mut Film,
+        sampler: &mut dyn Sampler,
+    ) -> (Color, Color) {
+        let uv_density = film.uv_density();
+        let (camera_path, escaped) = self.camera_subpath(ray, scene, camera, uv_density, sampler);
+        let light_path = self.light_subpath(scene, sampler);
+        film.add_light_paths(1);
+
+        let (mut direct, mut indirect) = if camera_path.len() <= 2 {
+            (escaped, BLACK)
+        } else {
+            (BLACK, escaped)
+        };
+        for t in 2..=camera_path.len() {
+            for s in 0..=light_path.len() {
+                if s + t - 2 > self.max_depth {
+                    break;
+                }
+
+                let contribution = self.connect(scene, &light_path, &camera_path, s, t, sampler);
+                if s + t - 2 <= 1 {
+                    direct = direct.add(&contribution);
+                } else {
+                    indirect = indirect.add(&contribution);
+                }
+            }
+        }
+
+        for s in 1..=light_path.len().min(self.max_depth + 1) {
+            let qs = &light_path[s - 1];
+            if qs.delta {
+                continue;
+            }
+            let Some(sample) = camera.sample_lens(&qs.point, sampler) else {
+                continue;
+            };
+
+            // The camera's importance per unit solid angle equals its density of rays.
+            let lens = Vertex::camera(sample.get_point(), sample.get_density() * uv_density);
+            let contribution = self.connect_to_lens(scene, &light_path, &camera_path, s, &lens);
+            film.add_splat(sample.get_uv(), &contribution);
+        }
+
+        (direct, indirect)
+    }
+}
+
+impl Renderer for BidirectionalIntegrator {
+    fn render_pass(
+        &self,
+        scene: &Scene,
+        camera: &dyn Camera,
+        settings: &RenderSettings,
+        sampler: &mut dyn Sampler,
+        film: &mut Film,
+        progress: &mut ProgressTracker,
+    ) -> bool {
+        sample_pixels(
+            scene,
+            camera,
+            settings,
+            sampler,
+            film,
+            progress,
+            |ray, sampler, film| self.radiance_components(ray, scene, camera, film, sampler),
+        )
+    }
+}
+
+fn random_walk(
+    scene: &Scene,
+    mut ray: Ray,
+    mut beta: Color,
+    mut pdf_fwd: f64,
+    max_depth: usize,
+    path: &mut Vec<Vertex>,
+    sampler: &mut dyn Sampler,
+) -> Color {
+    for _ in 0..max_depth {
+        let Some(hit_record) = scene.get_world().hit(&ray, T_MIN, INFINITY) else {
+            return beta.multiply(&scene.background(&ray));
+        };
+
+        let material = hit_record.get_material();
+        let scatter_result = material.scatter(&ray, &hit_record, sampler);
+        let mut vertex = Vertex::surface(hit_record, &ray, beta);
+        let prev = path.last_mut().expect("random walk starts from a vertex");
+        vertex.pdf_fwd = prev.convert_density(pdf_fwd, &vertex);
+
+        let Some(scatter_result) = scatter_result else {
+            path.push(vertex);
+            break;
+        };
+
+        let wi = scatter_result.get_ray().get_direction().unit_vector();
+        let pdf_rev = if vertex.delta {
+            pdf_fwd = 0.0;
+            0.0
+        } else {
+            let VertexKind::Surface(hit_record) = &vertex.kind else {
+                unreachable!()
+            };
+            pdf_fwd = material.pdf(hit_record, &vertex.wo, &wi);
+            material.pdf(hit_record, &wi, &vertex.wo)
+        };
+
+        prev.pdf_rev = vertex.convert_density(pdf_rev, prev);
+        beta = beta.multiply(&scatter_result.get_color());
+        ray = *scatter_result.get_ray();
+        path.push(vertex);
+    }
+
+    BLACK
+}
+
+fn geometry(scene: &Scene, a: &Vertex, b: &Vertex) -> f64 {
+    let w = b.point.subtract(&a.point);
+    let distance = w.length();
+    let direction = w.divide_constant(distance);
+
+    let shadow_ray = Ray::new(a.point, direction);
+    if scene
+        .get_world()
+        .hit(&shadow_ray, T_MIN, distance - T_MIN)
+        .is_some()
+    {
+        return 0.0;
+    }
+
+    let cos_a = a.normal.map_or(1.0, |normal| normal.dot(&direction).abs());
+    let cos_b = b.normal.map_or(1.0, |normal| normal.dot(&direction).abs());
+    cos_a * cos_b / (distance * distance)
+}
+
+fn mis_weight(
+    scene: &Scene,
+    light_path: &[Vertex],
+    camera_path: &[Vertex],
+    sampled: Option<&Vertex>,
+    s: usize,
+    t: usize,
+) -> f64 {
+    // `sampled` stands in for the light path's first vertex when s = 1, or for the camera's
+    // when t = 1.
+    let qs = match s {
+        0 => None,
+        1 if t > 1 => sampled,
+        _ => Some(&light_path[s - 1]),
+    };
+    let qs_minus = if s > 1 {
+        Some(&light_path[s - 2])
+    } else {
+        None
+    };
+    let pt = match t {
+        1 => sampled.expect("joining to the lens samples a camera vertex"),
+        _ => &camera_path[t - 1],
+    };
+    let pt_minus = (t > 1).then(|| &camera_path[t - 2]);
+
+    let densities = |vertex: &Vertex| (vertex.pdf_fwd, vertex.pdf_rev, vertex.delta);
+    let mut camera: Vec<_> = camera_path[..t].iter().map(densities).collect();
+    let mut light: Vec<_> = light_path[..s].iter().map(densities).collect();
+    match (s, t, sampled) {
+        (_, 1, Some(sampled)) => camera[0] = densities(sampled),
+        (1, _, Some(sampled)) => light[0] = densities(sampled),
+        _ => {}
+    }
+
+    camera[t - 1].1 = qs.map_or_else(|| pt.pdf_light_origin(scene), |qs| qs.pdf(qs_minus, pt));
+    camera[t - 1].2 = false;
+    if let Some(pt_minus) = pt_minus {
+        camera[t - 2].1 =
+            qs.map_or_else(|| pt.pdf_light(pt_minus), |qs| pt.pdf(Some(qs), pt_minus));
+    }
+    if let Some(qs) = qs {
+        light[s - 1].1 = pt.pdf(pt_minus, qs);
+        light[s - 1].2 = false;
+    }
+    if let (Some(qs), Some(qs_minus)) = (qs, qs_minus) {
+        light[s - 2].1 = qs.pdf(Some(pt), qs_minus);
+    }
+
+    let remap = |pdf: f64| if pdf != 0.0 { pdf } else { 1.0 };
+    let mut sum = 0.0;
+
+    let mut ratio = 1.0;
+    for i in (1..t).rev() {
+        ratio *= remap(camera[i].1) / remap(camera[i].0);
+        if !camera[i].2 && !camera[i - 1].2 {
+            sum += ratio;
+        }
+    }
+
+    let mut ratio = 1.0;
+    for i in (0..s).rev() {
+        ratio *= remap(light[i].1) / remap(light[i].0);
+        let delta_light = i > 0 && light[i - 1].2;
+        if !light[i].2 && !delta_light {
+            sum += ratio;
+        }
+    }
+
+    1.0 / (1.0 + sum)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        camera::PerspectiveCamera, filter::BoxFilter, integrator::PathIntegrator,
+        light::SphereLight, material::Lambertian, sampler::RandomSampler, scene::Background,
+        sphere::Sphere,
+    };
+    use std::rc::Rc;
+
+    fn diffuse_scene() -> Scene {
+        let mut scene = Scene::new(Background::Solid(BLACK));
+        let gray = Rc::new(Lambertian::new(Color::new(0.5, 0.5, 0.5)));
+        scene.add(Box::new(Sphere::new(
+            Point3::new(0.0, -100.5, -1.0),
+            100.0,
+            gray.clone(),
+        )));
+        scene.add(Box::new(Sphere::new(
+            Point3::new(0.0, 0.0, -1.0),
+            0.5,
+            gray,
+        )));
+        scene.add_light(Box::new(SphereLight::new(
+            Point3::new(1.0, 1.5, 0.0),
+            0.5,
+            Color::new(4.0, 4.0, 4.0),
+        )));
+        scene
+    }
+
+    fn image_average(renderer: &dyn Renderer, scene: &Scene, camera: &dyn Camera) -> Color {
+        let settings = RenderSettings::new(256, None, None);
+        let mut film = Film::new(16, 12, Box::new(BoxFilter::new(0.5)));
+        renderer.render(
+            scene,
+            camera,
+            &settings,
+            &mut RandomSampler::new(),
+            &mut film,
+            &mut ProgressTracker::new(|_| {}),
+        );
+        let colors = film.get_colors();
+        let count = colors.len() as f64;
+        colors
+            .iter()
+            .fold(BLACK, |sum, color| sum.add(color))
+            .divide_constant(count)
+    }
+
+    #[test]
+    fn matches_path_tracing() {
+        let scene = diffuse_scene();
+        let camera = PerspectiveCamera::new(
+            Point3::new(0.0, 0.5, 2.0),
+            Point3::new(0.0, 0.0, -1.0),
+            Vec3::new(0.0, 1.0, 0.0),
+            60.0,
+            0.0,
+            3.0,
+        );
+
+        let expected = image_average(&PathIntegrator::new(5), &scene, &camera);
+        let actual = image_average(&BidirectionalIntegrator::new(5), &scene, &camera);
+        for (actual, expected) in [
+            (actual.get_x(), expected.get_x()),
+            (actual.get_y(), expected.get_y()),
+            (actual.get_z(), expected.get_z()),
+        ] {
+            assert!(
+                (actual - expected).abs() < 0.05 * expected,
+                "{} != {}",
+                actual,
+                expected
+            );
+        }
+    }
+}
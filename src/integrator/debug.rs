@@ -0,0 +1,70 @@
+use crate::{
+    aov::material_id_color,
+    color::{Color, BLACK, WHITE},
+    constants::INFINITY,
+    ray::Ray,
+    sampler::Sampler,
+    scene::Scene,
+};
+
+use super::{Integrator, T_MIN};
+
+pub struct NormalIntegrator;
+
+impl Integrator for NormalIntegrator {
+    fn radiance(&self, ray: &Ray, scene: &Scene, _sampler: &mut dyn Sampler) -> Color {
+        scene
+            .get_world()
+            .hit(ray, T_MIN, INFINITY)
+            .map_or(BLACK, |res| {
+                res.get_normal()
+                    .unit_vector()
+                    .add_constant(1.0)
+                    .multiply_constant(0.5)
+            })
+    }
+}
+
+pub struct DepthIntegrator {
+    max_distance: f64,
+}
+
+impl DepthIntegrator {
+    pub const fn new(max_distance: f64) -> Self {
+        Self { max_distance }
+    }
+}
+
+impl Integrator for DepthIntegrator {
+    fn radiance(&self, ray: &Ray, scene: &Scene, _sampler: &mut dyn Sampler) -> Color {
+        scene
+            .get_world()
+            .hit(ray, T_MIN, INFINITY)
+            .map_or(BLACK, |res| {
+                let distance = res.get_t() * ray.get_direction().length();
+                WHITE.multiply_constant(1.0 - (distance / self.max_distance).min(1.0))
+            })
+    }
+}
+
+pub struct UvIntegrator;
+
+impl Integrator for UvIntegrator {
+    fn radiance(&self, ray: &Ray, scene: &Scene, _sampler: &mut dyn Sampler) -> Color {
+        scene
+            .get_world()
+            .hit(ray, T_MIN, INFINITY)
+            .map_or(BLACK, |res| Color::new(res.get_u(), res.get_v(), 0.0))
+    }
+}
+
+pub struct MaterialIdIntegrator;
+
+impl Integrator for MaterialIdIntegrator {
+    fn radiance(&self, ray: &Ray, scene: &Scene, _sampler: &mut dyn Sampler) -> Color {
+        scene
+            .get_world()
+            .hit(ray, T_MIN, INFINITY)
+            .map_or(BLACK, |res| material_id_color(&res.get_material()))
+    }
+}
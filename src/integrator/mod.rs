@@ -0,0 +1,239 @@
+use std::time::Instant;
+
+use crate::{
+    aov::AovSample,
+    camera::Camera,
+    color::{Color, MultipleSamplesColor, BLACK},
+    film::Film,
+    hittable::HitRecord,
+    progress::ProgressTracker,
+    ray::Ray,
+    sampler::{RandomSampler, Sampler},
+    scene::Scene,
+    settings::RenderSettings,
+    stats,
+    vec3::{Point3, Vec3},
+};
+pub use ambient_occlusion::AmbientOcclusionIntegrator;
+pub use bidirectional::BidirectionalIntegrator;
+pub use debug::{DepthIntegrator, MaterialIdIntegrator, NormalIntegrator, UvIntegrator};
+pub use path::PathIntegrator;
+pub use photon_mapping::PhotonMappingIntegrator;
+pub use spectral::SpectralPathIntegrator;
+pub use sppm::SppmIntegrator;
+pub use whitted::{PointLight, WhittedIntegrator};
+
+mod ambient_occlusion;
+mod bidirectional;
+mod debug;
+mod path;
+mod photon_mapping;
+mod spectral;
+mod sppm;
+mod whitted;
+
+pub const T_MIN: f64 = 0.001;
+
+pub trait Integrator {
+    fn radiance(&self, ray: &Ray, scene: &Scene, sampler: &mut dyn Sampler) -> Color;
+
+    fn radiance_components(
+        &self,
+        ray: &Ray,
+        scene: &Scene,
+        sampler: &mut dyn Sampler,
+    ) -> (Color, Color) {
+        (self.radiance(ray, scene, sampler), BLACK)
+    }
+}
+
+pub trait Renderer {
+    /// Renders one pass over the film, returning false once no pixel needed more samples.
+    fn render_pass(
+        &self,
+        scene: &Scene,
+        camera: &dyn Camera,
+        settings: &RenderSettings,
+        sampler: &mut dyn Sampler,
+        film: &mut Film,
+        progress: &mut ProgressTracker,
+    ) -> bool;
+
+    fn render(
+        &self,
+        scene: &Scene,
+        camera: &dyn Camera,
+        settings: &RenderSettings,
+        sampler: &mut dyn Sampler,
+        film: &mut Film,
+        progress: &mut ProgressTracker,
+    ) {
+        let samples_done = film
+            .get_pixels()
+            .iter()
+            .map(MultipleSamplesColor::get_samples_count)
+            .min()
+            .unwrap_or(0);
+        progress.set_tiles_total(
+            film.get_height()
+                * settings
+                    .get_max_samples_per_pixel()
+                    .saturating_sub(samples_done),
+        );
+
+        let mut last_checkpoint = Instant::now();
+        while self.render_pass(scene, camera, settings, sampler, film, progress) {
+            if let Some(checkpoint) = settings.get_checkpoint() {
+                if last_checkpoint.elapsed() >= checkpoint.get_interval() {
+                    checkpoint.save(film);
+                    last_checkpoint = Instant::now();
+                }
+            }
+        }
+        progress.finish();
+    }
+}
+
+impl<T: Integrator> Renderer for T {
+    fn render_pass(
+        &self,
+        scene: &Scene,
+        camera: &dyn Camera,
+        settings: &RenderSettings,
+        sampler: &mut dyn Sampler,
+        film: &mut Film,
+        progress: &mut ProgressTracker,
+    ) -> bool {
+        sample_pixels(
+            scene,
+            camera,
+            settings,
+            sampler,
+            film,
+            progress,
+            |ray, sampler, _| self.radiance_components(ray, scene, sampler),
+        )
+    }
+}
+
+/// One pass over the pixels that still need samples, with `radiance` giving the direct and
+/// indirect light along each camera ray. It may also add to the film elsewhere.
+fn sample_pixels(
+    scene: &Scene,
+    camera: &dyn Camera,
+    settings: &RenderSettings,
+    sampler: &mut dyn Sampler,
+    film: &mut Film,
+    progress: &mut ProgressTracker,
+    mut radiance: impl FnMut(&Ray, &mut dyn Sampler, &mut Film) -> (Color, Color),
+) -> bool {
+    let mut active = false;
+    for i in (0..film.get_height()).rev() {
+        let mut row_active = false;
+        for j in 0..film.get_width() {
+            if !settings.needs_samples(film.get_pixel(i, j)) {
+                continue;
+            }
+            row_active = true;
+
+            let sample_index = film.get_pixel(i, j).get_samples_count();
+            sampler.start_pixel_sample((j, i), sample_index);
+
+            let offset = sampler.get_2d();
+            let (u, v) = film.uv(i, j, offset);
+            let Some(ray) = camera.get_ray(u, v, sampler) else {
+                film.add_sample(i, j, offset, &BLACK);
+                continue;
+            };
+            stats::record_primary_ray();
+            let components = radiance(&ray, sampler, film);
+            let color = components.0.add(&components.1);
+            film.add_sample(i, j, offset, &color);
+
+            if film.has_aovs() {
+                film.add_aovs(i, j, &AovSample::new(&ray, scene, components));
+            }
+        }
+        if row_active {
+            active = true;
+            progress.tile_done();
+        }
+    }
+
+    active
+}
+
+pub fn direct_lighting(
+    scene: &Scene,
+    hit_record: &HitRecord,
+    wo: &Vec3,
+    sampler: &mut dyn Sampler,
+) -> Color {
+    let Some((light, pdf_choice)) = scene.sample_light(sampler) else {
+        return BLACK;
+    };
+
+    let (point, normal) = light.sample_point(sampler);
+    let to_light = point.subtract(&hit_record.get_point());
+    let distance = to_light.length();
+    let direction = to_light.divide_constant(distance);
+    let cos_light = -normal.dot(&direction);
+    if cos_light <= 0.0 {
+        return BLACK;
+    }
+
+    let shadow_ray = Ray::new(hit_record.get_point(), direction);
+    if scene
+        .get_world()
+        .hit(&shadow_ray, T_MIN, distance - T_MIN)
+        .is_some()
+    {
+        return BLACK;
+    }
+
+    let cos = hit_record.get_normal().dot(&direction).abs();
+    hit_record
+        .get_material()
+        .eval(hit_record, wo, &direction)
+        .multiply(&light.get_emission())
+        .multiply_constant(cos * cos_light / (distance * distance * pdf_choice * light.pdf_point()))
+}
+
+pub fn from_name(
+    name: &str,
+    scene: &Scene,
+    max_depth: usize,
+    photons: usize,
+    progress: &mut ProgressTracker,
+) -> Option<Box<dyn Renderer>> {
+    let integrator: Box<dyn Renderer> = match name {
+        "path" => Box::new(PathIntegrator::new(max_depth)),
+        "spectral" => Box::new(SpectralPathIntegrator::new(max_depth)),
+        "bdpt" => Box::new(BidirectionalIntegrator::new(max_depth)),
+        "photon" => Box::new(PhotonMappingIntegrator::new(
+            scene,
+            photons,
+            100,
+            0.5,
+            max_depth,
+            &mut RandomSampler::new(),
+            progress,
+        )),
+        "sppm" => Box::new(SppmIntegrator::new((photons / 10).max(1), 0.25, max_depth)),
+        "ao" => Box::new(AmbientOcclusionIntegrator::new(16, 1.0)),
+        "whitted" => Box::new(WhittedIntegrator::new(
+            vec![PointLight::new(
+                Point3::new(10.0, 10.0, 5.0),
+                Color::new(150.0, 150.0, 150.0),
+            )],
+            max_depth,
+        )),
+        "normals" => Box::new(NormalIntegrator),
+        "depth" => Box::new(DepthIntegrator::new(20.0)),
+        "uv" => Box::new(UvIntegrator),
+        "material" => Box::new(MaterialIdIntegrator),
+        _ => return None,
+    };
+
+    Some(integrator)
+}
@@ -0,0 +1,74 @@
+use crate::{
+    color::{Color, BLACK},
+    constants::INFINITY,
+    ray::Ray,
+    sampler::Sampler,
+    scene::Scene,
+};
+
+use super::{Integrator, T_MIN};
+
+pub struct PathIntegrator {
+    max_depth: usize,
+}
+
+impl PathIntegrator {
+    pub const fn new(max_depth: usize) -> Self {
+        Self { max_depth }
+    }
+
+    fn trace(
+        &self,
+        ray: &Ray,
+        scene: &Scene,
+        depth: usize,
+        sampler: &mut dyn Sampler,
+    ) -> (Color, Color) {
+        if depth == 0 {
+            return (BLACK, BLACK);
+        }
+
+        let is_direct = self.max_depth - depth <= 1;
+        let split = |color: Color| {
+            if is_direct {
+                (color, BLACK)
+            } else {
+                (BLACK, color)
+            }
+        };
+
+        let Some(res) = scene.get_world().hit(ray, T_MIN, INFINITY) else {
+            return split(scene.background(ray));
+        };
+        let material = res.get_material();
+        let (direct, indirect) = split(material.emitted(&res));
+
+        let Some(scatter_result) = material.scatter(ray, &res, sampler) else {
+            return (direct, indirect);
+        };
+        let color = scatter_result.get_color();
+        let (next_direct, next_indirect) =
+            self.trace(scatter_result.get_ray(), scene, depth - 1, sampler);
+
+        (
+            direct.add(&color.multiply(&next_direct)),
+            indirect.add(&color.multiply(&next_indirect)),
+        )
+    }
+}
+
+impl Integrator for PathIntegrator {
+    fn radiance(&self, ray: &Ray, scene: &Scene, sampler: &mut dyn Sampler) -> Color {
+        let (direct, indirect) = self.radiance_components(ray, scene, sampler);
+        direct.add(&indirect)
+    }
+
+    fn radiance_components(
+        &self,
+        ray: &Ray,
+        scene: &Scene,
+        sampler: &mut dyn Sampler,
+    ) -> (Color, Color) {
+        self.trace(ray, scene, self.max_depth, sampler)
+    }
+}
@@ -0,0 +1,196 @@
+use crate::{
+    color::{Color, BLACK},
+    constants::{INFINITY, PI},
+    hittable::HitRecord,
+    kdtree::KdTree,
+    progress::ProgressTracker,
+    ray::Ray,
+    sampler::Sampler,
+    scene::Scene,
+    utils::sample_cosine_direction,
+    vec3::Vec3,
+};
+
+use super::{direct_lighting, Integrator, T_MIN};
+
+/// Photons traced between progress reports.
+const PHOTON_BATCH: usize = 10_000;
+
+pub struct Photon {
+    direction: Vec3,
+    power: Color,
+}
+
+impl Photon {
+    pub const fn get_direction(&self) -> Vec3 {
+        self.direction
+    }
+
+    pub const fn get_power(&self) -> Color {
+        self.power
+    }
+}
+
+pub struct PhotonMappingIntegrator {
+    global: KdTree<Photon>,
+    caustic: KdTree<Photon>,
+    emitted: usize,
+    nearest: usize,
+    max_radius: f64,
+    max_depth: usize,
+}
+
+impl PhotonMappingIntegrator {
+    pub fn new(
+        scene: &Scene,
+        photon_count: usize,
+        nearest: usize,
+        max_radius: f64,
+        max_depth: usize,
+        sampler: &mut dyn Sampler,
+        progress: &mut ProgressTracker,
+    ) -> Self {
+        let mut global = vec![];
+        let mut caustic = vec![];
+
+        progress.set_tiles_total(photon_count.div_ceil(PHOTON_BATCH));
+        for index in 0..photon_count {
+            sampler.start_pixel_sample((index, 0), 0);
+            trace_photon(
+                scene,
+                max_depth,
+                sampler,
+                |hit_record, photon, specular_path| {
+                    let map = if specular_path {
+                        &mut caustic
+                    } else {
+                        &mut global
+                    };
+                    map.push((hit_record.get_point(), photon));
+                },
+            );
+            if (index + 1) % PHOTON_BATCH == 0 || index + 1 == photon_count {
+                progress.tile_done();
+            }
+        }
+        progress.finish();
+
+        Self {
+            global: KdTree::new(global),
+            caustic: KdTree::new(caustic),
+            emitted: photon_count,
+            nearest,
+            max_radius,
+            max_depth,
+        }
+    }
+
+    fn estimate(&self, map: &KdTree<Photon>, hit_record: &HitRecord, wo: &Vec3) -> Color {
+        let photons = map.nearest(&hit_record.get_point(), self.nearest, self.max_radius);
+        if photons.is_empty() {
+            return BLACK;
+        }
+
+        let radius2 = if photons.len() < self.nearest {
+            self.max_radius * self.max_radius
+        } else {
+            photons[photons.len() - 1].0
+        };
+
+        let material = hit_record.get_material();
+        let flux = photons.iter().fold(BLACK, |acc, (_, photon)| {
+            acc.add(
+                &material
+                    .eval(hit_record, wo, &photon.direction)
+                    .multiply(&photon.power),
+            )
+        });
+
+        flux.divide_constant(PI * radius2 * self.emitted as f64)
+    }
+
+    fn trace(&self, ray: &Ray, scene: &Scene, depth: usize, sampler: &mut dyn Sampler) -> Color {
+        if depth == 0 {
+            return BLACK;
+        }
+
+        let Some(res) = scene.get_world().hit(ray, T_MIN, INFINITY) else {
+            return scene.background(ray);
+        };
+        let material = res.get_material();
+        let emitted = material.emitted(&res);
+
+        if material.is_specular() {
+            return match material.scatter(ray, &res, sampler) {
+                Some(scatter_result) => {
+                    emitted.add(&scatter_result.get_color().multiply(&self.trace(
+                        scatter_result.get_ray(),
+                        scene,
+                        depth - 1,
+                        sampler,
+                    )))
+                }
+                None => emitted,
+            };
+        }
+
+        let wo = ray.get_direction().unit_vector().negative();
+        emitted
+            .add(&direct_lighting(scene, &res, &wo, sampler))
+            .add(&self.estimate(&self.caustic, &res, &wo))
+            .add(&self.estimate(&self.global, &res, &wo))
+    }
+}
+
+impl Integrator for PhotonMappingIntegrator {
+    fn radiance(&self, ray: &Ray, scene: &Scene, sampler: &mut dyn Sampler) -> Color {
+        self.trace(ray, scene, self.max_depth, sampler)
+    }
+}
+
+pub fn trace_photon<F>(scene: &Scene, max_depth: usize, sampler: &mut dyn Sampler, mut store: F)
+where
+    F: FnMut(&HitRecord, Photon, bool),
+{
+    let Some((light, pdf_choice)) = scene.sample_light(sampler) else {
+        return;
+    };
+
+    let (point, normal) = light.sample_point(sampler);
+    let mut ray = Ray::new(point, sample_cosine_direction(&normal, sampler.get_2d()));
+    let mut power = light
+        .get_emission()
+        .multiply_constant(PI / (pdf_choice * light.pdf_point()));
+    let mut specular_path = true;
+
+    for depth in 0..max_depth {
+        let Some(res) = scene.get_world().hit(&ray, T_MIN, INFINITY) else {
+            return;
+        };
+        let material = res.get_material();
+
+        if !material.is_specular() {
+            if depth > 0 {
+                let photon = Photon {
+                    direction: ray.get_direction().unit_vector().negative(),
+                    power,
+                };
+                store(&res, photon, specular_path);
+            }
+            specular_path = false;
+        }
+
+        let Some(scatter_result) = material.scatter(&ray, &res, sampler) else {
+            return;
+        };
+
+        let color = scatter_result.get_color();
+        let survival = color.get_x().max(color.get_y()).max(color.get_z()).min(1.0);
+        if sampler.get_1d() >= survival {
+            return;
+        }
+
+        power = power.multiply(&color).divide_constant(survival);
+        ray = *scatter_result.get_ray();
+    }
+}
@@ -0,0 +1,61 @@
+use crate::{
+    color::Color,
+    constants::INFINITY,
+    ray::Ray,
+    sampler::Sampler,
+    scene::Scene,
+    spectrum::{SampledSpectrum, SampledWavelengths},
+};
+
+use super::{Integrator, T_MIN};
+
+/// Path tracer carrying hero wavelength samples instead of RGB throughput.
+pub struct SpectralPathIntegrator {
+    max_depth: usize,
+}
+
+impl SpectralPathIntegrator {
+    pub const fn new(max_depth: usize) -> Self {
+        Self { max_depth }
+    }
+}
+
+impl Integrator for SpectralPathIntegrator {
+    fn radiance(&self, ray: &Ray, scene: &Scene, sampler: &mut dyn Sampler) -> Color {
+        let mut wavelengths = SampledWavelengths::sample_visible(sampler.get_1d());
+        let mut ray = ray.with_wavelength(wavelengths.get_hero());
+        let mut beta = SampledSpectrum::new(1.0);
+        let mut radiance = SampledSpectrum::new(0.0);
+
+        for _ in 0..self.max_depth {
+            let Some(res) = scene.get_world().hit(&ray, T_MIN, INFINITY) else {
+                let background = SampledSpectrum::from_rgb(&scene.background(&ray), &wavelengths);
+                radiance = radiance.add(&beta.multiply(&background));
+                break;
+            };
+            let material = res.get_material();
+            let emitted = SampledSpectrum::from_rgb(&material.emitted(&res), &wavelengths);
+            radiance = radiance.add(&beta.multiply(&emitted));
+
+            let Some(scatter_result) = material.scatter(&ray, &res, sampler) else {
+                break;
+            };
+            if material.is_dispersive() {
+                wavelengths.terminate_secondary();
+            }
+
+            beta = beta.multiply(&SampledSpectrum::from_rgb(
+                &scatter_result.get_color(),
+                &wavelengths,
+            ));
+            if beta.is_black() {
+                break;
+            }
+            ray = scatter_result
+                .get_ray()
+                .with_wavelength(wavelengths.get_hero());
+        }
+
+        wavelengths.to_rgb(&radiance)
+    }
+}
@@ -0,0 +1,227 @@
+use crate::{
+    aov::AovSample,
+    camera::Camera,
+    color::{Color, BLACK, WHITE},
+    constants::{INFINITY, PI},
+    film::Film,
+    hittable::HitRecord,
+    kdtree::KdTree,
+    progress::ProgressTracker,
+    ray::Ray,
+    sampler::Sampler,
+    scene::Scene,
+    settings::RenderSettings,
+    stats,
+    vec3::Vec3,
+};
+
+use super::{direct_lighting, photon_mapping::trace_photon, Renderer, T_MIN};
+
+const ALPHA: f64 = 2.0 / 3.0;
+
+struct VisiblePoint {
+    hit_record: HitRecord,
+    wo: Vec3,
+    beta: Color,
+}
+
+struct SppmPixel {
+    visible_point: Option<VisiblePoint>,
+    direct: Color,
+    radius: f64,
+    photons: f64,
+    tau: Color,
+    phi: Color,
+    found: usize,
+}
+
+impl SppmPixel {
+    const fn new(radius: f64) -> Self {
+        Self {
+            visible_point: None,
+            direct: BLACK,
+            radius,
+            photons: 0.0,
+            tau: BLACK,
+            phi: BLACK,
+            found: 0,
+        }
+    }
+
+    fn update(&mut self) {
+        if self.found > 0 {
+            let photons = self.photons + ALPHA * self.found as f64;
+            let radius = self.radius * (photons / (self.photons + self.found as f64)).sqrt();
+            let shrink = (radius * radius) / (self.radius * self.radius);
+
+            self.tau = self.tau.add(&self.phi).multiply_constant(shrink);
+            self.photons = photons;
+            self.radius = radius;
+        }
+
+        self.phi = BLACK;
+        self.found = 0;
+    }
+}
+
+pub struct SppmIntegrator {
+    photons_per_iteration: usize,
+    initial_radius: f64,
+    max_depth: usize,
+}
+
+impl SppmIntegrator {
+    pub const fn new(photons_per_iteration: usize, initial_radius: f64, max_depth: usize) -> Self {
+        Self {
+            photons_per_iteration,
+            initial_radius,
+            max_depth,
+        }
+    }
+
+    fn trace_camera(
+        &self,
+        pixel: &mut SppmPixel,
+        mut ray: Ray,
+        scene: &Scene,
+        sampler: &mut dyn Sampler,
+    ) {
+        let mut beta = WHITE;
+        pixel.visible_point = None;
+
+        for _ in 0..self.max_depth {
+            let Some(res) = scene.get_world().hit(&ray, T_MIN, INFINITY) else {
+                pixel.direct = pixel.direct.add(&beta.multiply(&scene.background(&ray)));
+                return;
+            };
+            let material = res.get_material();
+            pixel.direct = pixel.direct.add(&beta.multiply(&material.emitted(&res)));
+
+            if !material.is_specular() {
+                let wo = ray.get_direction().unit_vector().negative();
+                pixel.direct = pixel
+                    .direct
+                    .add(&beta.multiply(&direct_lighting(scene, &res, &wo, sampler)));
+                pixel.visible_point = Some(VisiblePoint {
+                    hit_record: res,
+                    wo,
+                    beta,
+                });
+                return;
+            }
+
+            let Some(scatter_result) = material.scatter(&ray, &res, sampler) else {
+                return;
+            };
+            beta = beta.multiply(&scatter_result.get_color());
+            ray = *scatter_result.get_ray();
+        }
+    }
+}
+
+impl Renderer for SppmIntegrator {
+    /// The per-pixel photon statistics live outside the film, so everything is done in one pass.
+    fn render_pass(
+        &self,
+        scene: &Scene,
+        camera: &dyn Camera,
+        settings: &RenderSettings,
+        sampler: &mut dyn Sampler,
+        film: &mut Film,
+        progress: &mut ProgressTracker,
+    ) -> bool {
+        if film.get_pixel(0, 0).get_samples_count() > 0 {
+            return false;
+        }
+
+        let width = film.get_width();
+        let height = film.get_height();
+        let samples_per_pixel = settings.get_samples_per_pixel();
+        let mut pixels: Vec<_> = (0..width * height)
+            .map(|_| SppmPixel::new(self.initial_radius))
+            .collect();
+
+        for iteration in 0..samples_per_pixel {
+            for (index, pixel) in pixels.iter_mut().enumerate() {
+                let i = height - 1 - index / width;
+                let j = index % width;
+                sampler.start_pixel_sample((j, i), iteration);
+                let (u, v) = film.uv(i, j, sampler.get_2d());
+                match camera.get_ray(u, v, sampler) {
+                    Some(ray) => {
+                        stats::record_primary_ray();
+                        self.trace_camera(pixel, ray, scene, sampler);
+                    }
+                    None => pixel.visible_point = None,
+                }
+                if j == width - 1 {
+                    progress.tile_done();
+                }
+            }
+
+            let visible: Vec<_> = pixels
+                .iter()
+                .enumerate()
+                .filter_map(|(index, pixel)| {
+                    pixel
+                        .visible_point
+                        .as_ref()
+                        .map(|point| (point.hit_record.get_point(), index))
+                })
+                .collect();
+            let max_radius = pixels.iter().map(|pixel| pixel.radius).fold(0.0, f64::max);
+            let tree = KdTree::new(visible);
+
+            for photon_index in 0..self.photons_per_iteration {
+                sampler.start_pixel_sample((photon_index, height), iteration);
+                trace_photon(scene, self.max_depth, sampler, |hit_record, photon, _| {
+                    tree.within(
+                        &hit_record.get_point(),
+                        max_radius,
+                        |_, &index, distance2| {
+                            let pixel = &mut pixels[index];
+                            if distance2 > pixel.radius * pixel.radius {
+                                return;
+                            }
+                            let Some(point) = &pixel.visible_point else {
+                                return;
+                            };
+
+                            let contribution = point
+                                .hit_record
+                                .get_material()
+                                .eval(&point.hit_record, &point.wo, &photon.get_direction())
+                                .multiply(&point.beta)
+                                .multiply(&photon.get_power());
+                            pixel.phi = pixel.phi.add(&contribution);
+                            pixel.found += 1;
+                        },
+                    );
+                });
+            }
+
+            pixels.iter_mut().for_each(SppmPixel::update);
+        }
+
+        let iterations = samples_per_pixel as f64;
+        let emitted = iterations * self.photons_per_iteration as f64;
+        for (index, pixel) in pixels.iter().enumerate() {
+            let indirect = pixel
+                .tau
+                .divide_constant(emitted * PI * pixel.radius * pixel.radius);
+            let direct = pixel.direct.divide_constant(iterations);
+            let i = height - 1 - index / width;
+            let j = index % width;
+            film.add_pixel(i, j, &direct.add(&indirect));
+
+            if film.has_aovs() {
+                let (u, v) = film.uv(i, j, (0.5, 0.5));
+                if let Some(ray) = camera.get_ray(u, v, sampler) {
+                    film.add_aovs(i, j, &AovSample::new(&ray, scene, (direct, indirect)));
+                }
+            }
+        }
+
+        true
+    }
+}
@@ -0,0 +1,90 @@
+use crate::{
+    color::{Color, BLACK},
+    constants::INFINITY,
+    ray::Ray,
+    sampler::Sampler,
+    scene::Scene,
+    vec3::Point3,
+};
+
+use super::{Integrator, T_MIN};
+
+pub struct PointLight {
+    position: Point3,
+    intensity: Color,
+}
+
+impl PointLight {
+    pub const fn new(position: Point3, intensity: Color) -> Self {
+        Self {
+            position,
+            intensity,
+        }
+    }
+}
+
+pub struct WhittedIntegrator {
+    lights: Vec<PointLight>,
+    max_depth: usize,
+}
+
+impl WhittedIntegrator {
+    pub const fn new(lights: Vec<PointLight>, max_depth: usize) -> Self {
+        Self { lights, max_depth }
+    }
+
+    fn trace(&self, ray: &Ray, scene: &Scene, depth: usize, sampler: &mut dyn Sampler) -> Color {
+        if depth == 0 {
+            return BLACK;
+        }
+
+        let Some(res) = scene.get_world().hit(ray, T_MIN, INFINITY) else {
+            return scene.background(ray);
+        };
+        let material = res.get_material();
+        let emitted = material.emitted(&res);
+        let Some(scatter_result) = material.scatter(ray, &res, sampler) else {
+            return emitted;
+        };
+
+        if material.is_specular() {
+            return emitted.add(&scatter_result.get_color().multiply(&self.trace(
+                scatter_result.get_ray(),
+                scene,
+                depth - 1,
+                sampler,
+            )));
+        }
+
+        let wo = ray.get_direction().unit_vector().negative();
+        self.lights.iter().fold(emitted, |acc, light| {
+            let to_light = light.position.subtract(&res.get_point());
+            let distance = to_light.length();
+            let direction = to_light.divide_constant(distance);
+            let cos = res.get_normal().dot(&direction);
+            let shadow_ray = Ray::new(res.get_point(), direction);
+
+            if cos <= 0.0
+                || scene
+                    .get_world()
+                    .hit(&shadow_ray, T_MIN, distance)
+                    .is_some()
+            {
+                return acc;
+            }
+
+            acc.add(
+                &material
+                    .eval(&res, &wo, &direction)
+                    .multiply(&light.intensity)
+                    .multiply_constant(cos / (distance * distance)),
+            )
+        })
+    }
+}
+
+impl Integrator for WhittedIntegrator {
+    fn radiance(&self, ray: &Ray, scene: &Scene, sampler: &mut dyn Sampler) -> Color {
+        self.trace(ray, scene, self.max_depth, sampler)
+    }
+}
@@ -0,0 +1,168 @@
+use std::{cmp::Ordering, collections::BinaryHeap};
+
+use crate::vec3::Point3;
+
+pub struct KdTree<T> {
+    items: Vec<(Point3, T)>,
+    axes: Vec<usize>,
+}
+
+impl<T> KdTree<T> {
+    pub fn new(mut items: Vec<(Point3, T)>) -> Self {
+        let mut axes = vec![0; items.len()];
+        build(&mut items, &mut axes);
+        Self { items, axes }
+    }
+
+    pub fn within<F>(&self, point: &Point3, radius: f64, mut visit: F)
+    where
+        F: FnMut(&Point3, &T, f64),
+    {
+        self.search(0, self.items.len(), point, radius * radius, &mut visit);
+    }
+
+    pub fn nearest(&self, point: &Point3, count: usize, max_radius: f64) -> Vec<(f64, &T)> {
+        let mut heap = BinaryHeap::new();
+        let mut radius2 = max_radius * max_radius;
+        self.search_nearest(0, self.items.len(), point, count, &mut radius2, &mut heap);
+
+        heap.into_sorted_vec()
+            .into_iter()
+            .map(|candidate| (candidate.distance2, &self.items[candidate.index].1))
+            .collect()
+    }
+
+    fn search<F>(&self, start: usize, end: usize, point: &Point3, radius2: f64, visit: &mut F)
+    where
+        F: FnMut(&Point3, &T, f64),
+    {
+        if start >= end {
+            return;
+        }
+
+        let middle = (start + end) / 2;
+        let (position, item) = &self.items[middle];
+        let distance2 = position.subtract(point).square();
+        if distance2 <= radius2 {
+            visit(position, item, distance2);
+        }
+
+        let delta = axis_value(point, self.axes[middle]) - axis_value(position, self.axes[middle]);
+        let (near, far) = if delta < 0.0 {
+            ((start, middle), (middle + 1, end))
+        } else {
+            ((middle + 1, end), (start, middle))
+        };
+
+        self.search(near.0, near.1, point, radius2, visit);
+        if delta * delta <= radius2 {
+            self.search(far.0, far.1, point, radius2, visit);
+        }
+    }
+
+    fn search_nearest(
+        &self,
+        start: usize,
+        end: usize,
+        point: &Point3,
+        count: usize,
+        radius2: &mut f64,
+        heap: &mut BinaryHeap<Candidate>,
+    ) {
+        if start >= end {
+            return;
+        }
+
+        let middle = (start + end) / 2;
+        let position = &self.items[middle].0;
+        let distance2 = position.subtract(point).square();
+        if distance2 <= *radius2 {
+            heap.push(Candidate {
+                distance2,
+                index: middle,
+            });
+            if heap.len() > count {
+                heap.pop();
+            }
+            if heap.len() == count {
+                *radius2 = heap.peek().map_or(*radius2, |farthest| farthest.distance2);
+            }
+        }
+
+        let delta = axis_value(point, self.axes[middle]) - axis_value(position, self.axes[middle]);
+        let (near, far) = if delta < 0.0 {
+            ((start, middle), (middle + 1, end))
+        } else {
+            ((middle + 1, end), (start, middle))
+        };
+
+        self.search_nearest(near.0, near.1, point, count, radius2, heap);
+        if delta * delta <= *radius2 {
+            self.search_nearest(far.0, far.1, point, count, radius2, heap);
+        }
+    }
+}
+
+struct Candidate {
+    distance2: f64,
+    index: usize,
+}
+
+impl PartialEq for Candidate {
+    fn eq(&self, other: &Self) -> bool {
+        self.distance2 == other.distance2
+    }
+}
+
+impl Eq for Candidate {}
+
+impl PartialOrd for Candidate {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Candidate {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.distance2.total_cmp(&other.distance2)
+    }
+}
+
+fn build<T>(items: &mut [(Point3, T)], axes: &mut [usize]) {
+    if items.is_empty() {
+        return;
+    }
+
+    let axis = widest_axis(items);
+    let middle = items.len() / 2;
+    items.select_nth_unstable_by(middle, |a, b| {
+        axis_value(&a.0, axis).total_cmp(&axis_value(&b.0, axis))
+    });
+    axes[middle] = axis;
+
+    let (left_items, right_items) = items.split_at_mut(middle);
+    let (left_axes, right_axes) = axes.split_at_mut(middle);
+    build(left_items, left_axes);
+    build(&mut right_items[1..], &mut right_axes[1..]);
+}
+
+fn widest_axis<T>(items: &[(Point3, T)]) -> usize {
+    (0..3)
+        .map(|axis| {
+            let (min, max) = items.iter().fold((f64::MAX, f64::MIN), |(min, max), item| {
+                let value = axis_value(&item.0, axis);
+                (min.min(value), max.max(value))
+            });
+            (axis, max - min)
+        })
+        .max_by(|a, b| a.1.total_cmp(&b.1))
+        .map_or(0, |(axis, _)| axis)
+}
+
+fn axis_value(point: &Point3, axis: usize) -> f64 {
+    match axis {
+        0 => point.get_x(),
+        1 => point.get_y(),
+        _ => point.get_z(),
+    }
+}
@@ -0,0 +1,24 @@
+use std::rc::Rc;
+
+use crate::{
+    color::Color,
+    hittable::Hittable,
+    material::Material,
+    sampler::Sampler,
+    vec3::{Point3, Vec3},
+};
+pub use sphere_light::SphereLight;
+
+mod sphere_light;
+
+pub trait Light {
+    fn sample_point(&self, sampler: &mut dyn Sampler) -> (Point3, Vec3);
+
+    fn pdf_point(&self) -> f64;
+
+    fn get_emission(&self) -> Color;
+
+    fn get_material(&self) -> Rc<dyn Material>;
+
+    fn shape(&self) -> Box<dyn Hittable>;
+}
@@ -0,0 +1,60 @@
+use std::rc::Rc;
+
+use crate::{
+    color::Color,
+    constants::PI,
+    hittable::Hittable,
+    material::{DiffuseLight, Material},
+    sampler::Sampler,
+    sphere::Sphere,
+    utils::sample_unit_sphere,
+    vec3::{Point3, Vec3},
+};
+
+use super::Light;
+
+pub struct SphereLight {
+    center: Point3,
+    radius: f64,
+    emission: Color,
+    material: Rc<dyn Material>,
+}
+
+impl SphereLight {
+    pub fn new(center: Point3, radius: f64, emission: Color) -> Self {
+        Self {
+            center,
+            radius,
+            emission,
+            material: Rc::new(DiffuseLight::new(emission)),
+        }
+    }
+}
+
+impl Light for SphereLight {
+    fn sample_point(&self, sampler: &mut dyn Sampler) -> (Point3, Vec3) {
+        let normal = sample_unit_sphere(sampler.get_2d());
+        let point = self.center.add(&normal.multiply_constant(self.radius));
+        (point, normal)
+    }
+
+    fn pdf_point(&self) -> f64 {
+        1.0 / (4.0 * PI * self.radius * self.radius)
+    }
+
+    fn get_emission(&self) -> Color {
+        self.emission
+    }
+
+    fn get_material(&self) -> Rc<dyn Material> {
+        Rc::clone(&self.material)
+    }
+
+    fn shape(&self) -> Box<dyn Hittable> {
+        Box::new(Sphere::new(
+            self.center,
+            self.radius,
+            Rc::clone(&self.material),
+        ))
+    }
+}
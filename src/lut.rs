@@ -0,0 +1,156 @@
+use std::fs;
+
+use crate::color::Color;
+
+/// A 3D lookup table in the Adobe/Resolve `.cube` format, sampled trilinearly.
+pub struct Lut {
+    size: usize,
+    domain_min: Color,
+    domain_max: Color,
+    table: Vec<Color>,
+}
+
+impl Lut {
+    pub fn load(path: &str) -> Result<Self, String> {
+        let contents = fs::read_to_string(path)
+            .map_err(|error| format!("failed to read {}: {}", path, error))?;
+        Self::parse(&contents, path)
+    }
+
+    /// Parses the contents of a `.cube` file, with `path` only used in errors.
+    fn parse(contents: &str, path: &str) -> Result<Self, String> {
+        let mut size = 0;
+        let mut domain_min = Color::new(0.0, 0.0, 0.0);
+        let mut domain_max = Color::new(1.0, 1.0, 1.0);
+        let mut table = Vec::new();
+
+        for line in contents.lines() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') || line.starts_with("TITLE") {
+                continue;
+            }
+
+            let mut fields = line.split_whitespace();
+            let keyword = fields.next().unwrap_or_default();
+            let numbers = |fields: std::str::SplitWhitespace| -> Result<Color, String> {
+                let values = fields
+                    .map(str::parse)
+                    .collect::<Result<Vec<f64>, _>>()
+                    .map_err(|_| format!("invalid line in {}: {}", path, line))?;
+                match values[..] {
+                    [r, g, b] => Ok(Color::new(r, g, b)),
+                    _ => Err(format!("invalid line in {}: {}", path, line)),
+                }
+            };
+
+            match keyword {
+                "LUT_3D_SIZE" => {
+                    size = fields
+                        .next()
+                        .and_then(|value| value.parse().ok())
+                        .ok_or(format!("invalid LUT_3D_SIZE in {}", path))?;
+                }
+                "LUT_1D_SIZE" => return Err(format!("1D LUTs are not supported: {}", path)),
+                "DOMAIN_MIN" => domain_min = numbers(fields)?,
+                "DOMAIN_MAX" => domain_max = numbers(fields)?,
+                "LUT_3D_INPUT_RANGE" => {
+                    let range = fields
+                        .map(str::parse)
+                        .collect::<Result<Vec<f64>, _>>()
+                        .map_err(|_| format!("invalid LUT_3D_INPUT_RANGE in {}", path))?;
+                    let [min, max] = range[..] else {
+                        return Err(format!("invalid LUT_3D_INPUT_RANGE in {}", path));
+                    };
+                    domain_min = Color::new(min, min, min);
+                    domain_max = Color::new(max, max, max);
+                }
+                // Other keywords, which some tools add, don't affect the table.
+                _ if keyword.parse::<f64>().is_err() => {}
+                _ => table.push(numbers(line.split_whitespace())?),
+            }
+        }
+
+        if size < 2 || table.len() != size * size * size {
+            return Err(format!(
+                "expected {} entries in {}, found {}",
+                size * size * size,
+                path,
+                table.len()
+            ));
+        }
+
+        Ok(Self {
+            size,
+            domain_min,
+            domain_max,
+            table,
+        })
+    }
+
+    pub fn apply(&self, color: &Color) -> Color {
+        let scale = (self.size - 1) as f64;
+        let position = color
+            .subtract(&self.domain_min)
+            .divide(&self.domain_max.subtract(&self.domain_min))
+            .multiply_constant(scale);
+        let split = |value: f64| {
+            let value = value.clamp(0.0, scale);
+            let index = (value.floor() as usize).min(self.size - 2);
+            (index, value - index as f64)
+        };
+        let (r, fr) = split(position.get_x());
+        let (g, fg) = split(position.get_y());
+        let (b, fb) = split(position.get_z());
+
+        let mut result = Color::new(0.0, 0.0, 0.0);
+        for (dr, wr) in [(0, 1.0 - fr), (1, fr)] {
+            for (dg, wg) in [(0, 1.0 - fg), (1, fg)] {
+                for (db, wb) in [(0, 1.0 - fb), (1, fb)] {
+                    let index = (r + dr) + (g + dg) * self.size + (b + db) * self.size * self.size;
+                    result = result.add(&self.table[index].multiply_constant(wr * wg * wb));
+                }
+            }
+        }
+
+        result
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_with_input_range_header() {
+        let contents = "\
+TITLE \"identity\"
+# written by a grading tool
+LUT_3D_SIZE 2
+LUT_3D_INPUT_RANGE 0.0 2.0
+LUT_IN_VIDEO_RANGE
+
+0 0 0
+1 0 0
+0 1 0
+1 1 0
+0 0 1
+1 0 1
+0 1 1
+1 1 1
+";
+        let lut = Lut::parse(contents, "identity.cube").unwrap();
+        assert_eq!(lut.size, 2);
+        assert_eq!(lut.table.len(), 8);
+
+        let color = lut.apply(&Color::new(1.0, 0.5, 2.0));
+        assert!((color.get_x() - 0.5).abs() < 1e-9);
+        assert!((color.get_y() - 0.25).abs() < 1e-9);
+        assert!((color.get_z() - 1.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn parse_rejects_short_table() {
+        let contents = "LUT_3D_SIZE 2\n0 0 0\n1 1 1\n";
+        assert!(Lut::parse(contents, "short.cube").is_err());
+    }
+}
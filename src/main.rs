@@ -1,130 +1,211 @@
-use std::rc::Rc;
-
-use camera::ASPECT_RATIO;
-use color::{BLACK, BLUE, WHITE};
-use constants::INFINITY;
-use hittable::Hittable;
-use material::Material;
-use ray::Ray;
-use utils::{random_in_range, random_vec3, random_vec_in_range};
-
-use crate::{
-    camera::Camera,
-    color::{Color, MultipleSamplesColor},
-    hittable_list::HittableList,
-    material::{Dielectric, Lambertian, Metal},
-    sphere::Sphere,
-    utils::random_double,
-    vec3::{Point3, Vec3},
+use std::{
+    path::Path,
+    process,
+    rc::Rc,
+    time::{Duration, Instant},
 };
 
+use aov::Aov;
+use aperture::{Aperture, ApertureMask};
+use camera::{CameraView, Lens, LensPrescription, Projection};
+use color::{format_color, Color};
+use color_pipeline::ColorPipeline;
+use film::Film;
+use gltf::Gltf;
+use options::Options;
+use progress::{Progress, ProgressTracker};
+use scene::Scene;
+use scene_description::SceneDescription;
+use settings::{AdaptiveSampling, Checkpoint, RenderSettings};
+use stats::Statistics;
+
 // const IMAGE_WIDTH: usize = 1200;
 // const IMAGE_HEIGHT: usize = (IMAGE_WIDTH as f64 / ASPECT_RATIO) as usize;
 const IMAGE_WIDTH: usize = 300;
-const IMAGE_HEIGHT: usize = (IMAGE_WIDTH as f64 / ASPECT_RATIO) as usize;
 const MAX_COLOR: usize = 255;
 const SAMPLES_PER_PIXERL: usize = 50;
+/// With adaptive sampling, the samples taken before testing for convergence unless `--spp`
+/// is given.
+const MIN_ADAPTIVE_SAMPLES: usize = 16;
 const MAX_DEPTH: usize = 50;
-
+const DEFAULT_FOCAL_LENGTH: f64 = 50.0;
+const DEFAULT_F_NUMBER: f64 = 8.0;
+
+mod aabb;
+mod animation;
+mod aov;
+mod aperture;
+mod bezier;
+mod bezier_patch;
+mod bvh;
 mod camera;
 mod color;
+mod color_pipeline;
+mod cone;
 mod constants;
+mod csg;
+mod curve;
+mod cylinder;
+mod denoise;
+mod disk;
+mod film;
+mod filter;
+mod frame;
+mod gltf;
+mod heatmap;
 mod hittable;
 mod hittable_list;
+mod integrator;
+mod json;
+mod kdtree;
+mod light;
+mod lut;
 mod material;
+mod matrix;
+mod mesh;
+mod options;
+mod plane;
+mod ply;
+mod polynomial;
+mod ppm;
+#[cfg(feature = "preview")]
+mod preview;
+mod progress;
+mod quadric;
 mod ray;
+mod sampler;
+mod scene;
+mod scene_description;
+mod scenes;
+mod sdf;
+mod settings;
+mod spectrum;
 mod sphere;
+mod stats;
+mod stl;
+mod subdivision;
+mod tonemap;
+mod torus;
+mod transform;
 mod utils;
 mod vec3;
 
-fn random_scene() -> HittableList {
-    let mut world = HittableList::new();
-
-    let ground_material = Rc::new(Lambertian::new(Color::new(0.5, 0.5, 0.5)));
-    world.add(Box::new(Sphere::new(
-        Point3::new(0.0, -1000.0, 0.0),
-        1000.0,
-        ground_material,
-    )));
-
-    for a in -11..11 {
-        for b in -11..11 {
-            let choose_mat = random_double();
-            let center = Point3::new(
-                a as f64 + 0.9 * random_double(),
-                0.2,
-                b as f64 + 0.9 * random_double(),
-            );
-
-            if (center.subtract(&Point3::new(4.0, 0.2, 0.0))).length() > 0.9 {
-                let sphere_material: Rc<dyn Material> = if choose_mat < 0.8 {
-                    let albedo = random_vec3().multiply(&random_vec3());
-                    Rc::new(Lambertian::new(albedo))
-                } else if choose_mat < 0.95 {
-                    let albedo = random_vec_in_range(0.5, 1.0);
-                    let fuzz = random_in_range(0.0, 0.5);
-                    Rc::new(Metal::new(albedo, fuzz))
-                } else {
-                    Rc::new(Dielectric::new(1.5))
-                };
-
-                world.add(Box::new(Sphere::new(center, 0.2, sphere_material)));
-            }
+/// Setup, render and output time, summed over every frame.
+type Timings = [(&'static str, Duration); 3];
+
+/// A scene read from `--scene-file`, either a scene description or, going by the extension, a
+/// glTF file.
+enum SceneFile {
+    Description(Box<SceneDescription>),
+    Gltf(Gltf),
+}
+
+impl SceneFile {
+    fn load(path: &str) -> Result<Self, String> {
+        let extension = Path::new(path)
+            .extension()
+            .and_then(|extension| extension.to_str())
+            .map(str::to_ascii_lowercase);
+        match extension.as_deref() {
+            Some("gltf" | "glb") => Gltf::load(path).map(Self::Gltf),
+            _ => SceneDescription::load(path)
+                .map(|description| Self::Description(Box::new(description))),
         }
     }
 
-    let material1 = Rc::new(Dielectric::new(1.5));
-    world.add(Box::new(Sphere::new(
-        Point3::new(0.0, 1.0, 0.0),
-        1.0,
-        material1,
-    )));
-
-    let material2 = Rc::new(Lambertian::new(Color::new(0.4, 0.2, 0.1)));
-    world.add(Box::new(Sphere::new(
-        Point3::new(-4.0, 1.0, 0.0),
-        1.0,
-        material2,
-    )));
-
-    let material3 = Rc::new(Metal::new(Color::new(0.7, 0.6, 0.5), 0.0));
-    world.add(Box::new(Sphere::new(
-        Point3::new(4.0, 1.0, 0.0),
-        1.0,
-        material3,
-    )));
-
-    world
+    /// glTF scenes are not animated and look the same in every frame.
+    fn at(&self, frame: f64) -> Result<(Scene, CameraView), String> {
+        match self {
+            Self::Description(description) => Ok(description.at(frame)),
+            Self::Gltf(gltf) => gltf.scene(),
+        }
+    }
 }
 
-fn ray_color(ray: &Ray, world: Rc<dyn Hittable>, depth: usize) -> Color {
-    if depth == 0 {
-        return BLACK;
-    }
+fn main() {
+    let options = match Options::from_args() {
+        Ok(options) => options,
+        Err(message) => {
+            eprintln!("{}", message);
+            process::exit(1);
+        }
+    };
+    let scene_file = options.get_scene_file().map(|path| {
+        SceneFile::load(path).unwrap_or_else(|message| {
+            eprintln!("{}", message);
+            process::exit(1);
+        })
+    });
+    let mut timings = [
+        ("Setup", Duration::ZERO),
+        ("Render", Duration::ZERO),
+        ("Output", Duration::ZERO),
+    ];
+
+    if let Some((first, last)) = options.get_frames() {
+        if options.get_preview() || options.get_resume().is_some() {
+            eprintln!("--frames cannot be combined with --preview or --resume");
+            process::exit(1);
+        }
 
-    if let Some(res) = world.hit(ray, 0.001, INFINITY) {
-        let answer = if let Some(scatter_result) = res.get_material().scatter(ray, &res) {
-            scatter_result.get_color().multiply(&ray_color(
-                scatter_result.get_ray(),
-                world,
-                depth - 1,
-            ))
-        } else {
-            BLACK
-        };
+        for frame in first..=last {
+            eprintln!("Frame {}/{}", frame, last);
+            let (width, height, colors) =
+                render_frame(&options, scene_file.as_ref(), frame, &mut timings);
 
-        return answer;
+            let output_start = Instant::now();
+            let path = frame_path(options.get_output(), frame);
+            if let Err(error) = ppm::write_ppm(&path, width, height, &colors) {
+                eprintln!("failed to write frame {}: {}", path, error);
+                process::exit(1);
+            }
+            timings[2].1 += output_start.elapsed();
+        }
+    } else {
+        let (width, height, colors) = render_frame(&options, scene_file.as_ref(), 1, &mut timings);
+
+        let output_start = Instant::now();
+        println!("P3");
+        println!("{} {}", width, height);
+        println!("{}", MAX_COLOR);
+        for color in &colors {
+            println!("{}", format_color(color));
+        }
+        timings[2].1 += output_start.elapsed();
     }
 
-    let unit_direction = ray.get_direction().unit_vector();
-    let t = 0.5 * (unit_direction.get_y() + 1.0);
-    WHITE
-        .multiply_constant(1.0 - t)
-        .add(&BLUE.multiply_constant(t))
+    Statistics::snapshot().print_summary(&timings);
+    eprintln!("Done");
 }
 
-fn main() {
-    let mut world = HittableList::new();
+/// Renders one frame, of the scene file if there is one, and returns its display colors.
+fn render_frame(
+    options: &Options,
+    scene_file: Option<&SceneFile>,
+    frame: usize,
+    timings: &mut Timings,
+) -> (usize, usize, Vec<Color>) {
+    let setup_start = Instant::now();
+    let (scene, view) = match scene_file {
+        Some(scene_file) => scene_file.at(frame as f64).unwrap_or_else(|message| {
+            eprintln!("{}", message);
+            process::exit(1);
+        }),
+        None => scenes::from_name(options.get_scene()).unwrap_or_else(|| {
+            eprintln!("unknown scene {}", options.get_scene());
+            process::exit(1);
+        }),
+    };
+    let Some(integrator) = integrator::from_name(
+        options.get_integrator(),
+        &scene,
+        MAX_DEPTH,
+        options.get_photons(),
+        &mut ProgressTracker::new(progress_printer()),
+    ) else {
+        eprintln!("unknown integrator {}", options.get_integrator());
+        process::exit(1);
+    };
 
     // let R = (PI / 4.0).cos();
 
@@ -174,37 +255,228 @@ fn main() {
     // )));
     // let world: Rc<dyn Hittable> = Rc::new(world);
 
-    let world: Rc<dyn Hittable> = Rc::new(random_scene());
+    let projection = if let Some(path) = options.get_lens_file() {
+        let prescription = LensPrescription::load(path).unwrap_or_else(|message| {
+            eprintln!("{}", message);
+            process::exit(1);
+        });
+        Some(Projection::Realistic {
+            prescription: Rc::new(prescription),
+            sensor_width: options.get_sensor_width(),
+        })
+    } else {
+        options.get_projection().map(|name| {
+            Projection::from_name(
+                name,
+                options.get_fisheye_fov(),
+                options.get_interocular(),
+                options.get_convergence(),
+                options.get_views(),
+            )
+            .unwrap_or_else(|| {
+                eprintln!("unknown projection {}", name);
+                process::exit(1);
+            })
+        })
+    };
+    let mut view = view.with_cat_eye(options.get_cat_eye());
+    if let Some(projection) = projection {
+        view = view.with_projection(projection);
+    }
+    if options.get_focal_length().is_some() || options.get_f_number().is_some() {
+        view = view.with_lens(&Lens::new(
+            options.get_focal_length().unwrap_or(DEFAULT_FOCAL_LENGTH),
+            options.get_f_number().unwrap_or(DEFAULT_F_NUMBER),
+            options.get_sensor_width(),
+        ));
+    }
+    if let Some(path) = options.get_aperture_mask() {
+        let mask = ApertureMask::load(path).unwrap_or_else(|message| {
+            eprintln!("{}", message);
+            process::exit(1);
+        });
+        view = view.with_aperture_shape(Aperture::Mask(Rc::new(mask)));
+    } else if let Some(blades) = options.get_blades() {
+        view = view.with_aperture_shape(Aperture::polygon(blades, options.get_blade_rotation()));
+    }
+    let views = view.views();
+    let image_width = IMAGE_WIDTH * views;
+    let image_height = (IMAGE_WIDTH as f64 / view.aspect_ratio()) as usize;
+
+    let adaptive = options
+        .get_adaptive_threshold()
+        .map(|threshold| AdaptiveSampling::new(threshold, options.get_max_samples()));
+    let samples_per_pixel = match &adaptive {
+        Some(_) => options
+            .get_samples_per_pixel()
+            .unwrap_or(MIN_ADAPTIVE_SAMPLES)
+            .min(options.get_max_samples()),
+        None => options
+            .get_samples_per_pixel()
+            .unwrap_or(SAMPLES_PER_PIXERL),
+    };
+    let checkpoint = options.get_checkpoint().map(|path| {
+        Checkpoint::new(
+            frame_path(path, frame),
+            Duration::from_secs(options.get_checkpoint_interval()),
+        )
+    });
+    let settings = RenderSettings::new(samples_per_pixel, adaptive, checkpoint);
+    let Some(mut sampler) = sampler::from_name(options.get_sampler(), samples_per_pixel, 0) else {
+        eprintln!("unknown sampler {}", options.get_sampler());
+        process::exit(1);
+    };
+    let Some(filter) = filter::from_name(options.get_filter()) else {
+        eprintln!("unknown filter {}", options.get_filter());
+        process::exit(1);
+    };
+    let denoiser = options.get_denoiser().map(|name| {
+        denoise::from_name(name).unwrap_or_else(|| {
+            eprintln!("unknown denoiser {}", name);
+            process::exit(1);
+        })
+    });
+    let Some(tone_mapper) = tonemap::from_name(options.get_tone_mapper()) else {
+        eprintln!("unknown tone mapper {}", options.get_tone_mapper());
+        process::exit(1);
+    };
+    let lut = options.get_lut().map(|path| {
+        lut::Lut::load(path).unwrap_or_else(|message| {
+            eprintln!("{}", message);
+            process::exit(1);
+        })
+    });
+    let pipeline = ColorPipeline::new(
+        options.get_exposure(),
+        options.get_white_balance(),
+        tone_mapper,
+        lut,
+    );
+    let mut film = Film::new(image_width, image_height, filter);
+    if denoiser.is_some() {
+        film.enable_aov(Aov::Albedo);
+        film.enable_aov(Aov::Normal);
+    }
+    let mut aovs = Vec::new();
+    for (name, path) in options.get_aovs() {
+        let Some(aov) = Aov::from_name(name) else {
+            eprintln!("unknown aov {}", name);
+            process::exit(1);
+        };
+        film.enable_aov(aov);
+        aovs.push((aov, path));
+    }
+    if let Some(path) = options.get_resume() {
+        if let Err(error) = film.load_checkpoint(path) {
+            eprintln!("failed to resume from {}: {}", path, error);
+            process::exit(1);
+        }
+    }
+    timings[0].1 += setup_start.elapsed();
+
+    let render_start = Instant::now();
+    if options.get_preview() {
+        #[cfg(feature = "preview")]
+        if let Err(message) = preview::run(
+            integrator.as_ref(),
+            &scene,
+            view,
+            &settings,
+            sampler.as_mut(),
+            &mut film,
+            &pipeline,
+        ) {
+            eprintln!("preview failed: {}", message);
+            process::exit(1);
+        }
+        #[cfg(not(feature = "preview"))]
+        {
+            eprintln!("preview requires building with --features preview");
+            process::exit(1);
+        }
+    } else {
+        integrator.render(
+            &scene,
+            view.build().as_ref(),
+            &settings,
+            sampler.as_mut(),
+            &mut film,
+            &mut ProgressTracker::new(progress_printer()),
+        );
+        eprintln!();
+    }
+    timings[1].1 += render_start.elapsed();
 
-    let look_from = Point3::new(13.0, 2.0, 3.0);
-    let look_at = Point3::new(0.0, 0.0, 0.0);
-    let vup = Vec3::new(0.0, 1.0, 0.0);
-    let dist_to_focus = 10.0;
-    let aperture = 0.1;
+    let output_start = Instant::now();
+    if let Some(checkpoint) = settings.get_checkpoint() {
+        checkpoint.save(&film);
+    }
 
-    let camera = Camera::new(look_from, look_at, vup, 20.0, aperture, dist_to_focus);
+    for (aov, path) in aovs {
+        let path = frame_path(path, frame);
+        if let Err(error) = film.write_aov(aov, &path) {
+            eprintln!("failed to write aov {}: {}", path, error);
+        }
+    }
 
-    println!("P3");
-    println!("{} {}", IMAGE_WIDTH, IMAGE_HEIGHT);
-    println!("{}", MAX_COLOR);
+    if let Some(path) = options.get_heatmap() {
+        let path = frame_path(path, frame);
+        if let Err(error) = heatmap::write_heatmap(&path, &film) {
+            eprintln!("failed to write heatmap {}: {}", path, error);
+        }
+    }
 
-    for i in (0..IMAGE_HEIGHT).rev() {
-        eprintln!("Scanlines remaining {}", i);
-        for j in 0..IMAGE_WIDTH {
-            let mut color = MultipleSamplesColor::new();
+    let colors: Vec<_> = match denoiser {
+        Some(denoiser) => denoise::denoise(denoiser.as_ref(), &film),
+        None => film.get_colors(),
+    }
+    .iter()
+    .map(|color| pipeline.apply(color))
+    .collect();
+
+    if let Some(pattern) = options.get_view_files() {
+        for view in 0..views {
+            let path = frame_path(pattern, frame).replace("{}", &view.to_string());
+            let view_colors: Vec<_> = colors
+                .chunks(image_width)
+                .flat_map(|row| &row[view * IMAGE_WIDTH..(view + 1) * IMAGE_WIDTH])
+                .copied()
+                .collect();
+            if let Err(error) = ppm::write_ppm(&path, IMAGE_WIDTH, image_height, &view_colors) {
+                eprintln!("failed to write view {}: {}", path, error);
+            }
+        }
+    }
+    timings[2].1 += output_start.elapsed();
 
-            for _ in 0..SAMPLES_PER_PIXERL {
-                let u = (j as f64 + random_double()) / (IMAGE_WIDTH - 1) as f64;
-                let v = (i as f64 + random_double()) / (IMAGE_HEIGHT - 1) as f64;
-                let ray = camera.get_ray(u, v);
-                let pixel = ray_color(&ray, Rc::clone(&world), MAX_DEPTH);
+    (image_width, image_height, colors)
+}
 
-                color.add(&pixel);
-            }
+/// Replaces `{frame}` in an output path with the zero-padded frame number.
+fn frame_path(path: &str, frame: usize) -> String {
+    path.replace("{frame}", &format!("{:04}", frame))
+}
 
-            println!("{}", color);
+fn progress_printer() -> impl FnMut(&Progress) {
+    let mut last_print: Option<Instant> = None;
+    move |progress| {
+        if !progress.is_finished()
+            && last_print.is_some_and(|time| time.elapsed() < Duration::from_millis(250))
+        {
+            return;
         }
+        last_print = Some(Instant::now());
+
+        let eta = progress.eta().map_or(String::from("?"), |eta| {
+            format!("{:.0}s", eta.as_secs_f64())
+        });
+        eprint!(
+            "\rTiles {}/{} | {:.2} Mrays/s | elapsed {:.1}s | ETA {}    ",
+            progress.get_tiles_done(),
+            progress.get_tiles_total(),
+            progress.rays_per_second() / 1e6,
+            progress.get_elapsed().as_secs_f64(),
+            eta
+        );
     }
-
-    eprintln!("Done");
 }
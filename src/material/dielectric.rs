@@ -2,27 +2,72 @@ use crate::{
     color::WHITE,
     hittable::HitRecord,
     ray::Ray,
-    utils::{random_double, reflect, refract},
+    sampler::Sampler,
+    utils::{reflect, refract},
 };
 
 use super::{Material, ScatterResult};
 
+const SODIUM_D_LINE: f64 = 587.6;
+
+/// Wavelength-dependent index of refraction, with wavelengths in micrometers.
+pub enum Dispersion {
+    Cauchy { a: f64, b: f64 },
+    Sellmeier { b: [f64; 3], c: [f64; 3] },
+}
+
+impl Dispersion {
+    pub fn refraction_index(&self, wavelength_nm: f64) -> f64 {
+        let lambda = wavelength_nm / 1000.0;
+        let lambda2 = lambda * lambda;
+        match self {
+            Self::Cauchy { a, b } => a + b / lambda2,
+            Self::Sellmeier { b, c } => (1.0
+                + (0..3)
+                    .map(|i| b[i] * lambda2 / (lambda2 - c[i]))
+                    .sum::<f64>())
+            .sqrt(),
+        }
+    }
+}
+
 pub struct Dielectric {
     refraction_index: f64,
+    dispersion: Option<Dispersion>,
 }
 
 impl Dielectric {
     pub const fn new(refraction_index: f64) -> Self {
-        Self { refraction_index }
+        Self {
+            refraction_index,
+            dispersion: None,
+        }
+    }
+
+    /// Outside spectral rendering the index at the sodium D line is used.
+    pub fn with_dispersion(dispersion: Dispersion) -> Self {
+        Self {
+            refraction_index: dispersion.refraction_index(SODIUM_D_LINE),
+            dispersion: Some(dispersion),
+        }
     }
 }
 
 impl Material for Dielectric {
-    fn scatter(&self, ray: &Ray, hit_record: &HitRecord) -> Option<ScatterResult> {
+    fn scatter(
+        &self,
+        ray: &Ray,
+        hit_record: &HitRecord,
+        sampler: &mut dyn Sampler,
+    ) -> Option<ScatterResult> {
+        let refraction_index = match (&self.dispersion, ray.get_wavelength()) {
+            (Some(dispersion), Some(wavelength)) => dispersion.refraction_index(wavelength),
+            _ => self.refraction_index,
+        };
         let refraction_ratio = if hit_record.get_front_face() {
-            1.0 / self.refraction_index
+            1.0 / refraction_index
         } else {
-            self.refraction_index
+            refraction_index
         };
 
         let unit_direction = ray.get_direction().unit_vector();
@@ -33,7 +78,7 @@ impl Material for Dielectric {
         let sin = (1.0 - cos * cos).sqrt();
 
         let direction = if refraction_ratio * sin > 1.0
-            || reflectance(cos, refraction_ratio) > random_double()
+            || reflectance(cos, refraction_ratio) > sampler.get_1d()
         {
             reflect(&unit_direction, &hit_record.get_normal())
         } else {
@@ -44,6 +89,14 @@ impl Material for Dielectric {
 
         Some(ScatterResult::new(color, ray))
     }
+
+    fn is_specular(&self) -> bool {
+        true
+    }
+
+    fn is_dispersive(&self) -> bool {
+        self.dispersion.is_some()
+    }
 }
 
 fn reflectance(cos: f64, refraction_ratio: f64) -> f64 {
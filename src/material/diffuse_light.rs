@@ -0,0 +1,37 @@
+use crate::{
+    color::{Color, BLACK},
+    hittable::HitRecord,
+    ray::Ray,
+    sampler::Sampler,
+};
+
+use super::{Material, ScatterResult};
+
+pub struct DiffuseLight {
+    emission: Color,
+}
+
+impl DiffuseLight {
+    pub const fn new(emission: Color) -> Self {
+        Self { emission }
+    }
+}
+
+impl Material for DiffuseLight {
+    fn scatter(
+        &self,
+        _ray: &Ray,
+        _hit_record: &HitRecord,
+        _sampler: &mut dyn Sampler,
+    ) -> Option<ScatterResult> {
+        None
+    }
+
+    fn emitted(&self, hit_record: &HitRecord) -> Color {
+        if hit_record.get_front_face() {
+            self.emission
+        } else {
+            BLACK
+        }
+    }
+}
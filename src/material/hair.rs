@@ -0,0 +1,375 @@
+use crate::{
+    color::{Color, BLACK},
+    constants::PI,
+    hittable::HitRecord,
+    ray::Ray,
+    sampler::Sampler,
+    utils::orthonormal_basis,
+    vec3::Vec3,
+};
+
+use super::{Material, ScatterResult};
+
+/// Scattering orders evaluated separately: R, TT and TRT. Everything after is lumped together.
+const MAX_ORDER: usize = 3;
+const ETA: f64 = 1.55;
+const EUMELANIN_ABSORPTION: [f64; 3] = [0.419, 0.697, 1.37];
+const PHEOMELANIN_ABSORPTION: [f64; 3] = [0.187, 0.4, 1.05];
+
+/// The hair fiber model of d'Eon et al. and Chiang et al.: a rough dielectric cylinder with
+/// tilted cuticle scales and an absorbing interior. It needs a hit with a tangent along the
+/// fiber and v running across it, as curves provide.
+pub struct Hair {
+    sigma_a: Color,
+    /// Longitudinal variances for each order.
+    variances: [f64; MAX_ORDER + 1],
+    /// Scale of the azimuthal logistic distribution.
+    azimuthal_scale: f64,
+    /// Sines and cosines of the scale tilt doubled once, twice and four times.
+    tilts: [(f64, f64); 3],
+}
+
+impl Hair {
+    /// `sigma_a` is the absorption per unit of diameter, `beta_m` and `beta_n` the longitudinal
+    /// and azimuthal roughness in [0, 1], and `alpha` the cuticle scale tilt in degrees.
+    pub fn new(sigma_a: Color, beta_m: f64, beta_n: f64, alpha: f64) -> Self {
+        let variance = (0.726 * beta_m + 0.812 * beta_m.powi(2) + 3.7 * beta_m.powi(20)).powi(2);
+        let variances = [variance, 0.25 * variance, 4.0 * variance, 4.0 * variance];
+        let azimuthal_scale =
+            (PI / 8.0).sqrt() * (0.265 * beta_n + 1.194 * beta_n.powi(2) + 5.372 * beta_n.powi(22));
+
+        let sin = alpha.to_radians().sin();
+        let mut tilts = [(sin, safe_sqrt(1.0 - sin * sin)); 3];
+        for i in 1..3 {
+            let (sin, cos) = tilts[i - 1];
+            tilts[i] = (2.0 * cos * sin, cos * cos - sin * sin);
+        }
+
+        Self {
+            sigma_a,
+            variances,
+            azimuthal_scale,
+            tilts,
+        }
+    }
+
+    /// Absorption from the concentrations of the dark eumelanin and the reddish pheomelanin,
+    /// where about 8 gives black hair, 1.3 brown and 0.3 blonde.
+    pub fn from_melanin(
+        eumelanin: f64,
+        pheomelanin: f64,
+        beta_m: f64,
+        beta_n: f64,
+        alpha: f64,
+    ) -> Self {
+        let absorption = |i: usize| {
+            eumelanin * EUMELANIN_ABSORPTION[i] + pheomelanin * PHEOMELANIN_ABSORPTION[i]
+        };
+        Self::new(
+            Color::new(absorption(0), absorption(1), absorption(2)),
+            beta_m,
+            beta_n,
+            alpha,
+        )
+    }
+
+    /// The fiber frame: x along the fiber, z towards `wo` and y across the fiber, which is the
+    /// direction v increases along on curves.
+    fn frame(hit_record: &HitRecord, wo: &Vec3) -> (Vec3, Vec3, Vec3) {
+        let x = hit_record
+            .get_tangent()
+            .unwrap_or_else(|| orthonormal_basis(&hit_record.get_normal()).0);
+        let facing = wo.subtract(&x.multiply_constant(x.dot(wo)));
+        let z = if facing.near_zero() {
+            orthonormal_basis(&x).0
+        } else {
+            facing.unit_vector()
+        };
+
+        (x, x.cross(&z), z)
+    }
+
+    /// Longitudinal angle sines and cosines for `wo` after the scale tilt for order `p`.
+    fn tilted(&self, p: usize, sin_theta_o: f64, cos_theta_o: f64) -> (f64, f64) {
+        let (sin, cos) = match p {
+            0 => (-self.tilts[1].0, self.tilts[1].1),
+            1 => self.tilts[0],
+            2 => self.tilts[2],
+            _ => return (sin_theta_o, cos_theta_o),
+        };
+        (
+            sin_theta_o * cos + cos_theta_o * sin,
+            (cos_theta_o * cos - sin_theta_o * sin).abs(),
+        )
+    }
+
+    /// The attenuation of each order for a ray entering at offset `h`, and the refracted
+    /// azimuthal angle inside the fiber.
+    fn attenuation(&self, sin_theta_o: f64, cos_theta_o: f64, h: f64) -> ([Color; 4], f64) {
+        let sin_theta_t = sin_theta_o / ETA;
+        let cos_theta_t = safe_sqrt(1.0 - sin_theta_t * sin_theta_t);
+        let eta_p = safe_sqrt(ETA * ETA - sin_theta_o * sin_theta_o) / cos_theta_o;
+        let sin_gamma_t = (h / eta_p).clamp(-1.0, 1.0);
+        let cos_gamma_t = safe_sqrt(1.0 - sin_gamma_t * sin_gamma_t);
+
+        let distance = 2.0 * cos_gamma_t / cos_theta_t;
+        let transmittance = Color::new(
+            (-self.sigma_a.get_x() * distance).exp(),
+            (-self.sigma_a.get_y() * distance).exp(),
+            (-self.sigma_a.get_z() * distance).exp(),
+        );
+
+        let cos_gamma_o = safe_sqrt(1.0 - h * h);
+        let f = fresnel(cos_theta_o * cos_gamma_o, ETA);
+        let r = Color::new(f, f, f);
+        let tt = transmittance.multiply_constant((1.0 - f) * (1.0 - f));
+        let trt = tt.multiply(&transmittance).multiply_constant(f);
+        let rest = trt
+            .multiply(&transmittance)
+            .multiply_constant(f)
+            .divide(&Color::new(1.0, 1.0, 1.0).subtract(&transmittance.multiply_constant(f)));
+
+        ([r, tt, trt, rest], sin_gamma_t.asin())
+    }
+
+    /// The probability of choosing each order when sampling, proportional to its attenuation.
+    fn order_pdf(attenuation: &[Color; 4]) -> [f64; 4] {
+        let weights = attenuation.map(|a| (a.get_x() + a.get_y() + a.get_z()) / 3.0);
+        let total: f64 = weights.iter().sum();
+        weights.map(|weight| weight / total)
+    }
+
+    /// The attenuation of each order, and the product of its longitudinal and azimuthal terms
+    /// between two directions in the fiber frame.
+    fn terms(&self, h: f64, wo: &Vec3, wi: &Vec3) -> ([Color; 4], [f64; 4]) {
+        let (sin_theta_o, cos_theta_o, phi_o) = angles(wo);
+        let (sin_theta_i, cos_theta_i, phi_i) = angles(wi);
+        let (attenuation, gamma_t) = self.attenuation(sin_theta_o, cos_theta_o, h);
+        let gamma_o = h.clamp(-1.0, 1.0).asin();
+
+        let mut terms = [0.0; MAX_ORDER + 1];
+        for (p, term) in terms.iter_mut().enumerate() {
+            let (sin_theta_op, cos_theta_op) = self.tilted(p, sin_theta_o, cos_theta_o);
+            let azimuthal = if p < MAX_ORDER {
+                azimuthal(phi_i - phi_o, p, self.azimuthal_scale, gamma_o, gamma_t)
+            } else {
+                1.0 / (2.0 * PI)
+            };
+            *term = longitudinal(
+                cos_theta_i,
+                cos_theta_op,
+                sin_theta_i,
+                sin_theta_op,
+                self.variances[p],
+            ) * azimuthal;
+        }
+
+        (attenuation, terms)
+    }
+
+    fn local_directions(hit_record: &HitRecord, wo: &Vec3, wi: &Vec3) -> (f64, Vec3, Vec3) {
+        let (x, y, z) = Self::frame(hit_record, wo);
+        let to_local = |w: &Vec3| Vec3::new(w.dot(&x), w.dot(&y), w.dot(&z));
+        let h = 2.0 * hit_record.get_v() - 1.0;
+
+        (h, to_local(&wo.unit_vector()), to_local(&wi.unit_vector()))
+    }
+
+    /// The fiber response without the cosine that the integrators multiply by.
+    fn response(&self, hit_record: &HitRecord, wo: &Vec3, wi: &Vec3) -> Color {
+        let (h, wo, wi) = Self::local_directions(hit_record, wo, wi);
+        let (attenuation, terms) = self.terms(h, &wo, &wi);
+        attenuation
+            .iter()
+            .zip(terms)
+            .fold(BLACK, |total, (attenuation, term)| {
+                total.add(&attenuation.multiply_constant(term))
+            })
+    }
+}
+
+impl Material for Hair {
+    fn scatter(
+        &self,
+        ray: &Ray,
+        hit_record: &HitRecord,
+        sampler: &mut dyn Sampler,
+    ) -> Option<ScatterResult> {
+        let wo = ray.get_direction().unit_vector().negative();
+        let (x, y, z) = Self::frame(hit_record, &wo);
+        let h = 2.0 * hit_record.get_v() - 1.0;
+        let (sin_theta_o, cos_theta_o, phi_o) = angles(&Vec3::new(wo.dot(&x), 0.0, wo.dot(&z)));
+        let (attenuation, gamma_t) = self.attenuation(sin_theta_o, cos_theta_o, h);
+        let order_pdf = Self::order_pdf(&attenuation);
+
+        let (mut choice, azimuth_sample) = sampler.get_2d();
+        let mut p = 0;
+        while p < MAX_ORDER && choice >= order_pdf[p] {
+            choice -= order_pdf[p];
+            p += 1;
+        }
+
+        let (longitude_sample, turn) = sampler.get_2d();
+        let variance = self.variances[p];
+        let (sin_theta_op, cos_theta_op) = self.tilted(p, sin_theta_o, cos_theta_o);
+        let sample = longitude_sample.max(1e-5);
+        let cos_theta = 1.0 + variance * (sample + (1.0 - sample) * (-2.0 / variance).exp()).ln();
+        let sin_theta = safe_sqrt(1.0 - cos_theta * cos_theta);
+        let sin_theta_i =
+            -cos_theta * sin_theta_op + sin_theta * (2.0 * PI * turn).cos() * cos_theta_op;
+        let cos_theta_i = safe_sqrt(1.0 - sin_theta_i * sin_theta_i);
+
+        let delta_phi = if p < MAX_ORDER {
+            let gamma_o = h.clamp(-1.0, 1.0).asin();
+            phi(p, gamma_o, gamma_t)
+                + sample_trimmed_logistic(azimuth_sample, self.azimuthal_scale, -PI, PI)
+        } else {
+            2.0 * PI * azimuth_sample
+        };
+        let phi_i = phi_o + delta_phi;
+        let wi = x
+            .multiply_constant(sin_theta_i)
+            .add(&y.multiply_constant(cos_theta_i * phi_i.cos()))
+            .add(&z.multiply_constant(cos_theta_i * phi_i.sin()));
+
+        let pdf = self.pdf(hit_record, &wo, &wi);
+        if pdf <= 0.0 {
+            return None;
+        }
+        let color = self.response(hit_record, &wo, &wi).divide_constant(pdf);
+
+        Some(ScatterResult::new(
+            color,
+            Ray::new(hit_record.get_point(), wi),
+        ))
+    }
+
+    fn eval(&self, hit_record: &HitRecord, wo: &Vec3, wi: &Vec3) -> Color {
+        let cos = hit_record.get_normal().dot(&wi.unit_vector()).abs();
+        if cos == 0.0 {
+            return BLACK;
+        }
+
+        self.response(hit_record, wo, wi).divide_constant(cos)
+    }
+
+    fn pdf(&self, hit_record: &HitRecord, wo: &Vec3, wi: &Vec3) -> f64 {
+        let (h, wo, wi) = Self::local_directions(hit_record, wo, wi);
+        let (attenuation, terms) = self.terms(h, &wo, &wi);
+        Self::order_pdf(&attenuation)
+            .iter()
+            .zip(terms)
+            .map(|(probability, term)| probability * term)
+            .sum()
+    }
+
+    fn albedo(&self, _hit_record: &HitRecord) -> Color {
+        Color::new(
+            (-self.sigma_a.get_x()).exp(),
+            (-self.sigma_a.get_y()).exp(),
+            (-self.sigma_a.get_z()).exp(),
+        )
+    }
+}
+
+fn safe_sqrt(x: f64) -> f64 {
+    x.max(0.0).sqrt()
+}
+
+/// Sine and cosine of the angle from the normal plane, and the azimuth around the fiber.
+fn angles(w: &Vec3) -> (f64, f64, f64) {
+    let sin_theta = w.get_x().clamp(-1.0, 1.0);
+    (
+        sin_theta,
+        safe_sqrt(1.0 - sin_theta * sin_theta),
+        w.get_z().atan2(w.get_y()),
+    )
+}
+
+/// Unpolarized Fresnel reflectance of a dielectric entered from air.
+fn fresnel(cos_theta_i: f64, eta: f64) -> f64 {
+    let cos_theta_i = cos_theta_i.clamp(-1.0, 1.0);
+    let sin_theta_t = safe_sqrt(1.0 - cos_theta_i * cos_theta_i) / eta;
+    if sin_theta_t >= 1.0 {
+        return 1.0;
+    }
+    let cos_theta_t = safe_sqrt(1.0 - sin_theta_t * sin_theta_t);
+
+    let parallel = (eta * cos_theta_i - cos_theta_t) / (eta * cos_theta_i + cos_theta_t);
+    let perpendicular = (cos_theta_i - eta * cos_theta_t) / (cos_theta_i + eta * cos_theta_t);
+    (parallel * parallel + perpendicular * perpendicular) / 2.0
+}
+
+/// Modified Bessel function of the first kind of order zero.
+fn bessel_i0(x: f64) -> f64 {
+    let x2 = x * x;
+    let mut term = 1.0;
+    let mut sum = 1.0;
+    for i in 1..10 {
+        term *= x2 / (4.0 * (i * i) as f64);
+        sum += term;
+    }
+    sum
+}
+
+fn log_bessel_i0(x: f64) -> f64 {
+    if x > 12.0 {
+        x + 0.5 * (-(2.0 * PI).ln() + (1.0 / x).ln() + 1.0 / (8.0 * x))
+    } else {
+        bessel_i0(x).ln()
+    }
+}
+
+/// The longitudinal scattering function, in log space for small variances to avoid overflow.
+fn longitudinal(
+    cos_theta_i: f64,
+    cos_theta_o: f64,
+    sin_theta_i: f64,
+    sin_theta_o: f64,
+    variance: f64,
+) -> f64 {
+    let a = cos_theta_i * cos_theta_o / variance;
+    let b = sin_theta_i * sin_theta_o / variance;
+    if variance <= 0.1 {
+        (log_bessel_i0(a) - b - 1.0 / variance - variance.ln()).exp()
+    } else {
+        (-b).exp() * bessel_i0(a) / ((1.0 / variance).sinh() * 2.0 * variance)
+    }
+}
+
+/// The azimuthal angle by which order `p` leaves the fiber.
+fn phi(p: usize, gamma_o: f64, gamma_t: f64) -> f64 {
+    let p = p as f64;
+    2.0 * p * gamma_t - 2.0 * gamma_o + p * PI
+}
+
+fn azimuthal(phi_difference: f64, p: usize, scale: f64, gamma_o: f64, gamma_t: f64) -> f64 {
+    let mut delta = phi_difference - phi(p, gamma_o, gamma_t);
+    while delta > PI {
+        delta -= 2.0 * PI;
+    }
+    while delta < -PI {
+        delta += 2.0 * PI;
+    }
+
+    trimmed_logistic(delta, scale, -PI, PI)
+}
+
+fn logistic(x: f64, scale: f64) -> f64 {
+    let x = x.abs();
+    (-x / scale).exp() / (scale * (1.0 + (-x / scale).exp()).powi(2))
+}
+
+fn logistic_cdf(x: f64, scale: f64) -> f64 {
+    1.0 / (1.0 + (-x / scale).exp())
+}
+
+fn trimmed_logistic(x: f64, scale: f64, a: f64, b: f64) -> f64 {
+    logistic(x, scale) / (logistic_cdf(b, scale) - logistic_cdf(a, scale))
+}
+
+fn sample_trimmed_logistic(u: f64, scale: f64, a: f64, b: f64) -> f64 {
+    let k = logistic_cdf(b, scale) - logistic_cdf(a, scale);
+    let x = -scale * (1.0 / (u * k + logistic_cdf(a, scale)) - 1.0).ln();
+    x.clamp(a, b)
+}
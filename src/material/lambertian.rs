@@ -1,4 +1,12 @@
-use crate::{color::Color, hittable::HitRecord, ray::Ray, utils::random_unit_vector};
+use crate::{
+    color::{Color, BLACK},
+    constants::PI,
+    hittable::HitRecord,
+    ray::Ray,
+    sampler::Sampler,
+    utils::sample_cosine_direction,
+    vec3::Vec3,
+};
 
 use super::{Material, ScatterResult};
 
@@ -10,21 +18,45 @@ impl Lambertian {
     pub const fn new(color: Color) -> Self {
         Self { color }
     }
+
+    /// The albedo, multiplied by the vertex color of meshes that have them.
+    fn color(&self, hit_record: &HitRecord) -> Color {
+        hit_record
+            .get_color()
+            .map_or(self.color, |color| self.color.multiply(&color))
+    }
 }
 
 impl Material for Lambertian {
-    fn scatter(&self, ray: &Ray, hit_record: &HitRecord) -> Option<ScatterResult> {
-        let scatter_direction = hit_record.get_normal().add(&random_unit_vector());
+    fn scatter(
+        &self,
+        _ray: &Ray,
+        hit_record: &HitRecord,
+        sampler: &mut dyn Sampler,
+    ) -> Option<ScatterResult> {
+        let direction = sample_cosine_direction(&hit_record.get_normal(), sampler.get_2d());
+        let ray = Ray::new(hit_record.get_point(), direction);
+        let color = self.color(hit_record);
+
+        Some(ScatterResult::new(color, ray))
+    }
 
-        let direction = if scatter_direction.near_zero() {
-            hit_record.get_normal()
+    fn eval(&self, hit_record: &HitRecord, wo: &Vec3, wi: &Vec3) -> Color {
+        let normal = hit_record.get_normal();
+        if normal.dot(wo) * normal.dot(wi) > 0.0 {
+            self.color(hit_record).divide_constant(PI)
         } else {
-            scatter_direction
-        };
+            BLACK
+        }
+    }
 
-        let ray = Ray::new(hit_record.get_point(), direction);
-        let color = self.color;
+    fn albedo(&self, hit_record: &HitRecord) -> Color {
+        self.color(hit_record)
+    }
 
-        Some(ScatterResult::new(color, ray))
+    fn pdf(&self, hit_record: &HitRecord, wo: &Vec3, wi: &Vec3) -> f64 {
+        let normal = hit_record.get_normal();
+        let cos = normal.dot(&wi.unit_vector()) * normal.dot(wo).signum();
+        cos.max(0.0) / PI
     }
 }
@@ -1,7 +1,9 @@
 use crate::{
     color::Color,
+    hittable::HitRecord,
     ray::Ray,
-    utils::{random_in_unit_sphere, reflect},
+    sampler::Sampler,
+    utils::{reflect, sample_in_unit_sphere},
 };
 
 use super::{Material, ScatterResult};
@@ -21,15 +23,23 @@ impl Metal {
 }
 
 impl Material for Metal {
+    fn albedo(&self, _hit_record: &HitRecord) -> Color {
+        self.color
+    }
+
     fn scatter(
         &self,
-        ray: &crate::ray::Ray,
-        hit_record: &crate::hittable::HitRecord,
-    ) -> Option<super::ScatterResult> {
+        ray: &Ray,
+        hit_record: &HitRecord,
+        sampler: &mut dyn Sampler,
+    ) -> Option<ScatterResult> {
         let reflected = reflect(&ray.get_direction(), &hit_record.get_normal());
         let ray = Ray::new(
             hit_record.get_point(),
-            reflected.add(&random_in_unit_sphere().multiply_constant(self.fuzz)),
+            reflected.add(
+                &sample_in_unit_sphere(sampler.get_2d(), sampler.get_1d())
+                    .multiply_constant(self.fuzz),
+            ),
         );
         let color = self.color;
 
@@ -39,4 +49,8 @@ impl Material for Metal {
             None
         }
     }
+
+    fn is_specular(&self) -> bool {
+        true
+    }
 }
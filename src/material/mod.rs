@@ -1,14 +1,53 @@
-use crate::{hittable::HitRecord, ray::Ray};
-pub use dielectric::Dielectric;
+use crate::{
+    color::{Color, BLACK, WHITE},
+    hittable::HitRecord,
+    ray::Ray,
+    sampler::Sampler,
+    vec3::Vec3,
+};
+pub use dielectric::{Dielectric, Dispersion};
+pub use diffuse_light::DiffuseLight;
+pub use hair::Hair;
 pub use lambertian::Lambertian;
 pub use metal::Metal;
 pub use scatter_result::ScatterResult;
 
 mod dielectric;
+mod diffuse_light;
+mod hair;
 mod lambertian;
 mod metal;
 mod scatter_result;
 
 pub trait Material {
-    fn scatter(&self, ray: &Ray, hit_record: &HitRecord) -> Option<ScatterResult>;
+    fn scatter(
+        &self,
+        ray: &Ray,
+        hit_record: &HitRecord,
+        sampler: &mut dyn Sampler,
+    ) -> Option<ScatterResult>;
+
+    fn emitted(&self, _hit_record: &HitRecord) -> Color {
+        BLACK
+    }
+
+    fn eval(&self, _hit_record: &HitRecord, _wo: &Vec3, _wi: &Vec3) -> Color {
+        BLACK
+    }
+
+    fn pdf(&self, _hit_record: &HitRecord, _wo: &Vec3, _wi: &Vec3) -> f64 {
+        0.0
+    }
+
+    fn albedo(&self, _hit_record: &HitRecord) -> Color {
+        WHITE
+    }
+
+    fn is_specular(&self) -> bool {
+        false
+    }
+
+    fn is_dispersive(&self) -> bool {
+        false
+    }
 }
@@ -0,0 +1,160 @@
+use crate::vec3::Vec3;
+
+#[derive(Clone, Copy)]
+pub struct Matrix3 {
+    rows: [[f64; 3]; 3],
+}
+
+impl Matrix3 {
+    pub const fn new(rows: [[f64; 3]; 3]) -> Self {
+        Self { rows }
+    }
+
+    pub const fn diagonal(values: &Vec3) -> Self {
+        Self::new([
+            [values.get_x(), 0.0, 0.0],
+            [0.0, values.get_y(), 0.0],
+            [0.0, 0.0, values.get_z()],
+        ])
+    }
+
+    pub fn multiply(&self, other: &Self) -> Self {
+        let mut rows = [[0.0; 3]; 3];
+        for (i, row) in rows.iter_mut().enumerate() {
+            for (j, value) in row.iter_mut().enumerate() {
+                *value = (0..3).map(|k| self.rows[i][k] * other.rows[k][j]).sum();
+            }
+        }
+
+        Self::new(rows)
+    }
+
+    pub fn multiply_vector(&self, vector: &Vec3) -> Vec3 {
+        let row = |row: &[f64; 3]| vector.dot(&Vec3::new(row[0], row[1], row[2]));
+        Vec3::new(row(&self.rows[0]), row(&self.rows[1]), row(&self.rows[2]))
+    }
+
+    pub fn transpose(&self) -> Self {
+        let mut rows = [[0.0; 3]; 3];
+        for (i, row) in rows.iter_mut().enumerate() {
+            for (j, value) in row.iter_mut().enumerate() {
+                *value = self.rows[j][i];
+            }
+        }
+
+        Self::new(rows)
+    }
+
+    pub fn determinant(&self) -> f64 {
+        let [a, b, c] = self.rows.map(|row| Vec3::new(row[0], row[1], row[2]));
+        a.dot(&b.cross(&c))
+    }
+
+    /// The inverse, or `None` for a singular matrix.
+    pub fn inverse(&self) -> Option<Self> {
+        let determinant = self.determinant();
+        if determinant == 0.0 {
+            return None;
+        }
+
+        // The columns of the inverse are the cross products of the rows, over the determinant.
+        let [a, b, c] = self.rows.map(|row| Vec3::new(row[0], row[1], row[2]));
+        let columns = [b.cross(&c), c.cross(&a), a.cross(&b)]
+            .map(|column| column.divide_constant(determinant));
+        Some(
+            Self::new(columns.map(|column| [column.get_x(), column.get_y(), column.get_z()]))
+                .transpose(),
+        )
+    }
+}
+
+/// An affine transform; the bottom row is always `0 0 0 1`.
+#[derive(Clone, Copy)]
+pub struct Matrix4 {
+    rows: [[f64; 4]; 4],
+}
+
+impl Matrix4 {
+    pub const fn new(rows: [[f64; 4]; 4]) -> Self {
+        Self { rows }
+    }
+
+    pub const fn identity() -> Self {
+        Self::new([
+            [1.0, 0.0, 0.0, 0.0],
+            [0.0, 1.0, 0.0, 0.0],
+            [0.0, 0.0, 1.0, 0.0],
+            [0.0, 0.0, 0.0, 1.0],
+        ])
+    }
+
+    /// Scales, then rotates by the unit quaternion `[x, y, z, w]`, then translates.
+    pub fn from_translation_rotation_scale(
+        translation: &Vec3,
+        [x, y, z, w]: [f64; 4],
+        scale: &Vec3,
+    ) -> Self {
+        let rotation = [
+            [
+                1.0 - 2.0 * (y * y + z * z),
+                2.0 * (x * y - z * w),
+                2.0 * (x * z + y * w),
+            ],
+            [
+                2.0 * (x * y + z * w),
+                1.0 - 2.0 * (x * x + z * z),
+                2.0 * (y * z - x * w),
+            ],
+            [
+                2.0 * (x * z - y * w),
+                2.0 * (y * z + x * w),
+                1.0 - 2.0 * (x * x + y * y),
+            ],
+        ];
+        let scale = [scale.get_x(), scale.get_y(), scale.get_z()];
+        let translation = [
+            translation.get_x(),
+            translation.get_y(),
+            translation.get_z(),
+        ];
+
+        let mut rows = Self::identity().rows;
+        for i in 0..3 {
+            for j in 0..3 {
+                rows[i][j] = rotation[i][j] * scale[j];
+            }
+            rows[i][3] = translation[i];
+        }
+
+        Self::new(rows)
+    }
+
+    pub fn multiply(&self, other: &Self) -> Self {
+        let mut rows = [[0.0; 4]; 4];
+        for (i, row) in rows.iter_mut().enumerate() {
+            for (j, value) in row.iter_mut().enumerate() {
+                *value = (0..4).map(|k| self.rows[i][k] * other.rows[k][j]).sum();
+            }
+        }
+
+        Self::new(rows)
+    }
+
+    pub fn transform_point(&self, point: &Vec3) -> Vec3 {
+        self.linear().multiply_vector(point).add(&Vec3::new(
+            self.rows[0][3],
+            self.rows[1][3],
+            self.rows[2][3],
+        ))
+    }
+
+    pub fn transform_vector(&self, vector: &Vec3) -> Vec3 {
+        self.linear().multiply_vector(vector)
+    }
+
+    /// The upper left 3x3 part, without the translation.
+    pub fn linear(&self) -> Matrix3 {
+        let [a, b, c, _] = self.rows.map(|row| [row[0], row[1], row[2]]);
+        Matrix3::new([a, b, c])
+    }
+}
@@ -0,0 +1,221 @@
+use std::rc::Rc;
+
+use crate::{
+    aabb::Aabb,
+    bvh::BvhNode,
+    color::Color,
+    hittable::{HitRecord, Hittable},
+    material::Material,
+    ray::Ray,
+    stats,
+    vec3::{Point3, Vec3},
+};
+
+const EPSILON: f64 = 1e-12;
+
+/// Indexed triangles with optional per-vertex normals, texture coordinates and colors. Without
+/// normals the triangles are flat shaded, and without texture coordinates u and v are
+/// barycentric.
+pub struct TriangleMesh {
+    positions: Vec<Point3>,
+    normals: Vec<Vec3>,
+    uvs: Vec<(f64, f64)>,
+    colors: Vec<Color>,
+    triangles: Vec<[usize; 3]>,
+}
+
+impl TriangleMesh {
+    pub const fn new(positions: Vec<Point3>, triangles: Vec<[usize; 3]>) -> Self {
+        Self {
+            positions,
+            normals: Vec::new(),
+            uvs: Vec::new(),
+            colors: Vec::new(),
+            triangles,
+        }
+    }
+
+    pub fn with_normals(self, normals: Vec<Vec3>) -> Self {
+        Self { normals, ..self }
+    }
+
+    pub fn with_uvs(self, uvs: Vec<(f64, f64)>) -> Self {
+        Self { uvs, ..self }
+    }
+
+    pub fn with_colors(self, colors: Vec<Color>) -> Self {
+        Self { colors, ..self }
+    }
+
+    /// Scales the mesh about the origin by a positive `scale`.
+    pub fn scaled(self, scale: f64) -> Self {
+        let positions = self
+            .positions
+            .iter()
+            .map(|position| position.multiply_constant(scale))
+            .collect();
+        Self { positions, ..self }
+    }
+
+    /// Vertex normals averaged from the faces around each vertex, weighted by their area.
+    pub fn with_smooth_normals(self) -> Self {
+        let mut normals = vec![Vec3::new(0.0, 0.0, 0.0); self.positions.len()];
+        for triangle in &self.triangles {
+            let [a, b, c] = triangle.map(|index| self.positions[index]);
+            let normal = b.subtract(&a).cross(&c.subtract(&a));
+            for index in triangle {
+                normals[*index] = normals[*index].add(&normal);
+            }
+        }
+
+        let normals = normals
+            .into_iter()
+            .map(|normal| {
+                if normal.length() > EPSILON {
+                    normal.unit_vector()
+                } else {
+                    normal
+                }
+            })
+            .collect();
+        self.with_normals(normals)
+    }
+}
+
+struct Triangle {
+    mesh: Rc<TriangleMesh>,
+    index: usize,
+    material: Rc<dyn Material>,
+}
+
+impl Triangle {
+    fn vertices(&self) -> [usize; 3] {
+        self.mesh.triangles[self.index]
+    }
+}
+
+impl Hittable for Triangle {
+    /// Möller-Trumbore intersection.
+    fn hit(&self, ray: &Ray, t_min: f64, t_max: f64) -> Option<HitRecord> {
+        stats::record_intersection_test();
+        let vertices = self.vertices();
+        let [a, b, c] = vertices.map(|index| self.mesh.positions[index]);
+        let edge1 = b.subtract(&a);
+        let edge2 = c.subtract(&a);
+
+        let direction = ray.get_direction();
+        let p = direction.cross(&edge2);
+        let determinant = edge1.dot(&p);
+        if determinant.abs() < EPSILON {
+            return None;
+        }
+
+        let inverse = 1.0 / determinant;
+        let s = ray.get_origin().subtract(&a);
+        let beta = s.dot(&p) * inverse;
+        if !(0.0..=1.0).contains(&beta) {
+            return None;
+        }
+
+        let q = s.cross(&edge1);
+        let gamma = direction.dot(&q) * inverse;
+        if gamma < 0.0 || beta + gamma > 1.0 {
+            return None;
+        }
+
+        let t = edge2.dot(&q) * inverse;
+        if t < t_min || t > t_max {
+            return None;
+        }
+
+        let alpha = 1.0 - beta - gamma;
+        let geometric_normal = edge1.cross(&edge2).unit_vector();
+        let outward_normal = if self.mesh.normals.is_empty() {
+            geometric_normal
+        } else {
+            let [na, nb, nc] = vertices.map(|index| self.mesh.normals[index]);
+            let normal = na
+                .multiply_constant(alpha)
+                .add(&nb.multiply_constant(beta))
+                .add(&nc.multiply_constant(gamma));
+            // Interpolated normals can tilt past the face near silhouettes, which would flip
+            // which side the hit is on.
+            if normal.dot(&geometric_normal) < 0.0 {
+                normal.negative().unit_vector()
+            } else {
+                normal.unit_vector()
+            }
+        };
+        let uv = if self.mesh.uvs.is_empty() {
+            (beta, gamma)
+        } else {
+            let [ta, tb, tc] = vertices.map(|index| self.mesh.uvs[index]);
+            (
+                alpha * ta.0 + beta * tb.0 + gamma * tc.0,
+                alpha * ta.1 + beta * tb.1 + gamma * tc.1,
+            )
+        };
+
+        let record = HitRecord::new(
+            ray.at(t),
+            outward_normal,
+            t,
+            uv,
+            ray,
+            Rc::clone(&self.material),
+        );
+        if self.mesh.colors.is_empty() {
+            return Some(record);
+        }
+
+        let [ca, cb, cc] = vertices.map(|index| self.mesh.colors[index]);
+        Some(
+            record.with_color(
+                ca.multiply_constant(alpha)
+                    .add(&cb.multiply_constant(beta))
+                    .add(&cc.multiply_constant(gamma)),
+            ),
+        )
+    }
+
+    fn bounding_box(&self) -> Option<Aabb> {
+        let [a, b, c] = self.vertices().map(|index| self.mesh.positions[index]);
+        // Axis-aligned triangles would give a flat box, which the slab test can miss.
+        Some(Aabb::new(a, b).surrounding(&Aabb::new(c, c)).expand(1e-6))
+    }
+}
+
+/// A triangle mesh with one material, traced through its own BVH.
+pub struct Mesh {
+    root: Option<Rc<dyn Hittable>>,
+}
+
+impl Mesh {
+    pub fn new(mesh: TriangleMesh, material: Rc<dyn Material>) -> Self {
+        let mesh = Rc::new(mesh);
+        let triangles = (0..mesh.triangles.len())
+            .map(|index| {
+                let triangle: Rc<dyn Hittable> = Rc::new(Triangle {
+                    mesh: Rc::clone(&mesh),
+                    index,
+                    material: Rc::clone(&material),
+                });
+                triangle
+            })
+            .collect();
+
+        Self {
+            root: BvhNode::build(triangles),
+        }
+    }
+}
+
+impl Hittable for Mesh {
+    fn hit(&self, ray: &Ray, t_min: f64, t_max: f64) -> Option<HitRecord> {
+        self.root.as_ref()?.hit(ray, t_min, t_max)
+    }
+
+    fn bounding_box(&self) -> Option<Aabb> {
+        self.root.as_ref()?.bounding_box()
+    }
+}
@@ -0,0 +1,316 @@
+use std::{env, str::FromStr};
+
+pub struct Options {
+    integrator: String,
+    scene: String,
+    sampler: String,
+    filter: String,
+    photons: usize,
+    adaptive_threshold: Option<f64>,
+    max_samples: usize,
+    heatmap: Option<String>,
+    aovs: Vec<(String, String)>,
+    denoiser: Option<String>,
+    exposure: f64,
+    white_balance: Option<f64>,
+    tone_mapper: String,
+    lut: Option<String>,
+    samples_per_pixel: Option<usize>,
+    checkpoint: Option<String>,
+    checkpoint_interval: u64,
+    resume: Option<String>,
+    preview: bool,
+    focal_length: Option<f64>,
+    f_number: Option<f64>,
+    sensor_width: f64,
+    blades: Option<usize>,
+    blade_rotation: f64,
+    aperture_mask: Option<String>,
+    cat_eye: f64,
+    projection: Option<String>,
+    fisheye_fov: f64,
+    interocular: f64,
+    lens_file: Option<String>,
+    convergence: Option<f64>,
+    views: usize,
+    view_files: Option<String>,
+    scene_file: Option<String>,
+    frames: Option<(usize, usize)>,
+    output: String,
+}
+
+impl Options {
+    pub fn from_args() -> Result<Self, String> {
+        let mut options = Self {
+            integrator: String::from("path"),
+            scene: String::from("random"),
+            sampler: String::from("random"),
+            filter: String::from("box"),
+            photons: 200_000,
+            adaptive_threshold: None,
+            max_samples: 1024,
+            heatmap: None,
+            aovs: Vec::new(),
+            denoiser: None,
+            exposure: 0.0,
+            white_balance: None,
+            tone_mapper: String::from("linear"),
+            lut: None,
+            samples_per_pixel: None,
+            checkpoint: None,
+            checkpoint_interval: 60,
+            resume: None,
+            preview: false,
+            focal_length: None,
+            f_number: None,
+            sensor_width: 36.0,
+            blades: None,
+            blade_rotation: 0.0,
+            aperture_mask: None,
+            cat_eye: 0.0,
+            projection: None,
+            fisheye_fov: 180.0,
+            interocular: 0.064,
+            lens_file: None,
+            convergence: None,
+            views: 2,
+            view_files: None,
+            scene_file: None,
+            frames: None,
+            output: String::from("frame_{frame}.ppm"),
+        };
+
+        let mut args = env::args().skip(1);
+        while let Some(arg) = args.next() {
+            if arg == "--preview" {
+                options.preview = true;
+                continue;
+            }
+            let mut value = || args.next().ok_or(format!("missing value for {}", arg));
+
+            match arg.as_str() {
+                "--integrator" => options.integrator = value()?,
+                "--scene" => options.scene = value()?,
+                "--sampler" => options.sampler = value()?,
+                "--filter" => options.filter = value()?,
+                "--photons" => options.photons = parse(&arg, value()?)?,
+                "--adaptive" => options.adaptive_threshold = Some(parse(&arg, value()?)?),
+                "--max-spp" => options.max_samples = parse(&arg, value()?)?,
+                "--heatmap" => options.heatmap = Some(value()?),
+                "--denoise" => options.denoiser = Some(value()?),
+                "--exposure" => options.exposure = parse(&arg, value()?)?,
+                "--white-balance" => options.white_balance = Some(parse(&arg, value()?)?),
+                "--tonemap" => options.tone_mapper = value()?,
+                "--lut" => options.lut = Some(value()?),
+                "--spp" => options.samples_per_pixel = Some(parse(&arg, value()?)?),
+                "--checkpoint" => options.checkpoint = Some(value()?),
+                "--checkpoint-interval" => options.checkpoint_interval = parse(&arg, value()?)?,
+                "--resume" => options.resume = Some(value()?),
+                "--focal-length" => options.focal_length = Some(parse(&arg, value()?)?),
+                "--f-number" => options.f_number = Some(parse(&arg, value()?)?),
+                "--sensor-width" => options.sensor_width = parse(&arg, value()?)?,
+                "--blades" => options.blades = Some(parse(&arg, value()?)?),
+                "--blade-rotation" => options.blade_rotation = parse(&arg, value()?)?,
+                "--aperture-mask" => options.aperture_mask = Some(value()?),
+                "--cat-eye" => options.cat_eye = parse(&arg, value()?)?,
+                "--projection" => options.projection = Some(value()?),
+                "--fisheye-fov" => options.fisheye_fov = parse(&arg, value()?)?,
+                "--interocular" => options.interocular = parse(&arg, value()?)?,
+                "--lens-file" => options.lens_file = Some(value()?),
+                "--convergence" => options.convergence = Some(parse(&arg, value()?)?),
+                "--views" => options.views = parse(&arg, value()?)?,
+                "--view-files" => options.view_files = Some(value()?),
+                "--scene-file" => options.scene_file = Some(value()?),
+                "--frames" => {
+                    let value = value()?;
+                    let range = value
+                        .split_once("..")
+                        .and_then(|(first, last)| Some((first.parse().ok()?, last.parse().ok()?)))
+                        .filter(|(first, last)| first <= last)
+                        .ok_or(format!(
+                            "invalid value {} for {}, expected first..last",
+                            value, arg
+                        ))?;
+                    options.frames = Some(range);
+                }
+                "--output" => options.output = value()?,
+                "--aov" => {
+                    let value = value()?;
+                    let (name, path) = value.split_once('=').ok_or(format!(
+                        "invalid value {} for {}, expected name=path",
+                        value, arg
+                    ))?;
+                    options.aovs.push((name.to_string(), path.to_string()));
+                }
+                _ => return Err(format!("unknown argument {}", arg)),
+            }
+        }
+
+        // SPPM keeps its per-pixel photon statistics outside the film, so a checkpoint
+        // couldn't resume it.
+        if options.integrator == "sppm"
+            && (options.checkpoint.is_some() || options.resume.is_some())
+        {
+            return Err(String::from(
+                "--checkpoint and --resume are not supported with --integrator sppm",
+            ));
+        }
+
+        Ok(options)
+    }
+
+    pub fn get_integrator(&self) -> &str {
+        &self.integrator
+    }
+
+    pub fn get_scene(&self) -> &str {
+        &self.scene
+    }
+
+    pub fn get_sampler(&self) -> &str {
+        &self.sampler
+    }
+
+    pub fn get_filter(&self) -> &str {
+        &self.filter
+    }
+
+    pub const fn get_photons(&self) -> usize {
+        self.photons
+    }
+
+    pub const fn get_adaptive_threshold(&self) -> Option<f64> {
+        self.adaptive_threshold
+    }
+
+    pub const fn get_max_samples(&self) -> usize {
+        self.max_samples
+    }
+
+    pub fn get_heatmap(&self) -> Option<&str> {
+        self.heatmap.as_deref()
+    }
+
+    pub fn get_denoiser(&self) -> Option<&str> {
+        self.denoiser.as_deref()
+    }
+
+    pub const fn get_exposure(&self) -> f64 {
+        self.exposure
+    }
+
+    pub const fn get_white_balance(&self) -> Option<f64> {
+        self.white_balance
+    }
+
+    pub fn get_tone_mapper(&self) -> &str {
+        &self.tone_mapper
+    }
+
+    pub fn get_lut(&self) -> Option<&str> {
+        self.lut.as_deref()
+    }
+
+    pub const fn get_samples_per_pixel(&self) -> Option<usize> {
+        self.samples_per_pixel
+    }
+
+    /// Path pattern for checkpoints, with `{frame}` replaced as in `get_output`.
+    pub fn get_checkpoint(&self) -> Option<&str> {
+        self.checkpoint.as_deref()
+    }
+
+    pub const fn get_checkpoint_interval(&self) -> u64 {
+        self.checkpoint_interval
+    }
+
+    pub fn get_resume(&self) -> Option<&str> {
+        self.resume.as_deref()
+    }
+
+    pub const fn get_preview(&self) -> bool {
+        self.preview
+    }
+
+    pub const fn get_focal_length(&self) -> Option<f64> {
+        self.focal_length
+    }
+
+    pub const fn get_f_number(&self) -> Option<f64> {
+        self.f_number
+    }
+
+    pub const fn get_sensor_width(&self) -> f64 {
+        self.sensor_width
+    }
+
+    pub const fn get_blades(&self) -> Option<usize> {
+        self.blades
+    }
+
+    pub const fn get_blade_rotation(&self) -> f64 {
+        self.blade_rotation
+    }
+
+    pub fn get_aperture_mask(&self) -> Option<&str> {
+        self.aperture_mask.as_deref()
+    }
+
+    pub const fn get_cat_eye(&self) -> f64 {
+        self.cat_eye
+    }
+
+    /// Replaces the projection the scene's camera was set up with.
+    pub fn get_projection(&self) -> Option<&str> {
+        self.projection.as_deref()
+    }
+
+    pub const fn get_fisheye_fov(&self) -> f64 {
+        self.fisheye_fov
+    }
+
+    pub const fn get_interocular(&self) -> f64 {
+        self.interocular
+    }
+
+    pub fn get_lens_file(&self) -> Option<&str> {
+        self.lens_file.as_deref()
+    }
+
+    pub const fn get_convergence(&self) -> Option<f64> {
+        self.convergence
+    }
+
+    pub const fn get_views(&self) -> usize {
+        self.views
+    }
+
+    /// Path pattern for writing each view to its own file, with `{}` replaced by the view index.
+    pub fn get_view_files(&self) -> Option<&str> {
+        self.view_files.as_deref()
+    }
+
+    pub fn get_scene_file(&self) -> Option<&str> {
+        self.scene_file.as_deref()
+    }
+
+    /// Inclusive range of frames to render as an image sequence.
+    pub const fn get_frames(&self) -> Option<(usize, usize)> {
+        self.frames
+    }
+
+    /// Path pattern for image sequence frames, with `{frame}` replaced by the frame number.
+    pub fn get_output(&self) -> &str {
+        &self.output
+    }
+
+    pub fn get_aovs(&self) -> &[(String, String)] {
+        &self.aovs
+    }
+}
+
+fn parse<T: FromStr>(arg: &str, value: String) -> Result<T, String> {
+    value
+        .parse()
+        .map_err(|_| format!("invalid value {} for {}", value, arg))
+}
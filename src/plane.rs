@@ -0,0 +1,56 @@
+use std::rc::Rc;
+
+use crate::{
+    aabb::Aabb,
+    frame::Frame,
+    hittable::{HitRecord, Hittable},
+    material::Material,
+    ray::Ray,
+    stats,
+    vec3::{Point3, Vec3},
+};
+
+/// An infinite plane, with UVs repeating every unit along two axes in the plane.
+pub struct Plane {
+    frame: Frame,
+    material: Rc<dyn Material>,
+}
+
+impl Plane {
+    pub fn new(point: Point3, normal: Vec3, material: Rc<dyn Material>) -> Self {
+        Self {
+            frame: Frame::new(point, &normal),
+            material,
+        }
+    }
+}
+
+impl Hittable for Plane {
+    fn hit(&self, ray: &Ray, t_min: f64, t_max: f64) -> Option<HitRecord> {
+        stats::record_intersection_test();
+        let origin = self.frame.to_local_point(&ray.get_origin());
+        let direction = self.frame.to_local_vector(&ray.get_direction());
+        if direction.get_y() == 0.0 {
+            return None;
+        }
+
+        let t = -origin.get_y() / direction.get_y();
+        if !(t_min..=t_max).contains(&t) {
+            return None;
+        }
+
+        let local = origin.add(&direction.multiply_constant(t));
+        Some(HitRecord::new(
+            ray.at(t),
+            self.frame.get_axis(),
+            t,
+            (local.get_x().rem_euclid(1.0), local.get_z().rem_euclid(1.0)),
+            ray,
+            Rc::clone(&self.material),
+        ))
+    }
+
+    fn bounding_box(&self) -> Option<Aabb> {
+        None
+    }
+}
@@ -0,0 +1,579 @@
+use std::fs;
+
+use crate::{
+    color::Color,
+    color_pipeline::srgb_eotf,
+    mesh::TriangleMesh,
+    vec3::{Point3, Vec3},
+};
+
+#[derive(Clone, Copy, PartialEq)]
+enum Format {
+    Ascii,
+    LittleEndian,
+    BigEndian,
+}
+
+#[derive(Clone, Copy)]
+enum Scalar {
+    Int8,
+    Uint8,
+    Int16,
+    Uint16,
+    Int32,
+    Uint32,
+    Float32,
+    Float64,
+}
+
+impl Scalar {
+    fn from_name(name: &str) -> Option<Self> {
+        let scalar = match name {
+            "char" | "int8" => Self::Int8,
+            "uchar" | "uint8" => Self::Uint8,
+            "short" | "int16" => Self::Int16,
+            "ushort" | "uint16" => Self::Uint16,
+            "int" | "int32" => Self::Int32,
+            "uint" | "uint32" => Self::Uint32,
+            "float" | "float32" => Self::Float32,
+            "double" | "float64" => Self::Float64,
+            _ => return None,
+        };
+
+        Some(scalar)
+    }
+
+    const fn size(self) -> usize {
+        match self {
+            Self::Int8 | Self::Uint8 => 1,
+            Self::Int16 | Self::Uint16 => 2,
+            Self::Int32 | Self::Uint32 | Self::Float32 => 4,
+            Self::Float64 => 8,
+        }
+    }
+
+    /// Integer colors are stored as a fraction of the largest value of their type.
+    const fn color_scale(self) -> f64 {
+        match self {
+            Self::Int8 => 127.0,
+            Self::Uint8 => 255.0,
+            Self::Int16 => 32767.0,
+            Self::Uint16 => 65535.0,
+            Self::Int32 => 2147483647.0,
+            Self::Uint32 => 4294967295.0,
+            Self::Float32 | Self::Float64 => 1.0,
+        }
+    }
+
+    const fn is_integer(self) -> bool {
+        !matches!(self, Self::Float32 | Self::Float64)
+    }
+}
+
+#[derive(Clone, Copy)]
+enum Property {
+    Scalar(Scalar),
+    List { count: Scalar, item: Scalar },
+}
+
+struct Element {
+    name: String,
+    count: usize,
+    properties: Vec<(String, Property)>,
+}
+
+impl Element {
+    fn scalar(&self, names: &[&str]) -> Option<(usize, Scalar)> {
+        self.properties
+            .iter()
+            .enumerate()
+            .find_map(|(index, (name, property))| match property {
+                Property::Scalar(scalar) if names.contains(&name.as_str()) => {
+                    Some((index, *scalar))
+                }
+                _ => None,
+            })
+    }
+
+    fn scalars<const N: usize>(&self, names: [&[&str]; N]) -> Option<[(usize, Scalar); N]> {
+        let mut columns = [(0, Scalar::Float32); N];
+        for (column, names) in columns.iter_mut().zip(names) {
+            *column = self.scalar(names)?;
+        }
+
+        Some(columns)
+    }
+}
+
+struct Reader<'a> {
+    data: &'a [u8],
+    position: usize,
+    format: Format,
+}
+
+impl Reader<'_> {
+    fn read(&mut self, scalar: Scalar) -> Result<f64, String> {
+        if self.format == Format::Ascii {
+            while self
+                .data
+                .get(self.position)
+                .is_some_and(u8::is_ascii_whitespace)
+            {
+                self.position += 1;
+            }
+            let start = self.position;
+            while self
+                .data
+                .get(self.position)
+                .is_some_and(|byte| !byte.is_ascii_whitespace())
+            {
+                self.position += 1;
+            }
+            let token = &self.data[start..self.position];
+            if token.is_empty() {
+                return Err("unexpected end of file".to_string());
+            }
+            return std::str::from_utf8(token)
+                .ok()
+                .and_then(|token| token.parse().ok())
+                .ok_or_else(|| format!("invalid number {}", String::from_utf8_lossy(token)));
+        }
+
+        let size = scalar.size();
+        let bytes = self
+            .data
+            .get(self.position..)
+            .and_then(|rest| rest.get(..size))
+            .ok_or("unexpected end of file")?;
+        self.position += size;
+
+        // Brought into little endian order, padded to eight bytes.
+        let mut b = [0; 8];
+        b[..size].copy_from_slice(bytes);
+        if self.format == Format::BigEndian {
+            b[..size].reverse();
+        }
+        Ok(match scalar {
+            Scalar::Int8 => f64::from(b[0] as i8),
+            Scalar::Uint8 => f64::from(b[0]),
+            Scalar::Int16 => f64::from(i16::from_le_bytes([b[0], b[1]])),
+            Scalar::Uint16 => f64::from(u16::from_le_bytes([b[0], b[1]])),
+            Scalar::Int32 => f64::from(i32::from_le_bytes([b[0], b[1], b[2], b[3]])),
+            Scalar::Uint32 => f64::from(u32::from_le_bytes([b[0], b[1], b[2], b[3]])),
+            Scalar::Float32 => f64::from(f32::from_le_bytes([b[0], b[1], b[2], b[3]])),
+            Scalar::Float64 => f64::from_le_bytes(b),
+        })
+    }
+
+    /// Reads one instance of an element, with a value per property. Lists other than `list`
+    /// are skipped; the items of `list` go into `items`.
+    fn read_instance(
+        &mut self,
+        element: &Element,
+        list: Option<usize>,
+        values: &mut [f64],
+        items: &mut Vec<f64>,
+    ) -> Result<(), String> {
+        for (index, (value, (_, property))) in
+            values.iter_mut().zip(&element.properties).enumerate()
+        {
+            *value = match property {
+                Property::Scalar(scalar) => self.read(*scalar)?,
+                Property::List { count, item } => {
+                    let count = whole(self.read(*count)?).ok_or("invalid list length")?;
+                    if list == Some(index) {
+                        items.clear();
+                        for _ in 0..count {
+                            items.push(self.read(*item)?);
+                        }
+                    } else if self.format == Format::Ascii {
+                        for _ in 0..count {
+                            self.read(*item)?;
+                        }
+                    } else {
+                        self.position = self
+                            .position
+                            .saturating_add(count.saturating_mul(item.size()));
+                    }
+                    count as f64
+                }
+            };
+        }
+
+        Ok(())
+    }
+
+    /// How many instances of `element` to reserve room for. The count in the header is not
+    /// trusted beyond what the rest of the file could hold, at a byte or more per property.
+    fn capacity(&self, element: &Element) -> usize {
+        let remaining = self.data.len().saturating_sub(self.position);
+        element
+            .count
+            .min(remaining / element.properties.len().max(1))
+    }
+}
+
+/// The value as an index or count, if it is a non-negative whole number.
+fn whole(value: f64) -> Option<usize> {
+    (value >= 0.0 && value.fract() == 0.0).then_some(value as usize)
+}
+
+/// Reads a mesh from an ASCII or binary PLY file. Vertices need `x`, `y` and `z` and may have
+/// normals, texture coordinates and colors, with integer colors taken to be sRGB encoded. Faces
+/// are polygons, split into triangle fans, and other elements are skipped.
+pub fn load_ply(path: &str) -> Result<TriangleMesh, String> {
+    let data = fs::read(path).map_err(|error| format!("failed to read {}: {}", path, error))?;
+    parse(&data).map_err(|message| format!("{}: {}", path, message))
+}
+
+fn parse(data: &[u8]) -> Result<TriangleMesh, String> {
+    let (format, elements, body) = parse_header(data)?;
+    let mut reader = Reader {
+        data: &data[body..],
+        position: 0,
+        format,
+    };
+
+    let mut positions = Vec::new();
+    let mut normals = Vec::new();
+    let mut uvs = Vec::new();
+    let mut colors = Vec::new();
+    let mut triangles = Vec::new();
+    let mut items = Vec::new();
+    let mut indices = Vec::new();
+    for element in &elements {
+        let mut values = vec![0.0; element.properties.len()];
+        match element.name.as_str() {
+            "vertex" => {
+                let [x, y, z] = element
+                    .scalars([&["x"], &["y"], &["z"]])
+                    .ok_or("vertices without x, y and z")?;
+                let normal = element.scalars([&["nx"], &["ny"], &["nz"]]);
+                let uv = element.scalars([
+                    &["u", "s", "texture_u", "texture_s"],
+                    &["v", "t", "texture_v", "texture_t"],
+                ]);
+                let color = element.scalars([
+                    &["red", "diffuse_red"],
+                    &["green", "diffuse_green"],
+                    &["blue", "diffuse_blue"],
+                ]);
+
+                positions.reserve(reader.capacity(element));
+                for _ in 0..element.count {
+                    reader.read_instance(element, None, &mut values, &mut items)?;
+                    positions.push(Point3::new(values[x.0], values[y.0], values[z.0]));
+                    if let Some([nx, ny, nz]) = normal {
+                        normals.push(Vec3::new(values[nx.0], values[ny.0], values[nz.0]));
+                    }
+                    if let Some([u, v]) = uv {
+                        uvs.push((values[u.0], values[v.0]));
+                    }
+                    if let Some(channels) = color {
+                        let [r, g, b] = channels.map(|(index, scalar)| {
+                            let value = values[index] / scalar.color_scale();
+                            if scalar.is_integer() {
+                                srgb_eotf(value)
+                            } else {
+                                value
+                            }
+                        });
+                        colors.push(Color::new(r, g, b));
+                    }
+                }
+            }
+            "face" => {
+                let list = element
+                    .properties
+                    .iter()
+                    .position(|(name, property)| {
+                        matches!(property, Property::List { .. })
+                            && (name == "vertex_indices" || name == "vertex_index")
+                    })
+                    .ok_or("faces without vertex_indices")?;
+
+                triangles.reserve(reader.capacity(element));
+                for _ in 0..element.count {
+                    reader.read_instance(element, Some(list), &mut values, &mut items)?;
+                    indices.clear();
+                    for item in &items {
+                        indices.push(whole(*item).ok_or("invalid vertex index")?);
+                    }
+                    for k in 1..indices.len().saturating_sub(1) {
+                        triangles.push([indices[0], indices[k], indices[k + 1]]);
+                    }
+                }
+            }
+            _ => {
+                for _ in 0..element.count {
+                    reader.read_instance(element, None, &mut values, &mut items)?;
+                }
+            }
+        }
+    }
+
+    if triangles
+        .iter()
+        .flatten()
+        .any(|index| *index >= positions.len())
+    {
+        return Err("vertex index out of range".to_string());
+    }
+
+    let mut mesh = TriangleMesh::new(positions, triangles);
+    if !normals.is_empty() {
+        mesh = mesh.with_normals(normals);
+    }
+    if !uvs.is_empty() {
+        mesh = mesh.with_uvs(uvs);
+    }
+    if !colors.is_empty() {
+        mesh = mesh.with_colors(colors);
+    }
+
+    Ok(mesh)
+}
+
+/// The format, the elements and the offset of the body that follows the header.
+fn parse_header(data: &[u8]) -> Result<(Format, Vec<Element>, usize), String> {
+    if !data.starts_with(b"ply") {
+        return Err("not a PLY file".to_string());
+    }
+
+    let mut format = None;
+    let mut elements: Vec<Element> = Vec::new();
+    let mut offset = 0;
+    for line in data.split_inclusive(|byte| *byte == b'\n') {
+        offset += line.len();
+        let line = String::from_utf8_lossy(line);
+        let tokens: Vec<&str> = line.split_whitespace().collect();
+        let invalid = || format!("invalid header line {}", line.trim());
+        match tokens[..] {
+            ["ply"] | ["comment", ..] | ["obj_info", ..] | [] => {}
+            ["format", name, "1.0"] => {
+                format = Some(match name {
+                    "ascii" => Format::Ascii,
+                    "binary_little_endian" => Format::LittleEndian,
+                    "binary_big_endian" => Format::BigEndian,
+                    _ => return Err(format!("unknown format {}", name)),
+                });
+            }
+            ["element", name, count] => elements.push(Element {
+                name: name.to_string(),
+                count: count.parse().map_err(|_| invalid())?,
+                properties: Vec::new(),
+            }),
+            ["property", "list", count, item, name] => {
+                let property = Property::List {
+                    count: Scalar::from_name(count).ok_or_else(invalid)?,
+                    item: Scalar::from_name(item).ok_or_else(invalid)?,
+                };
+                let element = elements.last_mut().ok_or_else(invalid)?;
+                element.properties.push((name.to_string(), property));
+            }
+            ["property", scalar, name] => {
+                let property = Property::Scalar(Scalar::from_name(scalar).ok_or_else(invalid)?);
+                let element = elements.last_mut().ok_or_else(invalid)?;
+                element.properties.push((name.to_string(), property));
+            }
+            ["end_header"] => {
+                let format = format.ok_or("missing format")?;
+                return Ok((format, elements, offset));
+            }
+            _ => return Err(invalid()),
+        }
+    }
+
+    Err("missing end_header".to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::rc::Rc;
+
+    use super::*;
+    use crate::{
+        color::WHITE,
+        constants::INFINITY,
+        hittable::{HitRecord, Hittable},
+        material::Lambertian,
+        mesh::Mesh,
+        ray::Ray,
+    };
+
+    const HEADER: &str = "element vertex 4
+property float x
+property float y
+property float z
+property uchar red
+property uchar green
+property uchar blue
+element edge 1
+property list uchar int vertex_indices
+element face 1
+property list uchar int vertex_indices
+end_header
+";
+
+    /// A unit square with a red, a green, a blue and a white corner, as one quad.
+    const VERTICES: [([f32; 3], [u8; 3]); 4] = [
+        ([0.0, 0.0, 0.0], [255, 0, 0]),
+        ([1.0, 0.0, 0.0], [0, 255, 0]),
+        ([1.0, 1.0, 0.0], [0, 0, 255]),
+        ([0.0, 1.0, 0.0], [255, 255, 255]),
+    ];
+
+    fn binary(format: &str, big_endian: bool) -> Vec<u8> {
+        let mut data = format!("ply\nformat {} 1.0\n{}", format, HEADER).into_bytes();
+        let int = |value: i32| match big_endian {
+            true => value.to_be_bytes(),
+            false => value.to_le_bytes(),
+        };
+        for (position, color) in VERTICES {
+            for coordinate in position {
+                data.extend(match big_endian {
+                    true => coordinate.to_be_bytes(),
+                    false => coordinate.to_le_bytes(),
+                });
+            }
+            data.extend(color);
+        }
+        // The edge, which is skipped.
+        data.push(2);
+        data.extend([int(0), int(1)].concat());
+        data.push(4);
+        data.extend([int(0), int(1), int(2), int(3)].concat());
+        data
+    }
+
+    fn ascii(faces: &str) -> Vec<u8> {
+        let mut text = format!("ply\nformat ascii 1.0\ncomment a square\n{}", HEADER);
+        for (position, color) in VERTICES {
+            text += &format!(
+                "{} {} {} {} {} {}\n",
+                position[0], position[1], position[2], color[0], color[1], color[2]
+            );
+        }
+        text += "2 0 1\n";
+        text += faces;
+        text.into_bytes()
+    }
+
+    /// The hit on `mesh` by a ray down the z axis through `x y`.
+    fn hit(mesh: &Mesh, x: f64, y: f64) -> Option<HitRecord> {
+        let ray = Ray::new(Point3::new(x, y, 1.0), Vec3::new(0.0, 0.0, -1.0));
+        mesh.hit(&ray, 0.001, INFINITY)
+    }
+
+    fn assert_color(record: &HitRecord, red: f64, green: f64, blue: f64) {
+        let color = record.get_color().unwrap();
+        let expected = Color::new(red, green, blue);
+        assert!(color.subtract(&expected).length() < 1e-9);
+    }
+
+    fn check_square(mesh: TriangleMesh) {
+        let mesh = Mesh::new(mesh, Rc::new(Lambertian::new(WHITE)));
+
+        // The quad is split along the diagonal from its red corner to its blue one.
+        let record = hit(&mesh, 0.75, 0.25).unwrap();
+        assert!((record.get_t() - 1.0).abs() < 1e-9);
+        assert_eq!(record.get_normal().get_z(), 1.0);
+        assert_color(&record, 0.25, 0.5, 0.25);
+        let record = hit(&mesh, 0.25, 0.75).unwrap();
+        assert_color(&record, 0.75, 0.5, 0.75);
+        // Without texture coordinates u and v are barycentric.
+        assert!((record.get_u() - 0.25).abs() < 1e-9);
+        assert!((record.get_v() - 0.5).abs() < 1e-9);
+
+        assert!(hit(&mesh, 1.5, 0.5).is_none());
+        assert!(hit(&mesh, 0.5, -0.5).is_none());
+    }
+
+    #[test]
+    fn ascii_square() {
+        check_square(parse(&ascii("4 0 1 2 3\n")).unwrap());
+    }
+
+    #[test]
+    fn binary_square() {
+        check_square(parse(&binary("binary_little_endian", false)).unwrap());
+        check_square(parse(&binary("binary_big_endian", true)).unwrap());
+    }
+
+    #[test]
+    fn normals_and_texture_coordinates() {
+        let data = "ply
+format ascii 1.0
+element vertex 3
+property double x
+property double y
+property double z
+property float nx
+property float ny
+property float nz
+property float s
+property float t
+property float red
+property float green
+property float blue
+element face 1
+property list uchar uint vertex_index
+end_header
+0 0 0 0 0 1 0 1 0.5 0.5 0.5
+1 0 0 0 0 1 1 1 0.5 0.5 0.5
+0 1 0 0 0 1 0 0 0.5 0.5 0.5
+3 0 1 2
+";
+        let mesh = Mesh::new(
+            parse(data.as_bytes()).unwrap(),
+            Rc::new(Lambertian::new(WHITE)),
+        );
+        let record = hit(&mesh, 0.25, 0.25).unwrap();
+        assert_eq!(record.get_normal().get_z(), 1.0);
+        assert!((record.get_u() - 0.25).abs() < 1e-9);
+        assert!((record.get_v() - 0.75).abs() < 1e-9);
+        // Floating point colors are linear already.
+        assert_color(&record, 0.5, 0.5, 0.5);
+        assert!(hit(&mesh, 0.75, 0.5).is_none());
+    }
+
+    #[test]
+    fn invalid_indices() {
+        assert!(parse(&ascii("3 0 1 -1\n")).is_err());
+        assert!(parse(&ascii("3 0 1 1.5\n")).is_err());
+        assert!(parse(&ascii("3 0 1 4\n")).is_err());
+        assert!(parse(&ascii("-3 0 1 2\n")).is_err());
+    }
+
+    #[test]
+    fn truncated_files() {
+        assert!(parse(&ascii("4 0 1 2\n")).is_err());
+        let data = binary("binary_little_endian", false);
+        assert!(parse(&data[..data.len() - 1]).is_err());
+
+        let huge = "ply
+format binary_little_endian 1.0
+element vertex 100000000000000
+property float x
+property float y
+property float z
+element face 100000000000000
+property list uint uint vertex_indices
+end_header
+";
+        assert!(parse(huge.as_bytes()).is_err());
+    }
+
+    #[test]
+    fn invalid_headers() {
+        assert!(parse(b"off\n").is_err());
+        assert!(parse(b"ply\nformat ascii 1.0\nelement vertex 1\n").is_err());
+        assert!(parse(b"ply\nformat ascii 2.0\nend_header\n").is_err());
+        assert!(parse(b"ply\nelement vertex 0\nend_header\n").is_err());
+        assert!(parse(b"ply\nformat ascii 1.0\nproperty float x\nend_header\n").is_err());
+        assert!(parse(
+            b"ply\nformat ascii 1.0\nelement vertex 1\nproperty float x\nend_header\n0\n"
+        )
+        .is_err());
+    }
+}
@@ -0,0 +1,179 @@
+//! Real roots of low-degree polynomials, after Schwarze's "Cubic and Quartic Roots" in
+//! Graphics Gems. Coefficients are given from the highest power down and roots come back sorted.
+
+const EPSILON: f64 = 1e-9;
+const NEWTON_ITERATIONS: usize = 2;
+
+fn is_zero(value: f64) -> bool {
+    value.abs() < EPSILON
+}
+
+pub fn solve_quadratic(a: f64, b: f64, c: f64) -> Vec<f64> {
+    if a == 0.0 {
+        return if b == 0.0 { vec![] } else { vec![-c / b] };
+    }
+
+    let discriminant = b * b - 4.0 * a * c;
+    if discriminant < 0.0 {
+        return vec![];
+    }
+
+    // Avoids cancellation between -b and the square root.
+    let q = -0.5 * (b + b.signum() * discriminant.sqrt());
+    let mut roots = if q == 0.0 {
+        vec![0.0, 0.0]
+    } else {
+        vec![q / a, c / q]
+    };
+    roots.sort_by(f64::total_cmp);
+    roots
+}
+
+/// Roots of x³ + a x² + b x + c.
+fn solve_normalized_cubic(a: f64, b: f64, c: f64) -> Vec<f64> {
+    let square_a = a * a;
+    let p = (-square_a / 3.0 + b) / 3.0;
+    let q = (2.0 / 27.0 * a * square_a - a * b / 3.0 + c) / 2.0;
+    let cube_p = p * p * p;
+    let discriminant = q * q + cube_p;
+
+    let roots = if is_zero(discriminant) {
+        if is_zero(q) {
+            vec![0.0]
+        } else {
+            let u = (-q).cbrt();
+            vec![2.0 * u, -u]
+        }
+    } else if discriminant < 0.0 {
+        let phi = (-q / (-cube_p).sqrt()).clamp(-1.0, 1.0).acos() / 3.0;
+        let t = 2.0 * (-p).sqrt();
+        vec![
+            t * phi.cos(),
+            -t * (phi + std::f64::consts::FRAC_PI_3).cos(),
+            -t * (phi - std::f64::consts::FRAC_PI_3).cos(),
+        ]
+    } else {
+        let sqrt = discriminant.sqrt();
+        vec![(sqrt - q).cbrt() - (sqrt + q).cbrt()]
+    };
+
+    roots.into_iter().map(|root| root - a / 3.0).collect()
+}
+
+/// Ferrari's method through the resolvent cubic, with the roots polished by Newton's method.
+pub fn solve_quartic(a: f64, b: f64, c: f64, d: f64, e: f64) -> Vec<f64> {
+    if a == 0.0 {
+        return vec![];
+    }
+
+    let (b, c, d, e) = (b / a, c / a, d / a, e / a);
+    let square_b = b * b;
+    let p = -3.0 / 8.0 * square_b + c;
+    let q = square_b * b / 8.0 - b * c / 2.0 + d;
+    let r = -3.0 / 256.0 * square_b * square_b + square_b * c / 16.0 - b * d / 4.0 + e;
+
+    let mut roots = if is_zero(r) {
+        let mut roots = solve_normalized_cubic(0.0, p, q);
+        roots.push(0.0);
+        roots
+    } else {
+        let z = solve_normalized_cubic(-p / 2.0, -r, r * p / 2.0 - q * q / 8.0)[0];
+        let u = z * z - r;
+        let v = 2.0 * z - p;
+        let u = if is_zero(u) {
+            0.0
+        } else if u > 0.0 {
+            u.sqrt()
+        } else {
+            return vec![];
+        };
+        let v = if is_zero(v) {
+            0.0
+        } else if v > 0.0 {
+            v.sqrt()
+        } else {
+            return vec![];
+        };
+
+        let v = if q < 0.0 { -v } else { v };
+        let mut roots = solve_quadratic(1.0, v, z - u);
+        roots.extend(solve_quadratic(1.0, -v, z + u));
+        roots
+    };
+
+    for root in &mut roots {
+        *root -= b / 4.0;
+        for _ in 0..NEWTON_ITERATIONS {
+            let value = (((*root + b) * *root + c) * *root + d) * *root + e;
+            let derivative = ((4.0 * *root + 3.0 * b) * *root + 2.0 * c) * *root + d;
+            if derivative != 0.0 {
+                *root -= value / derivative;
+            }
+        }
+    }
+    roots.sort_by(f64::total_cmp);
+    roots
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assert_roots(roots: &[f64], expected: &[f64]) {
+        assert_eq!(roots.len(), expected.len(), "{:?} != {:?}", roots, expected);
+        for (root, expected) in roots.iter().zip(expected) {
+            assert!(
+                (root - expected).abs() < 1e-6,
+                "{:?} != {:?}",
+                roots,
+                expected
+            );
+        }
+    }
+
+    #[test]
+    fn quadratic() {
+        // (x + 3)(x - 2)
+        assert_roots(&solve_quadratic(1.0, 1.0, -6.0), &[-3.0, 2.0]);
+        assert_roots(&solve_quadratic(2.0, -4.0, 2.0), &[1.0, 1.0]);
+        assert_roots(&solve_quadratic(1.0, 0.0, 0.0), &[0.0, 0.0]);
+        assert_roots(&solve_quadratic(0.0, 2.0, -1.0), &[0.5]);
+        assert_roots(&solve_quadratic(1.0, 0.0, 1.0), &[]);
+        assert_roots(&solve_quadratic(0.0, 0.0, 1.0), &[]);
+    }
+
+    #[test]
+    fn quartic_distinct_roots() {
+        // (x - 1)(x - 2)(x - 3)(x - 4)
+        assert_roots(
+            &solve_quartic(1.0, -10.0, 35.0, -50.0, 24.0),
+            &[1.0, 2.0, 3.0, 4.0],
+        );
+        // 2(x + 1)(x - 0.5)(x² + 1)
+        assert_roots(&solve_quartic(2.0, 1.0, 1.0, 1.0, -1.0), &[-1.0, 0.5]);
+        assert_roots(&solve_quartic(1.0, 0.0, 0.0, 0.0, 1.0), &[]);
+    }
+
+    #[test]
+    fn quartic_zero_constant_after_depression() {
+        // x(x - 1)(x - 2)(x + 3) has no cubic term, so its constant term r is zero too.
+        assert_roots(
+            &solve_quartic(1.0, 0.0, -7.0, 6.0, 0.0),
+            &[-3.0, 0.0, 1.0, 2.0],
+        );
+    }
+
+    #[test]
+    fn quartic_double_roots() {
+        // (x - 1)²(x + 1)²
+        assert_roots(
+            &solve_quartic(1.0, 0.0, -2.0, 0.0, 1.0),
+            &[-1.0, -1.0, 1.0, 1.0],
+        );
+        // (x - 2)²(x - 3)(x + 1)
+        assert_roots(
+            &solve_quartic(1.0, -6.0, 9.0, 4.0, -12.0),
+            &[-1.0, 2.0, 2.0, 3.0],
+        );
+    }
+}
@@ -0,0 +1,109 @@
+use std::{
+    fs::{self, File},
+    io::{self, BufWriter, Write},
+};
+
+use crate::color::{format_color, Color};
+
+struct Tokenizer<'a> {
+    bytes: &'a [u8],
+    position: usize,
+}
+
+impl Tokenizer<'_> {
+    fn token(&mut self) -> Option<String> {
+        loop {
+            while self.bytes.get(self.position)?.is_ascii_whitespace() {
+                self.position += 1;
+            }
+            if self.bytes[self.position] != b'#' {
+                break;
+            }
+            while self.bytes.get(self.position)? != &b'\n' {
+                self.position += 1;
+            }
+        }
+
+        let start = self.position;
+        while self
+            .bytes
+            .get(self.position)
+            .is_some_and(|byte| !byte.is_ascii_whitespace())
+        {
+            self.position += 1;
+        }
+        Some(String::from_utf8_lossy(&self.bytes[start..self.position]).into_owned())
+    }
+
+    fn number(&mut self) -> Option<usize> {
+        self.token()?.parse().ok()
+    }
+}
+
+/// Reads a binary or ASCII PPM/PGM image (P2, P3, P5 or P6) as colors in [0, 1], stored top
+/// row first.
+pub fn read_ppm(path: &str) -> Result<(usize, usize, Vec<Color>), String> {
+    let bytes = fs::read(path).map_err(|error| format!("failed to read {}: {}", path, error))?;
+    let invalid = || format!("invalid PPM file {}", path);
+    let mut tokenizer = Tokenizer {
+        bytes: &bytes,
+        position: 0,
+    };
+
+    let magic = tokenizer.token().ok_or_else(invalid)?;
+    let width = tokenizer.number().ok_or_else(invalid)?;
+    let height = tokenizer.number().ok_or_else(invalid)?;
+    let max_value = tokenizer
+        .number()
+        .filter(|value| *value > 0)
+        .ok_or_else(invalid)?;
+    let channels = match magic.as_str() {
+        "P2" | "P5" => 1,
+        "P3" | "P6" => 3,
+        _ => return Err(format!("unsupported image format {} in {}", magic, path)),
+    };
+
+    let count = width * height * channels;
+    let values: Vec<usize> = if magic == "P2" || magic == "P3" {
+        (0..count)
+            .map(|_| tokenizer.number())
+            .collect::<Option<_>>()
+            .ok_or_else(invalid)?
+    } else {
+        let start = tokenizer.position + 1;
+        let sample_size = if max_value > 255 { 2 } else { 1 };
+        let data = bytes
+            .get(start..start + count * sample_size)
+            .ok_or_else(invalid)?;
+        data.chunks(sample_size)
+            .map(|sample| {
+                sample
+                    .iter()
+                    .fold(0, |value, byte| value << 8 | *byte as usize)
+            })
+            .collect()
+    };
+
+    let scale = 1.0 / max_value as f64;
+    let colors = values
+        .chunks(channels)
+        .map(|pixel| {
+            let channel = |index: usize| pixel[index.min(channels - 1)] as f64 * scale;
+            Color::new(channel(0), channel(1), channel(2))
+        })
+        .collect();
+
+    Ok((width, height, colors))
+}
+
+/// Writes display-referred colors, stored top row first, as an ASCII PPM.
+pub fn write_ppm(path: &str, width: usize, height: usize, colors: &[Color]) -> io::Result<()> {
+    let mut file = BufWriter::new(File::create(path)?);
+
+    write!(file, "P3\n{} {}\n255\n", width, height)?;
+    for color in colors {
+        writeln!(file, "{}", format_color(color))?;
+    }
+
+    Ok(())
+}
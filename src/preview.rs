@@ -0,0 +1,95 @@
+use minifb::{Key, MouseButton, MouseMode, Window, WindowOptions};
+
+use crate::{
+    camera::CameraView, color_pipeline::ColorPipeline, film::Film, integrator::Renderer,
+    progress::ProgressTracker, sampler::Sampler, scene::Scene, settings::RenderSettings,
+};
+
+const ORBIT_SPEED: f64 = 0.01;
+const PAN_SPEED: f64 = 0.002;
+const ZOOM_STEP: f64 = 0.1;
+
+/// Shows the film refining pass by pass. Dragging with the left mouse button orbits, with the
+/// right button pans and scrolling zooms; every change rebuilds the camera and clears the film.
+pub fn run(
+    renderer: &dyn Renderer,
+    scene: &Scene,
+    view: CameraView,
+    settings: &RenderSettings,
+    sampler: &mut dyn Sampler,
+    film: &mut Film,
+    pipeline: &ColorPipeline,
+) -> Result<(), String> {
+    let width = film.get_width();
+    let height = film.get_height();
+    let mut window = Window::new("ray-tracing", width, height, WindowOptions::default())
+        .map_err(|error| error.to_string())?;
+    window.set_target_fps(60);
+
+    let mut view = view;
+    let mut camera = view.build();
+    let mut buffer = vec![0; width * height];
+    let mut last_mouse = None;
+    let mut refining = true;
+    let mut pass = 0;
+
+    while window.is_open() && !window.is_key_down(Key::Escape) {
+        let mut changed = None;
+
+        let mouse = window.get_mouse_pos(MouseMode::Discard);
+        if let (Some((x, y)), Some((last_x, last_y))) = (mouse, last_mouse) {
+            let dx = f64::from(x - last_x);
+            let dy = f64::from(y - last_y);
+            if dx != 0.0 || dy != 0.0 {
+                if window.get_mouse_down(MouseButton::Left) {
+                    changed = Some(view.orbit(-dx * ORBIT_SPEED, -dy * ORBIT_SPEED));
+                } else if window.get_mouse_down(MouseButton::Right) {
+                    changed = Some(view.pan(-dx * PAN_SPEED, dy * PAN_SPEED));
+                }
+            }
+        }
+        last_mouse = mouse;
+
+        if let Some((_, scroll)) = window.get_scroll_wheel() {
+            if scroll != 0.0 {
+                changed = Some(view.zoom(1.0 - ZOOM_STEP * f64::from(scroll.signum())));
+            }
+        }
+
+        if let Some(new_view) = changed {
+            view = new_view;
+            camera = view.build();
+            film.clear();
+            refining = true;
+            pass = 0;
+        }
+
+        if !refining {
+            window.update();
+            continue;
+        }
+
+        refining = renderer.render_pass(
+            scene,
+            camera.as_ref(),
+            settings,
+            sampler,
+            film,
+            &mut ProgressTracker::new(|_| {}),
+        );
+        pass += 1;
+        window.set_title(&format!("ray-tracing - pass {}", pass));
+
+        for (pixel, color) in buffer.iter_mut().zip(film.get_colors()) {
+            let color = pipeline.apply(&color);
+            let channel = |value: f64| (value.clamp(0.0, 1.0) * 255.0).round() as u32;
+            *pixel =
+                channel(color.get_x()) << 16 | channel(color.get_y()) << 8 | channel(color.get_z());
+        }
+        window
+            .update_with_buffer(&buffer, width, height)
+            .map_err(|error| error.to_string())?;
+    }
+
+    Ok(())
+}
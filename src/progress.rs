@@ -0,0 +1,90 @@
+use std::time::{Duration, Instant};
+
+use crate::stats::Statistics;
+
+/// A snapshot of render progress. A tile is one scanline of one pass over the film, or a batch
+/// of photons while a photon map is built.
+pub struct Progress {
+    tiles_done: usize,
+    tiles_total: usize,
+    rays: u64,
+    elapsed: Duration,
+    finished: bool,
+}
+
+impl Progress {
+    pub const fn get_tiles_done(&self) -> usize {
+        self.tiles_done
+    }
+
+    pub const fn get_tiles_total(&self) -> usize {
+        self.tiles_total
+    }
+
+    pub const fn get_elapsed(&self) -> Duration {
+        self.elapsed
+    }
+
+    pub const fn is_finished(&self) -> bool {
+        self.finished
+    }
+
+    pub fn rays_per_second(&self) -> f64 {
+        self.rays as f64 / self.elapsed.as_secs_f64().max(1e-9)
+    }
+
+    /// Extrapolated from the average time per tile. With adaptive sampling the total assumes
+    /// every pixel takes its maximum samples, so this is an upper bound.
+    pub fn eta(&self) -> Option<Duration> {
+        if self.tiles_done == 0 || self.tiles_done >= self.tiles_total {
+            return None;
+        }
+
+        let remaining = (self.tiles_total - self.tiles_done) as f64 / self.tiles_done as f64;
+        Some(self.elapsed.mul_f64(remaining))
+    }
+}
+
+pub struct ProgressTracker<'a> {
+    start: Instant,
+    start_rays: u64,
+    tiles_done: usize,
+    tiles_total: usize,
+    callback: Box<dyn FnMut(&Progress) + 'a>,
+}
+
+impl<'a> ProgressTracker<'a> {
+    pub fn new(callback: impl FnMut(&Progress) + 'a) -> Self {
+        Self {
+            start: Instant::now(),
+            start_rays: Statistics::snapshot().get_rays(),
+            tiles_done: 0,
+            tiles_total: 0,
+            callback: Box::new(callback),
+        }
+    }
+
+    pub fn set_tiles_total(&mut self, tiles_total: usize) {
+        self.tiles_total = tiles_total;
+    }
+
+    pub fn tile_done(&mut self) {
+        self.tiles_done += 1;
+        self.report(false);
+    }
+
+    pub fn finish(&mut self) {
+        self.report(true);
+    }
+
+    fn report(&mut self, finished: bool) {
+        let progress = Progress {
+            tiles_done: self.tiles_done,
+            tiles_total: self.tiles_total.max(self.tiles_done),
+            rays: Statistics::snapshot().get_rays() - self.start_rays,
+            elapsed: self.start.elapsed(),
+            finished,
+        };
+        (self.callback)(&progress);
+    }
+}
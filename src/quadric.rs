@@ -0,0 +1,100 @@
+use std::rc::Rc;
+
+use crate::{
+    aabb::Aabb,
+    hittable::{first_in_range, HitRecord, Hittable},
+    material::Material,
+    polynomial::solve_quadratic,
+    ray::Ray,
+    sphere::sphere_uv,
+    stats,
+    vec3::{Point3, Vec3},
+};
+
+/// The surface a x² + b y² + c z² + d xy + e xz + f yz + g x + h y + i z + j = 0, clipped to
+/// `bounds` since most quadrics are unbounded. UVs map the direction from the center of the
+/// bounds as on a sphere.
+pub struct Quadric {
+    coefficients: [f64; 10],
+    bounds: Aabb,
+    material: Rc<dyn Material>,
+}
+
+impl Quadric {
+    pub const fn new(coefficients: [f64; 10], bounds: Aabb, material: Rc<dyn Material>) -> Self {
+        Self {
+            coefficients,
+            bounds,
+            material,
+        }
+    }
+
+    fn gradient(&self, point: &Point3) -> Vec3 {
+        let [a, b, c, d, e, f, g, h, i, _] = self.coefficients;
+        let (x, y, z) = (point.get_x(), point.get_y(), point.get_z());
+
+        Vec3::new(
+            2.0 * a * x + d * y + e * z + g,
+            2.0 * b * y + d * x + f * z + h,
+            2.0 * c * z + e * x + f * y + i,
+        )
+    }
+}
+
+impl Hittable for Quadric {
+    fn hit(&self, ray: &Ray, t_min: f64, t_max: f64) -> Option<HitRecord> {
+        stats::record_intersection_test();
+        let [a, b, c, d, e, f, g, h, i, j] = self.coefficients;
+        let origin = ray.get_origin();
+        let direction = ray.get_direction();
+        let (ox, oy, oz) = (origin.get_x(), origin.get_y(), origin.get_z());
+        let (dx, dy, dz) = (direction.get_x(), direction.get_y(), direction.get_z());
+
+        let quadratic =
+            a * dx * dx + b * dy * dy + c * dz * dz + d * dx * dy + e * dx * dz + f * dy * dz;
+        let linear = 2.0 * (a * ox * dx + b * oy * dy + c * oz * dz)
+            + d * (ox * dy + oy * dx)
+            + e * (ox * dz + oz * dx)
+            + f * (oy * dz + oz * dy)
+            + g * dx
+            + h * dy
+            + i * dz;
+        let constant = a * ox * ox
+            + b * oy * oy
+            + c * oz * oz
+            + d * ox * oy
+            + e * ox * oz
+            + f * oy * oz
+            + g * ox
+            + h * oy
+            + i * oz
+            + j;
+
+        let center = self
+            .bounds
+            .get_minimum()
+            .add(&self.bounds.get_maximum())
+            .divide_constant(2.0);
+        let records = solve_quadratic(quadratic, linear, constant)
+            .into_iter()
+            .map(|t| (t, ray.at(t)))
+            .filter(|(_, point)| self.bounds.contains(point))
+            .map(|(t, point)| {
+                HitRecord::new(
+                    point,
+                    self.gradient(&point).unit_vector(),
+                    t,
+                    sphere_uv(&point.subtract(&center).unit_vector()),
+                    ray,
+                    Rc::clone(&self.material),
+                )
+            })
+            .collect();
+
+        first_in_range(records, t_min, t_max)
+    }
+
+    fn bounding_box(&self) -> Option<Aabb> {
+        Some(self.bounds)
+    }
+}
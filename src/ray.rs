@@ -1,13 +1,29 @@
 use crate::vec3::{Point3, Vec3};
 
+#[derive(Clone, Copy)]
 pub struct Ray {
     origin: Point3,
     direction: Vec3,
+    wavelength: Option<f64>,
 }
 
 impl Ray {
     pub const fn new(origin: Point3, direction: Vec3) -> Self {
-        Self { origin, direction }
+        Self {
+            origin,
+            direction,
+            wavelength: None,
+        }
+    }
+
+    /// Tags the ray with the hero wavelength in nanometers, for spectral rendering.
+    pub const fn with_wavelength(mut self, wavelength: f64) -> Self {
+        self.wavelength = Some(wavelength);
+        self
+    }
+
+    pub const fn get_wavelength(&self) -> Option<f64> {
+        self.wavelength
     }
 
     pub const fn get_origin(&self) -> Point3 {
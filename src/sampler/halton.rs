@@ -0,0 +1,76 @@
+use super::{hash, hash_to_unit, mix_bits, permutation_element, Sampler, ONE_MINUS_EPSILON};
+
+const PRIMES: [u64; 32] = [
+    2, 3, 5, 7, 11, 13, 17, 19, 23, 29, 31, 37, 41, 43, 47, 53, 59, 61, 67, 71, 73, 79, 83, 89, 97,
+    101, 103, 107, 109, 113, 127, 131,
+];
+
+pub struct HaltonSampler {
+    seed: u64,
+    pixel: (usize, usize),
+    sample_index: usize,
+    dimension: usize,
+}
+
+impl HaltonSampler {
+    pub const fn new(seed: u64) -> Self {
+        Self {
+            seed,
+            pixel: (0, 0),
+            sample_index: 0,
+            dimension: 0,
+        }
+    }
+
+    fn next(&mut self) -> f64 {
+        let dimension = self.dimension;
+        self.dimension += 1;
+
+        let scramble = hash(&[
+            self.pixel.0 as u64,
+            self.pixel.1 as u64,
+            dimension as u64,
+            self.seed,
+        ]);
+        match PRIMES.get(dimension) {
+            Some(&base) => owen_radical_inverse(base, self.sample_index as u64, scramble),
+            None => hash_to_unit(hash(&[scramble, self.sample_index as u64])),
+        }
+    }
+}
+
+impl Sampler for HaltonSampler {
+    fn start_pixel_sample(&mut self, pixel: (usize, usize), sample_index: usize) {
+        self.pixel = pixel;
+        self.sample_index = sample_index;
+        self.dimension = 0;
+    }
+
+    fn get_1d(&mut self) -> f64 {
+        self.next()
+    }
+
+    fn get_2d(&mut self) -> (f64, f64) {
+        (self.next(), self.next())
+    }
+}
+
+fn owen_radical_inverse(base: u64, mut index: u64, scramble: u64) -> f64 {
+    let inv_base = 1.0 / base as f64;
+    let mut inv_base_m = 1.0;
+    let mut reversed_digits = 0u64;
+
+    // Digits past the end of the index are zero but still get permuted, so the
+    // loop runs until the remaining digits can no longer change the result.
+    while 1.0 - (base - 1) as f64 * inv_base_m < 1.0 {
+        let digit = index % base;
+        let digit_hash = mix_bits(scramble ^ reversed_digits);
+        let digit = permutation_element(digit as u32, base as u32, digit_hash as u32) as u64;
+
+        reversed_digits = reversed_digits * base + digit;
+        inv_base_m *= inv_base;
+        index /= base;
+    }
+
+    (inv_base_m * reversed_digits as f64).min(ONE_MINUS_EPSILON)
+}
@@ -0,0 +1,86 @@
+pub use halton::HaltonSampler;
+pub use random::RandomSampler;
+pub use sobol::SobolSampler;
+pub use stratified::StratifiedSampler;
+
+mod halton;
+mod random;
+mod sobol;
+mod stratified;
+
+const ONE_MINUS_EPSILON: f64 = 1.0 - f64::EPSILON / 2.0;
+
+pub trait Sampler {
+    fn start_pixel_sample(&mut self, pixel: (usize, usize), sample_index: usize);
+
+    fn get_1d(&mut self) -> f64;
+
+    fn get_2d(&mut self) -> (f64, f64);
+}
+
+pub fn from_name(name: &str, samples_per_pixel: usize, seed: u64) -> Option<Box<dyn Sampler>> {
+    let sampler: Box<dyn Sampler> = match name {
+        "random" => Box::new(RandomSampler::new()),
+        "stratified" => Box::new(StratifiedSampler::new(samples_per_pixel, seed)),
+        "halton" => Box::new(HaltonSampler::new(seed)),
+        "sobol" => Box::new(SobolSampler::new(samples_per_pixel, seed)),
+        _ => return None,
+    };
+
+    Some(sampler)
+}
+
+fn mix_bits(mut value: u64) -> u64 {
+    value ^= value >> 31;
+    value = value.wrapping_mul(0x7fb5d329728ea185);
+    value ^= value >> 27;
+    value = value.wrapping_mul(0x81dadef4bc2dd44d);
+    value ^= value >> 33;
+    value
+}
+
+fn hash(values: &[u64]) -> u64 {
+    values.iter().fold(0x9e3779b97f4a7c15, |acc, &value| {
+        mix_bits(acc ^ mix_bits(value))
+    })
+}
+
+fn hash_to_unit(value: u64) -> f64 {
+    ((value >> 11) as f64 * (1.0 / (1u64 << 53) as f64)).min(ONE_MINUS_EPSILON)
+}
+
+// Kensler's hash-based permutation: maps `index` to its position in a pseudo-random
+// permutation of `0..count` selected by `seed`, without materialising the table.
+fn permutation_element(mut index: u32, count: u32, seed: u32) -> u32 {
+    let mut w = count.wrapping_sub(1);
+    w |= w >> 1;
+    w |= w >> 2;
+    w |= w >> 4;
+    w |= w >> 8;
+    w |= w >> 16;
+
+    loop {
+        index ^= seed;
+        index = index.wrapping_mul(0xe170893d);
+        index ^= seed >> 16;
+        index ^= (index & w) >> 4;
+        index ^= seed >> 8;
+        index = index.wrapping_mul(0x0929eb3f);
+        index ^= seed >> 23;
+        index ^= (index & w) >> 1;
+        index = index.wrapping_mul(1 | seed >> 27);
+        index = index.wrapping_mul(0x6935fa69);
+        index ^= (index & w) >> 11;
+        index = index.wrapping_mul(0x74dcb303);
+        index ^= (index & w) >> 2;
+        index = index.wrapping_mul(0x9e501cc3);
+        index ^= (index & w) >> 2;
+        index = index.wrapping_mul(0xc860a3df);
+        index &= w;
+        index ^= index >> 5;
+
+        if index < count {
+            return index.wrapping_add(seed) % count;
+        }
+    }
+}
@@ -0,0 +1,23 @@
+use crate::utils::random_double;
+
+use super::Sampler;
+
+pub struct RandomSampler;
+
+impl RandomSampler {
+    pub const fn new() -> Self {
+        Self
+    }
+}
+
+impl Sampler for RandomSampler {
+    fn start_pixel_sample(&mut self, _pixel: (usize, usize), _sample_index: usize) {}
+
+    fn get_1d(&mut self) -> f64 {
+        random_double()
+    }
+
+    fn get_2d(&mut self) -> (f64, f64) {
+        (random_double(), random_double())
+    }
+}
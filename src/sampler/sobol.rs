@@ -0,0 +1,113 @@
+use super::{hash, permutation_element, Sampler, ONE_MINUS_EPSILON};
+
+const SECOND_DIMENSION: [u32; 32] = second_dimension_directions();
+
+pub struct SobolSampler {
+    samples_per_pixel: usize,
+    seed: u64,
+    pixel: (usize, usize),
+    sample_index: usize,
+    dimension: usize,
+}
+
+impl SobolSampler {
+    pub fn new(samples_per_pixel: usize, seed: u64) -> Self {
+        Self {
+            samples_per_pixel: samples_per_pixel.max(1),
+            seed,
+            pixel: (0, 0),
+            sample_index: 0,
+            dimension: 0,
+        }
+    }
+
+    fn next_hash(&mut self) -> u64 {
+        let value = hash(&[
+            self.pixel.0 as u64,
+            self.pixel.1 as u64,
+            self.dimension as u64,
+            self.seed,
+        ]);
+        self.dimension += 1;
+        value
+    }
+
+    fn permuted_index(&self, seed: u64) -> u32 {
+        let count = self.samples_per_pixel;
+        let round = self.sample_index / count;
+        let index = permutation_element(
+            (self.sample_index % count) as u32,
+            count as u32,
+            seed as u32,
+        );
+        (round * count) as u32 + index
+    }
+}
+
+impl Sampler for SobolSampler {
+    fn start_pixel_sample(&mut self, pixel: (usize, usize), sample_index: usize) {
+        self.pixel = pixel;
+        self.sample_index = sample_index;
+        self.dimension = 0;
+    }
+
+    fn get_1d(&mut self) -> f64 {
+        let seed = self.next_hash();
+        let index = self.permuted_index(seed);
+        to_unit(owen_scramble(sobol(index, 0), (seed >> 32) as u32))
+    }
+
+    fn get_2d(&mut self) -> (f64, f64) {
+        let seed = self.next_hash();
+        let index = self.permuted_index(seed);
+        (
+            to_unit(owen_scramble(sobol(index, 0), (seed >> 32) as u32)),
+            to_unit(owen_scramble(sobol(index, 1), seed as u32 ^ 0x9e3779b9)),
+        )
+    }
+}
+
+const fn second_dimension_directions() -> [u32; 32] {
+    let mut directions = [0; 32];
+    directions[0] = 1 << 31;
+    let mut i = 1;
+    while i < 32 {
+        directions[i] = directions[i - 1] ^ (directions[i - 1] >> 1);
+        i += 1;
+    }
+    directions
+}
+
+fn sobol(mut index: u32, dimension: usize) -> u32 {
+    let mut value = 0;
+    let mut bit = 0;
+
+    while index != 0 {
+        if index & 1 == 1 {
+            value ^= match dimension {
+                0 => 1 << (31 - bit),
+                _ => SECOND_DIMENSION[bit],
+            };
+        }
+        index >>= 1;
+        bit += 1;
+    }
+
+    value
+}
+
+// Laine-Karras style hash that is equivalent to a nested uniform (Owen) scramble
+// of the bits, applied in reversed order so higher bits scramble the lower ones.
+fn owen_scramble(mut value: u32, seed: u32) -> u32 {
+    value = value.reverse_bits();
+    value ^= value.wrapping_mul(0x3d20adea);
+    value = value.wrapping_add(seed);
+    value = value.wrapping_mul((seed >> 16) | 1);
+    value ^= value.wrapping_mul(0x05526c56);
+    value ^= value.wrapping_mul(0x53a22864);
+    value.reverse_bits()
+}
+
+fn to_unit(value: u32) -> f64 {
+    (value as f64 / (1u64 << 32) as f64).min(ONE_MINUS_EPSILON)
+}
@@ -0,0 +1,78 @@
+use super::{hash, hash_to_unit, permutation_element, Sampler, ONE_MINUS_EPSILON};
+
+pub struct StratifiedSampler {
+    strata: usize,
+    x_strata: usize,
+    y_strata: usize,
+    seed: u64,
+    pixel: (usize, usize),
+    sample_index: usize,
+    dimension: usize,
+}
+
+impl StratifiedSampler {
+    pub fn new(samples_per_pixel: usize, seed: u64) -> Self {
+        let strata = samples_per_pixel.max(1);
+        let x_strata = ((strata as f64).sqrt() as usize).max(1);
+        let y_strata = strata.div_ceil(x_strata);
+
+        Self {
+            strata,
+            x_strata,
+            y_strata,
+            seed,
+            pixel: (0, 0),
+            sample_index: 0,
+            dimension: 0,
+        }
+    }
+
+    fn next_hash(&mut self) -> u64 {
+        let round = self.sample_index / self.strata;
+        let value = hash(&[
+            self.pixel.0 as u64,
+            self.pixel.1 as u64,
+            self.dimension as u64,
+            round as u64,
+            self.seed,
+        ]);
+        self.dimension += 1;
+        value
+    }
+
+    fn stratum(&self, count: usize, seed: u64) -> usize {
+        let index = (self.sample_index % self.strata) as u32;
+        permutation_element(index, count as u32, seed as u32) as usize
+    }
+
+    fn jitter(&self, seed: u64) -> f64 {
+        hash_to_unit(hash(&[seed, self.sample_index as u64]))
+    }
+}
+
+impl Sampler for StratifiedSampler {
+    fn start_pixel_sample(&mut self, pixel: (usize, usize), sample_index: usize) {
+        self.pixel = pixel;
+        self.sample_index = sample_index;
+        self.dimension = 0;
+    }
+
+    fn get_1d(&mut self) -> f64 {
+        let seed = self.next_hash();
+        let stratum = self.stratum(self.strata, seed);
+
+        ((stratum as f64 + self.jitter(seed)) / self.strata as f64).min(ONE_MINUS_EPSILON)
+    }
+
+    fn get_2d(&mut self) -> (f64, f64) {
+        let seed = self.next_hash();
+        let stratum = self.stratum(self.x_strata * self.y_strata, seed);
+        let x = stratum % self.x_strata;
+        let y = stratum / self.x_strata;
+
+        (
+            ((x as f64 + self.jitter(seed)) / self.x_strata as f64).min(ONE_MINUS_EPSILON),
+            ((y as f64 + self.jitter(!seed)) / self.y_strata as f64).min(ONE_MINUS_EPSILON),
+        )
+    }
+}
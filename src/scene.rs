@@ -0,0 +1,118 @@
+use std::{cell::OnceCell, rc::Rc};
+
+use crate::{
+    aabb::Aabb,
+    bvh::BvhNode,
+    color::{Color, BLUE, WHITE},
+    hittable::{HitRecord, Hittable},
+    hittable_list::HittableList,
+    light::Light,
+    material::Material,
+    ray::Ray,
+    sampler::Sampler,
+    stats,
+};
+
+#[derive(Clone, Copy)]
+pub enum Background {
+    Sky,
+    Solid(Color),
+}
+
+/// The top-level objects, with the bounded ones in a BVH, counting every ray traced against
+/// them.
+struct World(HittableList);
+
+impl World {
+    fn new(objects: &[Rc<dyn Hittable>]) -> Self {
+        let (bounded, unbounded): (Vec<_>, Vec<_>) = objects
+            .iter()
+            .cloned()
+            .partition(|object| object.bounding_box().is_some());
+
+        let mut list = HittableList::new();
+        if let Some(bvh) = BvhNode::build(bounded) {
+            list.add(bvh);
+        }
+        for object in unbounded {
+            list.add(object);
+        }
+
+        Self(list)
+    }
+}
+
+impl Hittable for World {
+    fn hit(&self, ray: &Ray, t_min: f64, t_max: f64) -> Option<HitRecord> {
+        stats::record_ray();
+        self.0.hit(ray, t_min, t_max)
+    }
+
+    fn bounding_box(&self) -> Option<Aabb> {
+        self.0.bounding_box()
+    }
+}
+
+pub struct Scene {
+    objects: Vec<Rc<dyn Hittable>>,
+    world: OnceCell<World>,
+    lights: Vec<Box<dyn Light>>,
+    background: Background,
+}
+
+impl Scene {
+    pub const fn new(background: Background) -> Self {
+        Self {
+            objects: vec![],
+            world: OnceCell::new(),
+            lights: vec![],
+            background,
+        }
+    }
+
+    pub fn add(&mut self, object: Box<dyn Hittable>) {
+        self.objects.push(Rc::from(object));
+        self.world = OnceCell::new();
+    }
+
+    pub fn add_light(&mut self, light: Box<dyn Light>) {
+        self.add(light.shape());
+        self.lights.push(light);
+    }
+
+    /// The acceleration structure is built on first use after objects were added.
+    pub fn get_world(&self) -> &dyn Hittable {
+        self.world.get_or_init(|| World::new(&self.objects))
+    }
+
+    pub fn sample_light(&self, sampler: &mut dyn Sampler) -> Option<(&dyn Light, f64)> {
+        if self.lights.is_empty() {
+            return None;
+        }
+
+        let count = self.lights.len();
+        let index = ((sampler.get_1d() * count as f64) as usize).min(count - 1);
+        Some((self.lights[index].as_ref(), 1.0 / count as f64))
+    }
+
+    pub fn light_pdf(&self, material: &Rc<dyn Material>) -> f64 {
+        let material = Rc::as_ptr(material) as *const ();
+        self.lights
+            .iter()
+            .find(|light| Rc::as_ptr(&light.get_material()) as *const () == material)
+            .map_or(0.0, |light| light.pdf_point() / self.lights.len() as f64)
+    }
+
+    pub fn background(&self, ray: &Ray) -> Color {
+        match self.background {
+            Background::Sky => {
+                let unit_direction = ray.get_direction().unit_vector();
+                let t = 0.5 * (unit_direction.get_y() + 1.0);
+                WHITE
+                    .multiply_constant(1.0 - t)
+                    .add(&BLUE.multiply_constant(t))
+            }
+            Background::Solid(color) => color,
+        }
+    }
+}
@@ -0,0 +1,648 @@
+use std::{fs, path::Path, rc::Rc};
+
+use crate::{
+    animation::{Animated, Easing},
+    bezier_patch::{load_patches, tessellate, BezierPatch},
+    bvh::BvhNode,
+    camera::CameraView,
+    color::Color,
+    hittable::Hittable,
+    light::SphereLight,
+    material::{Dielectric, Lambertian, Material, Metal},
+    mesh::Mesh,
+    ply::load_ply,
+    scene::{Background, Scene},
+    sphere::Sphere,
+    stl::load_stl,
+    transform::{Transform, Transformed},
+    vec3::{Point3, Vec3},
+};
+
+enum MaterialKind {
+    Lambertian,
+    Metal,
+    Dielectric,
+    Light,
+}
+
+/// `color` is the albedo or emission and `parameter` the fuzz or index of refraction.
+struct MaterialDescription {
+    name: String,
+    kind: MaterialKind,
+    color: Animated<Color>,
+    parameter: Animated<f64>,
+}
+
+impl MaterialDescription {
+    /// The material at `frame`, or `None` for lights, which are not materials.
+    fn at(&self, frame: f64) -> Option<Rc<dyn Material>> {
+        let color = self.color.at(frame);
+        let parameter = self.parameter.at(frame);
+        let material: Rc<dyn Material> = match self.kind {
+            MaterialKind::Lambertian => Rc::new(Lambertian::new(color)),
+            MaterialKind::Metal => Rc::new(Metal::new(color, parameter)),
+            MaterialKind::Dielectric => Rc::new(Dielectric::new(parameter)),
+            MaterialKind::Light => return None,
+        };
+        Some(material)
+    }
+}
+
+/// Where an object is placed: scaled and rotated about its origin, then moved by `translate`.
+/// `rotate` holds angles in degrees about the x, y and z axes.
+struct TransformDescription {
+    translate: Animated<Vec3>,
+    rotate: Animated<Vec3>,
+    scale: Animated<f64>,
+}
+
+impl TransformDescription {
+    const fn new() -> Self {
+        Self {
+            translate: Animated::new(Vec3::empty()),
+            rotate: Animated::new(Vec3::empty()),
+            scale: Animated::new(1.0),
+        }
+    }
+
+    fn property(&mut self, property: &str) -> Option<Property<'_>> {
+        match property {
+            "translate" => Some(Property::Vector(&mut self.translate)),
+            "rotate" => Some(Property::Vector(&mut self.rotate)),
+            "scale" => Some(Property::Scalar(&mut self.scale)),
+            _ => None,
+        }
+    }
+
+    /// The transform at `frame` for an object whose origin is at `origin`, or `None` once it
+    /// is scaled to nothing.
+    fn at(&self, frame: f64, origin: &Point3) -> Option<Transform> {
+        let scale = self.scale.at(frame);
+        if scale == 0.0 {
+            return None;
+        }
+
+        Some(Transform::new(
+            origin.add(&self.translate.at(frame)),
+            &self.rotate.at(frame),
+            scale,
+        ))
+    }
+}
+
+struct SphereDescription {
+    name: String,
+    center: Point3,
+    radius: f64,
+    material: usize,
+    transform: TransformDescription,
+}
+
+/// Patches and meshes are built once and shared by every frame, which only places them and
+/// swaps in the material.
+struct ObjectDescription {
+    name: String,
+    origin: Point3,
+    object: Rc<dyn Hittable>,
+    material: usize,
+    transform: TransformDescription,
+}
+
+struct CameraDescription {
+    look_from: Animated<Point3>,
+    look_at: Animated<Point3>,
+    vup: Animated<Vec3>,
+    vfov: Animated<f64>,
+    aperture: Animated<f64>,
+    focus_dist: Option<Animated<f64>>,
+}
+
+enum Property<'a> {
+    Scalar(&'a mut Animated<f64>),
+    Vector(&'a mut Animated<Vec3>),
+}
+
+/// A scene read from a text file, whose camera, materials and object transforms can be
+/// keyframed. One statement per line, with `#` starting a comment:
+///
+/// ```text
+/// background sky | background <r> <g> <b>
+/// camera look_from|look_at|vup <x> <y> <z>
+/// camera vfov|aperture|focus_dist <value>
+/// material <name> lambertian|metal|dielectric|light <parameters>
+/// sphere <name> <x> <y> <z> <radius> <material>
+/// patches <name> <path> <x> <y> <z> <material> [<segments>]
+/// mesh <name> <path> <x> <y> <z> <material> [<scale>]
+/// key <target>.<property> <frame> <values> [linear | bezier <x1> <y1> <x2> <y2>]
+/// ```
+///
+/// Materials take `r g b` for lambertian and light, `r g b fuzz` for metal and `ior` for
+/// dielectric. Keyable properties are the camera's, a material's `albedo`, `emission`, `fuzz`
+/// and `ior`, and an object's `translate`, `rotate` and `scale`. Objects are scaled and rotated
+/// about their origin, which for a sphere is its center. Patch files are in the Utah teapot
+/// format and meshes PLY or STL files, with paths relative to the scene file. Both are placed
+/// with their origin at `x y z`, and meshes are first scaled about their own origin by
+/// `scale`. Vertex colors in a mesh tint a lambertian material.
+pub struct SceneDescription {
+    background: Background,
+    camera: CameraDescription,
+    materials: Vec<MaterialDescription>,
+    spheres: Vec<SphereDescription>,
+    objects: Vec<ObjectDescription>,
+}
+
+impl SceneDescription {
+    pub fn load(path: &str) -> Result<Self, String> {
+        let contents = fs::read_to_string(path)
+            .map_err(|error| format!("failed to read {}: {}", path, error))?;
+        Self::parse(path, &contents)
+    }
+
+    /// Parses the contents of the scene file at `path`, which errors refer to.
+    fn parse(path: &str, contents: &str) -> Result<Self, String> {
+        let mut description = Self {
+            background: Background::Sky,
+            camera: CameraDescription {
+                look_from: Animated::new(Point3::new(0.0, 0.0, 1.0)),
+                look_at: Animated::new(Point3::new(0.0, 0.0, 0.0)),
+                vup: Animated::new(Vec3::new(0.0, 1.0, 0.0)),
+                vfov: Animated::new(40.0),
+                aperture: Animated::new(0.0),
+                focus_dist: None,
+            },
+            materials: Vec::new(),
+            spheres: Vec::new(),
+            objects: Vec::new(),
+        };
+        let directory = Path::new(path).parent().unwrap_or(Path::new(""));
+        for (number, line) in contents.lines().enumerate() {
+            let line = line.split('#').next().unwrap_or_default();
+            let tokens: Vec<_> = line.split_whitespace().collect();
+            if tokens.is_empty() {
+                continue;
+            }
+            description
+                .parse_statement(&tokens, directory)
+                .map_err(|message| format!("{}:{}: {}", path, number + 1, message))?;
+        }
+
+        Ok(description)
+    }
+
+    fn parse_statement(&mut self, tokens: &[&str], directory: &Path) -> Result<(), String> {
+        match tokens {
+            ["background", "sky"] => self.background = Background::Sky,
+            ["background", values @ ..] => {
+                self.background = Background::Solid(parse_vector(values)?);
+            }
+            ["camera", property, values @ ..] => match self.property("camera", property)? {
+                Property::Scalar(animated) => animated.set(parse_scalar(values)?),
+                Property::Vector(animated) => animated.set(parse_vector(values)?),
+            },
+            ["material", name, kind, values @ ..] => {
+                let (kind, color, parameter) = match *kind {
+                    "lambertian" => (MaterialKind::Lambertian, parse_vector(values)?, 0.0),
+                    "light" => (MaterialKind::Light, parse_vector(values)?, 0.0),
+                    "metal" => {
+                        let [r, g, b, fuzz] = parse_numbers(values)?;
+                        (MaterialKind::Metal, Color::new(r, g, b), fuzz)
+                    }
+                    "dielectric" => (
+                        MaterialKind::Dielectric,
+                        Color::new(1.0, 1.0, 1.0),
+                        parse_scalar(values)?,
+                    ),
+                    _ => return Err(format!("unknown material type {}", kind)),
+                };
+                self.materials.push(MaterialDescription {
+                    name: name.to_string(),
+                    kind,
+                    color: Animated::new(color),
+                    parameter: Animated::new(parameter),
+                });
+            }
+            ["sphere", name, values @ .., material] => {
+                let [x, y, z, radius] = parse_numbers(values)?;
+                let material = self.material(material)?;
+                self.spheres.push(SphereDescription {
+                    name: name.to_string(),
+                    center: Point3::new(x, y, z),
+                    radius,
+                    material,
+                    transform: TransformDescription::new(),
+                });
+            }
+            ["patches", name, path, x, y, z, material, segments @ ..] => {
+                let origin = parse_vector(&[x, y, z])?;
+                let material = self.material(material)?;
+                let Some(initial) = self.materials[material].at(0.0) else {
+                    return Err("patches cannot be lights".to_string());
+                };
+                let segments = match segments {
+                    [] => None,
+                    [segments] => Some(
+                        segments
+                            .parse()
+                            .map_err(|_| format!("invalid segment count {}", segments))?,
+                    ),
+                    _ => return Err(format!("invalid statement {}", tokens.join(" "))),
+                };
+                let path = directory.join(path).to_string_lossy().to_string();
+                let patches = load_patches(&path)?;
+                if patches.is_empty() {
+                    return Err(format!("no patches in {}", path));
+                }
+                let object: Rc<dyn Hittable> = match segments {
+                    Some(segments) => Rc::new(Mesh::new(tessellate(&patches, segments), initial)),
+                    None => {
+                        let patches = patches
+                            .into_iter()
+                            .map(|points| {
+                                let patch: Rc<dyn Hittable> =
+                                    Rc::new(BezierPatch::new(points, initial.clone()));
+                                patch
+                            })
+                            .collect();
+                        BvhNode::build(patches).ok_or(format!("no patches in {}", path))?
+                    }
+                };
+                self.objects.push(ObjectDescription {
+                    name: name.to_string(),
+                    origin,
+                    object,
+                    material,
+                    transform: TransformDescription::new(),
+                });
+            }
+            ["mesh", name, path, x, y, z, material, scale @ ..] => {
+                let origin = parse_vector(&[x, y, z])?;
+                let material = self.material(material)?;
+                let Some(initial) = self.materials[material].at(0.0) else {
+                    return Err("meshes cannot be lights".to_string());
+                };
+                let scale = match scale {
+                    [] => 1.0,
+                    [scale] => parse_number(scale)?,
+                    _ => return Err(format!("invalid statement {}", tokens.join(" "))),
+                };
+                if scale <= 0.0 {
+                    return Err(format!("invalid scale {}", scale));
+                }
+                let path = directory.join(path);
+                let extension = path
+                    .extension()
+                    .and_then(|extension| extension.to_str())
+                    .map(str::to_ascii_lowercase);
+                let mesh = match extension.as_deref() {
+                    Some("ply") => load_ply(&path.to_string_lossy())?,
+                    Some("stl") => load_stl(&path.to_string_lossy())?,
+                    _ => return Err(format!("unknown mesh format {}", path.display())),
+                };
+                self.objects.push(ObjectDescription {
+                    name: name.to_string(),
+                    origin,
+                    object: Rc::new(Mesh::new(mesh.scaled(scale), initial)),
+                    material,
+                    transform: TransformDescription::new(),
+                });
+            }
+            ["key", target, frame, rest @ ..] => {
+                let (target, property) = target
+                    .split_once('.')
+                    .ok_or(format!("expected target.property, found {}", target))?;
+                let frame = parse_number(frame)?;
+                match self.property(target, property)? {
+                    Property::Scalar(animated) => {
+                        let (value, easing) = split_values(rest, 1)?;
+                        animated.add_keyframe(frame, parse_scalar(value)?, parse_easing(easing)?);
+                    }
+                    Property::Vector(animated) => {
+                        let (value, easing) = split_values(rest, 3)?;
+                        animated.add_keyframe(frame, parse_vector(value)?, parse_easing(easing)?);
+                    }
+                }
+            }
+            _ => return Err(format!("invalid statement {}", tokens.join(" "))),
+        }
+
+        Ok(())
+    }
+
+    fn material(&self, name: &str) -> Result<usize, String> {
+        self.materials
+            .iter()
+            .position(|description| description.name == name)
+            .ok_or(format!("unknown material {}", name))
+    }
+
+    fn property(&mut self, target: &str, property: &str) -> Result<Property<'_>, String> {
+        let unknown = || format!("unknown property {}.{}", target, property);
+
+        if target == "camera" {
+            let camera = &mut self.camera;
+            return Ok(match property {
+                "look_from" => Property::Vector(&mut camera.look_from),
+                "look_at" => Property::Vector(&mut camera.look_at),
+                "vup" => Property::Vector(&mut camera.vup),
+                "vfov" => Property::Scalar(&mut camera.vfov),
+                "aperture" => Property::Scalar(&mut camera.aperture),
+                "focus_dist" => {
+                    Property::Scalar(camera.focus_dist.get_or_insert(Animated::new(0.0)))
+                }
+                _ => return Err(unknown()),
+            });
+        }
+
+        if let Some(material) = self
+            .materials
+            .iter_mut()
+            .find(|material| material.name == target)
+        {
+            return match (&material.kind, property) {
+                (MaterialKind::Lambertian | MaterialKind::Metal, "albedo")
+                | (MaterialKind::Light, "emission") => Ok(Property::Vector(&mut material.color)),
+                (MaterialKind::Metal, "fuzz") | (MaterialKind::Dielectric, "ior") => {
+                    Ok(Property::Scalar(&mut material.parameter))
+                }
+                _ => Err(unknown()),
+            };
+        }
+
+        let transform =
+            if let Some(sphere) = self.spheres.iter_mut().find(|sphere| sphere.name == target) {
+                Some(&mut sphere.transform)
+            } else {
+                self.objects
+                    .iter_mut()
+                    .find(|object| object.name == target)
+                    .map(|object| &mut object.transform)
+            };
+        if let Some(transform) = transform {
+            return transform.property(property).ok_or_else(unknown);
+        }
+
+        Err(format!("unknown target {}", target))
+    }
+
+    /// Builds the scene and camera view as they are at `frame`.
+    pub fn at(&self, frame: f64) -> (Scene, CameraView) {
+        let mut scene = Scene::new(self.background);
+
+        let materials: Vec<_> = self
+            .materials
+            .iter()
+            .map(|material| material.at(frame))
+            .collect();
+
+        for sphere in &self.spheres {
+            let Some(transform) = sphere.transform.at(frame, &sphere.center) else {
+                continue;
+            };
+            match &materials[sphere.material] {
+                Some(material) => {
+                    let local = Sphere::new(Point3::empty(), sphere.radius, material.clone());
+                    scene.add(Box::new(Transformed::new(Rc::new(local), transform)));
+                }
+                None => {
+                    // Turning a light sphere about its center doesn't change it.
+                    let center = transform.transform_point(&Point3::empty());
+                    let radius = transform
+                        .transform_vector(&Vec3::new(sphere.radius, 0.0, 0.0))
+                        .length();
+                    let emission = self.materials[sphere.material].color.at(frame);
+                    scene.add_light(Box::new(SphereLight::new(center, radius, emission)));
+                }
+            }
+        }
+
+        for object in &self.objects {
+            let (Some(material), Some(transform)) = (
+                &materials[object.material],
+                object.transform.at(frame, &object.origin),
+            ) else {
+                continue;
+            };
+            let transformed = Transformed::new(Rc::clone(&object.object), transform);
+            scene.add(Box::new(transformed.with_material(material.clone())));
+        }
+
+        let camera = &self.camera;
+        let look_from = camera.look_from.at(frame);
+        let look_at = camera.look_at.at(frame);
+        let focus_dist = camera.focus_dist.as_ref().map_or_else(
+            || look_from.subtract(&look_at).length(),
+            |focus_dist| focus_dist.at(frame),
+        );
+        let view = CameraView::new(
+            look_from,
+            look_at,
+            camera.vup.at(frame),
+            camera.vfov.at(frame),
+            camera.aperture.at(frame),
+            focus_dist,
+        );
+
+        (scene, view)
+    }
+}
+
+fn parse_number(token: &str) -> Result<f64, String> {
+    token
+        .parse()
+        .map_err(|_| format!("invalid number {}", token))
+}
+
+fn parse_numbers<const N: usize>(tokens: &[&str]) -> Result<[f64; N], String> {
+    if tokens.len() != N {
+        return Err(format!("expected {} numbers, found {}", N, tokens.len()));
+    }
+
+    let mut numbers = [0.0; N];
+    for (number, token) in numbers.iter_mut().zip(tokens) {
+        *number = parse_number(token)?;
+    }
+
+    Ok(numbers)
+}
+
+fn parse_scalar(tokens: &[&str]) -> Result<f64, String> {
+    let [value] = parse_numbers(tokens)?;
+    Ok(value)
+}
+
+fn parse_vector(tokens: &[&str]) -> Result<Vec3, String> {
+    let [x, y, z] = parse_numbers(tokens)?;
+    Ok(Vec3::new(x, y, z))
+}
+
+fn split_values<'a>(
+    tokens: &'a [&'a str],
+    count: usize,
+) -> Result<(&'a [&'a str], &'a [&'a str]), String> {
+    if tokens.len() < count {
+        return Err(format!(
+            "expected {} numbers, found {}",
+            count,
+            tokens.len()
+        ));
+    }
+
+    Ok(tokens.split_at(count))
+}
+
+fn parse_easing(tokens: &[&str]) -> Result<Easing, String> {
+    match tokens {
+        [] | ["linear"] => Ok(Easing::Linear),
+        ["bezier", values @ ..] => {
+            let [x1, y1, x2, y2] = parse_numbers(values)?;
+            Ok(Easing::Bezier { x1, y1, x2, y2 })
+        }
+        _ => Err(format!("invalid interpolation {}", tokens.join(" "))),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{env, fs};
+
+    use super::*;
+    use crate::{constants::INFINITY, ray::Ray};
+
+    const SCENE: &str = "# a ball rolling away from a lamp
+background 0.1 0.2 0.3
+camera look_from 0 1 5
+material red lambertian 0.8 0.1 0.1
+material lamp light 4 4 4  # emission
+material glass dielectric 1.5
+sphere ball 0 0 0 1 red
+sphere sun 0 10 0 2 lamp
+
+key ball.translate 0 0 0 0 bezier 0.42 0 1 1
+key ball.translate 10 2 0 0
+key camera.vfov 0 30
+key camera.vfov 10 60
+";
+
+    fn assert_near(actual: f64, expected: f64) {
+        assert!(
+            (actual - expected).abs() < 1e-6,
+            "{} != {}",
+            actual,
+            expected
+        );
+    }
+
+    /// The distance to the first hit along -z from `origin` at `frame`.
+    fn depth(description: &SceneDescription, frame: f64, origin: Point3) -> Option<f64> {
+        let (scene, _) = description.at(frame);
+        let ray = Ray::new(origin, Vec3::new(0.0, 0.0, -1.0));
+        scene
+            .get_world()
+            .hit(&ray, 0.001, INFINITY)
+            .map(|hit| hit.get_t())
+    }
+
+    #[test]
+    fn statements() {
+        let description = SceneDescription::parse("scene", SCENE).unwrap();
+        let Background::Solid(background) = description.background else {
+            panic!("expected a solid background");
+        };
+        assert_near(background.get_y(), 0.2);
+        assert_near(description.camera.look_from.at(0.0).get_z(), 5.0);
+        assert_eq!(description.materials.len(), 3);
+        assert_eq!(description.spheres.len(), 2);
+
+        assert_near(description.camera.vfov.at(0.0), 30.0);
+        assert_near(description.camera.vfov.at(5.0), 45.0);
+        assert_near(description.camera.vfov.at(20.0), 60.0);
+        // Easing in lags behind the linear midpoint.
+        let x = description.spheres[0].transform.translate.at(5.0).get_x();
+        assert!(x > 0.0 && x < 0.9, "{}", x);
+
+        assert_eq!(depth(&description, 0.0, Point3::new(2.0, 0.0, 5.0)), None);
+        assert_near(
+            depth(&description, 10.0, Point3::new(2.0, 0.0, 5.0)).unwrap(),
+            4.0,
+        );
+    }
+
+    #[test]
+    fn errors() {
+        let error = |contents| SceneDescription::parse("scene", contents).err().unwrap();
+        assert_eq!(
+            error("material red lambertian 1 0 0\nsphere ball 0 0 0 1 blue\n"),
+            "scene:2: unknown material blue"
+        );
+        assert_eq!(error("bogus 1 2\n"), "scene:1: invalid statement bogus 1 2");
+        assert_eq!(
+            error("camera zoom 2\n"),
+            "scene:1: unknown property camera.zoom"
+        );
+        assert_eq!(
+            error("# comment\ncamera vfov x\n"),
+            "scene:2: invalid number x"
+        );
+        assert_eq!(
+            error("material red plastic 1 0 0\n"),
+            "scene:1: unknown material type plastic"
+        );
+        assert_eq!(
+            error("material red lambertian 1 0 0\nsphere ball 0 0 0 1 red\nkey ball.spin 0 1\n"),
+            "scene:3: unknown property ball.spin"
+        );
+    }
+
+    #[test]
+    fn meshes_relative_to_scene_file() {
+        let directory = env::temp_dir().join(format!("scene_description_{}", std::process::id()));
+        fs::create_dir_all(&directory).unwrap();
+        fs::write(
+            directory.join("triangle.stl"),
+            "solid triangle
+facet normal 0 0 1
+  outer loop
+    vertex 0 0 0
+    vertex 1 0 0
+    vertex 0 1 0
+  endloop
+endfacet
+endsolid triangle
+",
+        )
+        .unwrap();
+        let path = directory.join("scene.txt");
+        let load = |contents: &str| {
+            fs::write(&path, contents).unwrap();
+            SceneDescription::load(&path.to_string_lossy())
+        };
+
+        let description = load(
+            "material grey lambertian 0.5 0.5 0.5
+mesh tri triangle.stl 0 0 -1 grey 2
+key tri.rotate 0 0 0 0
+key tri.rotate 10 0 0 90
+",
+        )
+        .unwrap();
+        assert_eq!(description.objects.len(), 1);
+        assert_near(
+            depth(&description, 0.0, Point3::new(1.5, 0.25, 5.0)).unwrap(),
+            6.0,
+        );
+        assert_eq!(depth(&description, 0.0, Point3::new(2.5, 0.25, 5.0)), None);
+        // Turned a quarter about z, around the mesh's origin.
+        assert_eq!(depth(&description, 10.0, Point3::new(1.5, 0.25, 5.0)), None);
+        assert_near(
+            depth(&description, 10.0, Point3::new(-0.25, 1.5, 5.0)).unwrap(),
+            6.0,
+        );
+
+        let error = load("material lamp light 1 1 1\nmesh tri triangle.stl 0 0 0 lamp\n")
+            .err()
+            .unwrap();
+        assert!(error.ends_with(":2: meshes cannot be lights"), "{}", error);
+
+        fs::remove_dir_all(&directory).unwrap();
+    }
+}
@@ -0,0 +1,571 @@
+use std::rc::Rc;
+
+use crate::{
+    aabb::Aabb,
+    bezier_patch::{tessellate, BezierPatch, ControlPoints},
+    camera::{CameraView, Lens},
+    color::{Color, BLACK},
+    cone::Cone,
+    constants::PI,
+    csg::{Csg, CsgOperation},
+    curve::{Curve, CurveKind},
+    cylinder::Cylinder,
+    disk::Disk,
+    hittable::Solid,
+    light::SphereLight,
+    material::{Dielectric, DiffuseLight, Dispersion, Hair, Lambertian, Material, Metal},
+    mesh::Mesh,
+    plane::Plane,
+    quadric::Quadric,
+    scene::{Background, Scene},
+    sdf::{Ball, DistanceField, Mandelbulb, RoundedBox, SmoothUnion},
+    sphere::Sphere,
+    subdivision::PolygonMesh,
+    torus::Torus,
+    utils::{random_double, random_in_range, random_vec3, random_vec_in_range, sample_unit_sphere},
+    vec3::{Point3, Vec3},
+};
+
+pub fn from_name(name: &str) -> Option<(Scene, CameraView)> {
+    match name {
+        "random" => Some(random_scene()),
+        "caustics" => Some(caustics_scene()),
+        "dispersion" => Some(dispersion_scene()),
+        "bokeh" => Some(bokeh_scene()),
+        "csg" => Some(csg_scene()),
+        "shapes" => Some(shapes_scene()),
+        "sdf" => Some(sdf_scene()),
+        "patches" => Some(patches_scene()),
+        "hair" => Some(hair_scene()),
+        _ => None,
+    }
+}
+
+fn random_scene() -> (Scene, CameraView) {
+    let mut world = Scene::new(Background::Sky);
+
+    let ground_material = Rc::new(Lambertian::new(Color::new(0.5, 0.5, 0.5)));
+    world.add(Box::new(Plane::new(
+        Point3::new(0.0, 0.0, 0.0),
+        Vec3::new(0.0, 1.0, 0.0),
+        ground_material,
+    )));
+
+    for a in -11..11 {
+        for b in -11..11 {
+            let choose_mat = random_double();
+            let center = Point3::new(
+                a as f64 + 0.9 * random_double(),
+                0.2,
+                b as f64 + 0.9 * random_double(),
+            );
+
+            if (center.subtract(&Point3::new(4.0, 0.2, 0.0))).length() > 0.9 {
+                let sphere_material: Rc<dyn Material> = if choose_mat < 0.8 {
+                    let albedo = random_vec3().multiply(&random_vec3());
+                    Rc::new(Lambertian::new(albedo))
+                } else if choose_mat < 0.95 {
+                    let albedo = random_vec_in_range(0.5, 1.0);
+                    let fuzz = random_in_range(0.0, 0.5);
+                    Rc::new(Metal::new(albedo, fuzz))
+                } else {
+                    Rc::new(Dielectric::new(1.5))
+                };
+
+                world.add(Box::new(Sphere::new(center, 0.2, sphere_material)));
+            }
+        }
+    }
+
+    let material1 = Rc::new(Dielectric::new(1.5));
+    world.add(Box::new(Sphere::new(
+        Point3::new(0.0, 1.0, 0.0),
+        1.0,
+        material1,
+    )));
+
+    let material2 = Rc::new(Lambertian::new(Color::new(0.4, 0.2, 0.1)));
+    world.add(Box::new(Sphere::new(
+        Point3::new(-4.0, 1.0, 0.0),
+        1.0,
+        material2,
+    )));
+
+    let material3 = Rc::new(Metal::new(Color::new(0.7, 0.6, 0.5), 0.0));
+    world.add(Box::new(Sphere::new(
+        Point3::new(4.0, 1.0, 0.0),
+        1.0,
+        material3,
+    )));
+
+    let look_from = Point3::new(13.0, 2.0, 3.0);
+    let look_at = Point3::new(0.0, 0.0, 0.0);
+    let vup = Vec3::new(0.0, 1.0, 0.0);
+    let dist_to_focus = 10.0;
+    let aperture = 0.1;
+
+    let view = CameraView::new(look_from, look_at, vup, 20.0, aperture, dist_to_focus);
+
+    (world, view)
+}
+
+fn caustics_scene() -> (Scene, CameraView) {
+    let mut world = Scene::new(Background::Solid(BLACK));
+
+    let ground_material = Rc::new(Lambertian::new(Color::new(0.73, 0.73, 0.73)));
+    world.add(Box::new(Plane::new(
+        Point3::new(0.0, 0.0, 0.0),
+        Vec3::new(0.0, 1.0, 0.0),
+        ground_material,
+    )));
+
+    let glass = Rc::new(Dielectric::new(1.5));
+    world.add(Box::new(Sphere::new(
+        Point3::new(0.0, 1.0, 0.0),
+        1.0,
+        glass,
+    )));
+
+    let diffuse = Rc::new(Lambertian::new(Color::new(0.65, 0.05, 0.05)));
+    world.add(Box::new(Sphere::new(
+        Point3::new(-2.5, 0.7, -1.0),
+        0.7,
+        diffuse,
+    )));
+
+    world.add_light(Box::new(SphereLight::new(
+        Point3::new(1.0, 5.0, -0.5),
+        0.25,
+        Color::new(80.0, 80.0, 80.0),
+    )));
+
+    let look_from = Point3::new(0.0, 3.0, 8.0);
+    let look_at = Point3::new(0.0, 0.8, 0.0);
+    let vup = Vec3::new(0.0, 1.0, 0.0);
+    let dist_to_focus = look_from.subtract(&look_at).length();
+
+    let view = CameraView::new(look_from, look_at, vup, 35.0, 0.0, dist_to_focus);
+
+    (world, view)
+}
+
+fn dispersion_scene() -> (Scene, CameraView) {
+    let mut world = Scene::new(Background::Sky);
+
+    let ground_material = Rc::new(Lambertian::new(Color::new(0.73, 0.73, 0.73)));
+    world.add(Box::new(Plane::new(
+        Point3::new(0.0, 0.0, 0.0),
+        Vec3::new(0.0, 1.0, 0.0),
+        ground_material,
+    )));
+
+    let diamond = Rc::new(Dielectric::with_dispersion(Dispersion::Sellmeier {
+        b: [0.3306, 4.3356, 0.0],
+        c: [0.030625, 0.011236, 0.0],
+    }));
+    world.add(Box::new(Sphere::new(
+        Point3::new(-1.2, 1.0, 0.0),
+        1.0,
+        diamond,
+    )));
+
+    let flint = Rc::new(Dielectric::with_dispersion(Dispersion::Cauchy {
+        a: 1.728,
+        b: 0.01342,
+    }));
+    world.add(Box::new(Sphere::new(
+        Point3::new(1.2, 1.0, 0.0),
+        1.0,
+        flint,
+    )));
+
+    let look_from = Point3::new(0.0, 4.0, 8.0);
+    let look_at = Point3::new(0.0, 0.5, 0.0);
+    let vup = Vec3::new(0.0, 1.0, 0.0);
+    let dist_to_focus = look_from.subtract(&look_at).length();
+
+    let view = CameraView::new(look_from, look_at, vup, 35.0, 0.0, dist_to_focus);
+
+    (world, view)
+}
+
+fn bokeh_scene() -> (Scene, CameraView) {
+    let mut world = Scene::new(Background::Solid(Color::new(0.02, 0.02, 0.03)));
+
+    let ground_material = Rc::new(Lambertian::new(Color::new(0.4, 0.4, 0.4)));
+    world.add(Box::new(Plane::new(
+        Point3::new(0.0, 0.0, 0.0),
+        Vec3::new(0.0, 1.0, 0.0),
+        ground_material,
+    )));
+
+    let subject = Rc::new(Metal::new(Color::new(0.8, 0.6, 0.2), 0.1));
+    world.add(Box::new(Sphere::new(
+        Point3::new(0.0, 0.5, 0.0),
+        0.5,
+        subject,
+    )));
+
+    for a in -6..=6 {
+        for b in 0..4 {
+            let color = Color::new(
+                0.5 + 0.5 * random_double(),
+                0.4 + 0.4 * random_double(),
+                0.2 + 0.3 * random_double(),
+            );
+            let light = Rc::new(DiffuseLight::new(color.multiply_constant(20.0)));
+            world.add(Box::new(Sphere::new(
+                Point3::new(a as f64 * 0.8, 0.4 + b as f64 * 0.6, -12.0 - b as f64),
+                0.04,
+                light,
+            )));
+        }
+    }
+
+    let look_from = Point3::new(0.0, 0.8, 4.0);
+    let look_at = Point3::new(0.0, 0.5, 0.0);
+    let vup = Vec3::new(0.0, 1.0, 0.0);
+    let dist_to_focus = look_from.subtract(&look_at).length();
+
+    let view = CameraView::new(look_from, look_at, vup, 20.0, 0.0, dist_to_focus)
+        .with_lens(&Lens::new(85.0, 1.4, 36.0));
+
+    (world, view)
+}
+
+fn csg_scene() -> (Scene, CameraView) {
+    let mut world = Scene::new(Background::Sky);
+
+    let ground_material = Rc::new(Lambertian::new(Color::new(0.5, 0.5, 0.5)));
+    world.add(Box::new(Plane::new(
+        Point3::new(0.0, 0.0, 0.0),
+        Vec3::new(0.0, 1.0, 0.0),
+        ground_material,
+    )));
+
+    let glass: Rc<dyn Material> = Rc::new(Dielectric::new(1.5));
+    world.add(Box::new(Csg::new(
+        CsgOperation::Intersection,
+        Box::new(Sphere::new(
+            Point3::new(-2.2, 1.0, -1.6),
+            2.0,
+            glass.clone(),
+        )),
+        Box::new(Sphere::new(Point3::new(-2.2, 1.0, 1.6), 2.0, glass)),
+    )));
+
+    let steel: Rc<dyn Material> = Rc::new(Metal::new(Color::new(0.7, 0.7, 0.75), 0.05));
+    let paint: Rc<dyn Material> = Rc::new(Lambertian::new(Color::new(0.8, 0.3, 0.1)));
+    let mut part: Box<dyn Solid> = Box::new(Sphere::new(Point3::new(0.0, 1.0, 0.0), 1.0, steel));
+    for offset in [
+        Vec3::new(0.0, 0.9, 0.0),
+        Vec3::new(0.7, 0.3, 0.6),
+        Vec3::new(-0.7, 0.3, 0.6),
+    ] {
+        part = Box::new(Csg::new(
+            CsgOperation::Difference,
+            part,
+            Box::new(Sphere::new(
+                Point3::new(0.0, 1.0, 0.0).add(&offset),
+                0.5,
+                paint.clone(),
+            )),
+        ));
+    }
+    world.add(part);
+
+    let red: Rc<dyn Material> = Rc::new(Lambertian::new(Color::new(0.7, 0.1, 0.1)));
+    world.add(Box::new(Csg::new(
+        CsgOperation::Union,
+        Box::new(Sphere::new(Point3::new(2.2, 0.8, 0.0), 0.8, red.clone())),
+        Box::new(Sphere::new(Point3::new(2.2, 1.6, 0.0), 0.6, red)),
+    )));
+
+    let look_from = Point3::new(0.0, 3.0, 8.0);
+    let look_at = Point3::new(0.0, 0.9, 0.0);
+    let vup = Vec3::new(0.0, 1.0, 0.0);
+    let dist_to_focus = look_from.subtract(&look_at).length();
+
+    let view = CameraView::new(look_from, look_at, vup, 35.0, 0.0, dist_to_focus);
+
+    (world, view)
+}
+
+fn shapes_scene() -> (Scene, CameraView) {
+    let mut world = Scene::new(Background::Sky);
+
+    let ground_material = Rc::new(Lambertian::new(Color::new(0.5, 0.5, 0.5)));
+    world.add(Box::new(Plane::new(
+        Point3::new(0.0, 0.0, 0.0),
+        Vec3::new(0.0, 1.0, 0.0),
+        ground_material,
+    )));
+
+    let red = Rc::new(Lambertian::new(Color::new(0.7, 0.15, 0.1)));
+    world.add(Box::new(Cylinder::new(
+        Point3::new(-3.0, 0.0, 0.0),
+        Vec3::new(0.0, 1.0, 0.0),
+        0.6,
+        1.5,
+        red,
+    )));
+
+    let green = Rc::new(Lambertian::new(Color::new(0.1, 0.5, 0.15)));
+    world.add(Box::new(Cone::new(
+        Point3::new(-1.2, 0.0, 0.5),
+        Vec3::new(0.0, 1.0, 0.0),
+        0.7,
+        1.8,
+        green,
+    )));
+
+    let gold = Rc::new(Metal::new(Color::new(0.8, 0.6, 0.2), 0.1));
+    world.add(Box::new(Torus::new(
+        Point3::new(0.7, 0.9, 0.0),
+        Vec3::new(0.0, 0.5, 1.0),
+        0.65,
+        0.25,
+        gold,
+    )));
+
+    let blue = Rc::new(Lambertian::new(Color::new(0.1, 0.2, 0.6)));
+    world.add(Box::new(Disk::new(
+        Point3::new(0.7, 0.01, 1.4),
+        Vec3::new(0.0, 1.0, 0.0),
+        0.6,
+        blue,
+    )));
+
+    // A hyperboloid of one sheet, x² + z² - y² = 0.1, centered at (2.8, 1, 0).
+    let steel = Rc::new(Metal::new(Color::new(0.7, 0.7, 0.75), 0.02));
+    let (cx, cy, cz) = (2.8, 1.0, 0.0);
+    world.add(Box::new(Quadric::new(
+        [
+            1.0,
+            -1.0,
+            1.0,
+            0.0,
+            0.0,
+            0.0,
+            -2.0 * cx,
+            2.0 * cy,
+            -2.0 * cz,
+            cx * cx - cy * cy + cz * cz - 0.1,
+        ],
+        Aabb::new(Point3::new(1.8, 0.0, -1.0), Point3::new(3.8, 2.0, 1.0)),
+        steel,
+    )));
+
+    let look_from = Point3::new(0.0, 3.5, 9.0);
+    let look_at = Point3::new(0.0, 0.8, 0.0);
+    let vup = Vec3::new(0.0, 1.0, 0.0);
+    let dist_to_focus = look_from.subtract(&look_at).length();
+
+    let view = CameraView::new(look_from, look_at, vup, 35.0, 0.0, dist_to_focus);
+
+    (world, view)
+}
+
+fn sdf_scene() -> (Scene, CameraView) {
+    let mut world = Scene::new(Background::Sky);
+
+    let ground_material = Rc::new(Lambertian::new(Color::new(0.5, 0.5, 0.5)));
+    world.add(Box::new(Plane::new(
+        Point3::new(0.0, 0.0, 0.0),
+        Vec3::new(0.0, 1.0, 0.0),
+        ground_material,
+    )));
+
+    let red = Rc::new(Lambertian::new(Color::new(0.7, 0.15, 0.1)));
+    world.add(Box::new(DistanceField::new(
+        Box::new(RoundedBox::new(
+            Point3::new(-2.6, 0.6, 0.0),
+            Vec3::new(0.6, 0.6, 0.6),
+            0.15,
+        )),
+        red,
+    )));
+
+    let blob = Rc::new(Metal::new(Color::new(0.3, 0.5, 0.8), 0.05));
+    world.add(Box::new(DistanceField::new(
+        Box::new(SmoothUnion::new(
+            Box::new(RoundedBox::new(
+                Point3::new(0.0, 0.4, 0.0),
+                Vec3::new(0.8, 0.4, 0.5),
+                0.1,
+            )),
+            Box::new(Ball::new(Point3::new(0.0, 1.1, 0.0), 0.5)),
+            0.4,
+        )),
+        blob,
+    )));
+
+    // Fractals need a tighter epsilon and more steps to resolve their detail.
+    let gold = Rc::new(Metal::new(Color::new(0.8, 0.6, 0.2), 0.2));
+    world.add(Box::new(
+        DistanceField::new(
+            Box::new(Mandelbulb::new(Point3::new(2.6, 1.1, 0.0), 1.0, 8.0, 8)),
+            gold,
+        )
+        .with_max_steps(384)
+        .with_epsilon(5e-5),
+    ));
+
+    let look_from = Point3::new(0.0, 3.0, 9.0);
+    let look_at = Point3::new(0.0, 0.8, 0.0);
+    let vup = Vec3::new(0.0, 1.0, 0.0);
+    let dist_to_focus = look_from.subtract(&look_at).length();
+
+    let view = CameraView::new(look_from, look_at, vup, 35.0, 0.0, dist_to_focus);
+
+    (world, view)
+}
+
+/// Four patches sweeping a cubic profile of `(radius, height)` points around the y axis, with
+/// each quarter circle drawn as a cubic arc.
+fn revolve(center: Point3, profile: [(f64, f64); 4]) -> Vec<ControlPoints> {
+    const ARC: f64 = 0.552_284_75;
+    let quarter = [(1.0, 0.0), (1.0, ARC), (ARC, 1.0), (0.0, 1.0)];
+
+    (0..4)
+        .map(|turn| {
+            let mut points = [[center; 4]; 4];
+            for (i, (radius, height)) in profile.iter().enumerate() {
+                for (j, (x, z)) in quarter.iter().enumerate() {
+                    let (x, z) = match turn {
+                        0 => (*x, *z),
+                        1 => (-z, *x),
+                        2 => (-x, -z),
+                        _ => (*z, -x),
+                    };
+                    points[i][j] = center.add(&Vec3::new(radius * x, *height, radius * z));
+                }
+            }
+            points
+        })
+        .collect()
+}
+
+fn patches_scene() -> (Scene, CameraView) {
+    let mut world = Scene::new(Background::Sky);
+
+    let ground_material = Rc::new(Lambertian::new(Color::new(0.5, 0.5, 0.5)));
+    world.add(Box::new(Plane::new(
+        Point3::new(0.0, 0.0, 0.0),
+        Vec3::new(0.0, 1.0, 0.0),
+        ground_material,
+    )));
+
+    let profile = [(0.5, 0.0), (1.3, 0.5), (0.1, 1.2), (0.45, 1.9)];
+
+    // The same vase traced directly on the left and as triangles in the middle.
+    let blue = Rc::new(Lambertian::new(Color::new(0.1, 0.2, 0.6)));
+    for points in revolve(Point3::new(-2.4, 0.0, 0.0), profile) {
+        world.add(Box::new(BezierPatch::new(points, blue.clone())));
+    }
+
+    let gold = Rc::new(Metal::new(Color::new(0.8, 0.6, 0.2), 0.1));
+    world.add(Box::new(Mesh::new(
+        tessellate(&revolve(Point3::new(0.0, 0.0, 0.0), profile), 8),
+        gold,
+    )));
+
+    // A cube cage subdivided three times into a rounded blob.
+    let cage = PolygonMesh::new(
+        vec![
+            Point3::new(1.7, 0.0, -0.7),
+            Point3::new(3.1, 0.0, -0.7),
+            Point3::new(3.1, 1.4, -0.7),
+            Point3::new(1.7, 1.4, -0.7),
+            Point3::new(1.7, 0.0, 0.7),
+            Point3::new(3.1, 0.0, 0.7),
+            Point3::new(3.1, 1.4, 0.7),
+            Point3::new(1.7, 1.4, 0.7),
+        ],
+        vec![
+            vec![0, 3, 2, 1],
+            vec![4, 5, 6, 7],
+            vec![0, 1, 5, 4],
+            vec![3, 7, 6, 2],
+            vec![0, 4, 7, 3],
+            vec![1, 2, 6, 5],
+        ],
+    );
+    let red = Rc::new(Lambertian::new(Color::new(0.7, 0.15, 0.1)));
+    world.add(Box::new(Mesh::new(cage.subdivide(3), red)));
+
+    let look_from = Point3::new(0.0, 3.0, 9.0);
+    let look_at = Point3::new(0.0, 0.8, 0.0);
+    let vup = Vec3::new(0.0, 1.0, 0.0);
+    let dist_to_focus = look_from.subtract(&look_at).length();
+
+    let view = CameraView::new(look_from, look_at, vup, 35.0, 0.0, dist_to_focus);
+
+    (world, view)
+}
+
+fn hair_scene() -> (Scene, CameraView) {
+    let mut world = Scene::new(Background::Sky);
+
+    let ground_material = Rc::new(Lambertian::new(Color::new(0.35, 0.25, 0.15)));
+    world.add(Box::new(Plane::new(
+        Point3::new(0.0, 0.0, 0.0),
+        Vec3::new(0.0, 1.0, 0.0),
+        ground_material,
+    )));
+
+    // A ball of brown fur whose strands droop under their own weight.
+    let fur = Rc::new(Hair::from_melanin(1.3, 0.2, 0.3, 0.3, 2.0));
+    let center = Point3::new(0.0, 1.0, 0.0);
+    let gravity = Vec3::new(0.0, -0.12, 0.0);
+    for _ in 0..4000 {
+        let normal = sample_unit_sphere((random_double(), random_double()));
+        let root = center.add(&normal.multiply_constant(0.7));
+        let length = random_in_range(0.25, 0.35);
+        let step = normal.multiply_constant(length / 3.0);
+        let p1 = root.add(&step);
+        let p2 = p1.add(&step).add(&gravity);
+        let p3 = p2.add(&step).add(&gravity.multiply_constant(2.0));
+        world.add(Box::new(Curve::new(
+            [root, p1, p2, p3],
+            [0.012, 0.003],
+            CurveKind::Round,
+            fur.clone(),
+        )));
+    }
+    world.add(Box::new(Sphere::new(
+        center,
+        0.72,
+        Rc::new(Lambertian::new(Color::new(0.1, 0.07, 0.05))),
+    )));
+
+    // Blades of grass bending away from their own flat side.
+    let grass = Rc::new(Lambertian::new(Color::new(0.2, 0.45, 0.1)));
+    for _ in 0..6000 {
+        let root = Point3::new(random_in_range(-4.0, 4.0), 0.0, random_in_range(-2.5, 2.5));
+        let height = random_in_range(0.25, 0.6);
+        let angle = random_in_range(0.0, 2.0 * PI);
+        let lean = Vec3::new(angle.cos(), 0.0, angle.sin()).multiply_constant(height * 0.4);
+        let up = Vec3::new(0.0, height / 3.0, 0.0);
+        let p1 = root.add(&up);
+        let p2 = p1.add(&up).add(&lean.multiply_constant(0.3));
+        let p3 = p2.add(&up).add(&lean);
+        world.add(Box::new(Curve::new(
+            [root, p1, p2, p3],
+            [0.05, 0.005],
+            CurveKind::Ribbon {
+                normals: [lean, lean.add(&Vec3::new(0.0, height, 0.0))],
+            },
+            grass.clone(),
+        )));
+    }
+
+    let look_from = Point3::new(0.0, 2.0, 6.0);
+    let look_at = Point3::new(0.0, 0.8, 0.0);
+    let vup = Vec3::new(0.0, 1.0, 0.0);
+    let dist_to_focus = look_from.subtract(&look_at).length();
+
+    let view = CameraView::new(look_from, look_at, vup, 35.0, 0.0, dist_to_focus);
+
+    (world, view)
+}
@@ -0,0 +1,30 @@
+use crate::{
+    aabb::Aabb,
+    vec3::{Point3, Vec3},
+};
+
+use super::Sdf;
+
+pub struct Ball {
+    center: Point3,
+    radius: f64,
+}
+
+impl Ball {
+    pub const fn new(center: Point3, radius: f64) -> Self {
+        Self { center, radius }
+    }
+}
+
+impl Sdf for Ball {
+    fn distance(&self, point: &Point3) -> f64 {
+        point.subtract(&self.center).length() - self.radius
+    }
+
+    fn bounding_box(&self) -> Aabb {
+        Aabb::around(
+            self.center,
+            Vec3::new(self.radius, self.radius, self.radius),
+        )
+    }
+}
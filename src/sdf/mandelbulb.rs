@@ -0,0 +1,69 @@
+use crate::{
+    aabb::Aabb,
+    vec3::{Point3, Vec3},
+};
+
+use super::Sdf;
+
+const BAILOUT: f64 = 2.0;
+/// Radius of a sphere that encloses the set for the usual powers.
+const BOUND: f64 = 1.2;
+
+/// The power `power` Mandelbulb, with its pole along y, scaled by `scale` about `center`.
+pub struct Mandelbulb {
+    center: Point3,
+    scale: f64,
+    power: f64,
+    iterations: usize,
+}
+
+impl Mandelbulb {
+    pub const fn new(center: Point3, scale: f64, power: f64, iterations: usize) -> Self {
+        Self {
+            center,
+            scale,
+            power,
+            iterations,
+        }
+    }
+}
+
+impl Sdf for Mandelbulb {
+    /// The distance estimate `0.5 ln(r) r / dr` from the running derivative of the iteration.
+    fn distance(&self, point: &Point3) -> f64 {
+        let c = point.subtract(&self.center).divide_constant(self.scale);
+        let mut z = c;
+        let mut derivative = 1.0;
+        let mut r = z.length();
+
+        for _ in 0..self.iterations {
+            if r > BAILOUT || r == 0.0 {
+                break;
+            }
+
+            let theta = (z.get_y() / r).acos() * self.power;
+            let phi = z.get_z().atan2(z.get_x()) * self.power;
+            derivative = self.power * r.powf(self.power - 1.0) * derivative + 1.0;
+            let radius = r.powf(self.power);
+            z = Vec3::new(
+                theta.sin() * phi.cos(),
+                theta.cos(),
+                theta.sin() * phi.sin(),
+            )
+            .multiply_constant(radius)
+            .add(&c);
+            r = z.length();
+        }
+
+        if r == 0.0 {
+            return 0.0;
+        }
+
+        0.5 * r.ln() * r / derivative * self.scale
+    }
+
+    fn bounding_box(&self) -> Aabb {
+        let extent = BOUND * self.scale;
+        Aabb::around(self.center, Vec3::new(extent, extent, extent))
+    }
+}
@@ -0,0 +1,129 @@
+use std::rc::Rc;
+
+pub use ball::Ball;
+pub use mandelbulb::Mandelbulb;
+pub use rounded_box::RoundedBox;
+pub use smooth_union::SmoothUnion;
+
+mod ball;
+mod mandelbulb;
+mod rounded_box;
+mod smooth_union;
+
+use crate::{
+    aabb::Aabb,
+    hittable::{HitRecord, Hittable},
+    material::Material,
+    ray::Ray,
+    sphere::sphere_uv,
+    stats,
+    vec3::{Point3, Vec3},
+};
+
+const DEFAULT_MAX_STEPS: usize = 256;
+const DEFAULT_EPSILON: f64 = 1e-4;
+
+/// A signed distance field: negative inside the shape, positive outside, and never more than
+/// the true distance to the surface so that sphere tracing cannot step through it.
+pub trait Sdf {
+    fn distance(&self, point: &Point3) -> f64;
+
+    /// A box outside of which the shape is guaranteed to be empty.
+    fn bounding_box(&self) -> Aabb;
+}
+
+/// Renders a distance field by sphere tracing, stepping along the ray by the distance to the
+/// surface until it is closer than `epsilon` or `max_steps` is used up.
+pub struct DistanceField {
+    sdf: Box<dyn Sdf>,
+    material: Rc<dyn Material>,
+    max_steps: usize,
+    epsilon: f64,
+}
+
+impl DistanceField {
+    pub fn new(sdf: Box<dyn Sdf>, material: Rc<dyn Material>) -> Self {
+        Self {
+            sdf,
+            material,
+            max_steps: DEFAULT_MAX_STEPS,
+            epsilon: DEFAULT_EPSILON,
+        }
+    }
+
+    pub fn with_max_steps(self, max_steps: usize) -> Self {
+        Self { max_steps, ..self }
+    }
+
+    pub fn with_epsilon(self, epsilon: f64) -> Self {
+        Self { epsilon, ..self }
+    }
+
+    /// Gradient of the field by central differences.
+    fn normal(&self, point: &Point3) -> Vec3 {
+        let h = self.epsilon;
+        let difference = |offset: Vec3| {
+            self.sdf.distance(&point.add(&offset)) - self.sdf.distance(&point.subtract(&offset))
+        };
+
+        Vec3::new(
+            difference(Vec3::new(h, 0.0, 0.0)),
+            difference(Vec3::new(0.0, h, 0.0)),
+            difference(Vec3::new(0.0, 0.0, h)),
+        )
+        .unit_vector()
+    }
+}
+
+impl Hittable for DistanceField {
+    /// Rays that start on the surface, like bounces off it, first have to get further than
+    /// `epsilon` from it before a hit counts. Rays starting inside march on the negated field.
+    fn hit(&self, ray: &Ray, t_min: f64, t_max: f64) -> Option<HitRecord> {
+        stats::record_intersection_test();
+        let bounds = self.sdf.bounding_box().expand(self.epsilon);
+        let (mut t, t_max) = bounds.clip(ray, t_min, t_max)?;
+        let speed = ray.get_direction().length();
+
+        let mut sign = None;
+        let mut escaped = t > t_min;
+        for _ in 0..self.max_steps {
+            let point = ray.at(t);
+            let distance = self.sdf.distance(&point);
+            let sign = *sign.get_or_insert_with(|| {
+                if distance.abs() >= self.epsilon {
+                    distance.signum()
+                } else if self.normal(&point).dot(&ray.get_direction()) > 0.0 {
+                    1.0
+                } else {
+                    -1.0
+                }
+            });
+
+            let distance = sign * distance;
+            if distance >= self.epsilon {
+                escaped = true;
+            } else if escaped {
+                let outward_normal = self.normal(&point);
+                return Some(HitRecord::new(
+                    point,
+                    outward_normal,
+                    t,
+                    sphere_uv(&outward_normal),
+                    ray,
+                    Rc::clone(&self.material),
+                ));
+            }
+
+            t += distance.max(self.epsilon) / speed;
+            if t > t_max {
+                return None;
+            }
+        }
+
+        None
+    }
+
+    fn bounding_box(&self) -> Option<Aabb> {
+        Some(self.sdf.bounding_box())
+    }
+}
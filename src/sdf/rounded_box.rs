@@ -0,0 +1,44 @@
+use crate::{
+    aabb::Aabb,
+    vec3::{Point3, Vec3},
+};
+
+use super::Sdf;
+
+/// An axis-aligned box whose edges and corners are rounded off with `radius`, which is taken
+/// from inside `half_extent`.
+pub struct RoundedBox {
+    center: Point3,
+    half_extent: Vec3,
+    radius: f64,
+}
+
+impl RoundedBox {
+    pub const fn new(center: Point3, half_extent: Vec3, radius: f64) -> Self {
+        Self {
+            center,
+            half_extent,
+            radius,
+        }
+    }
+}
+
+impl Sdf for RoundedBox {
+    fn distance(&self, point: &Point3) -> f64 {
+        let local = point.subtract(&self.center);
+        let q = Vec3::new(
+            local.get_x().abs(),
+            local.get_y().abs(),
+            local.get_z().abs(),
+        )
+        .subtract(&self.half_extent)
+        .add_constant(self.radius);
+        let inside = q.get_x().max(q.get_y()).max(q.get_z()).min(0.0);
+
+        q.max_constant(0.0).length() + inside - self.radius
+    }
+
+    fn bounding_box(&self) -> Aabb {
+        Aabb::around(self.center, self.half_extent)
+    }
+}
@@ -0,0 +1,42 @@
+use crate::{aabb::Aabb, vec3::Point3};
+
+use super::Sdf;
+
+/// Joins two fields with a blend of width `smoothness` instead of a crease, using the
+/// polynomial smooth minimum.
+pub struct SmoothUnion {
+    left: Box<dyn Sdf>,
+    right: Box<dyn Sdf>,
+    smoothness: f64,
+}
+
+impl SmoothUnion {
+    pub fn new(left: Box<dyn Sdf>, right: Box<dyn Sdf>, smoothness: f64) -> Self {
+        Self {
+            left,
+            right,
+            smoothness,
+        }
+    }
+}
+
+impl Sdf for SmoothUnion {
+    fn distance(&self, point: &Point3) -> f64 {
+        let a = self.left.distance(point);
+        let b = self.right.distance(point);
+        if self.smoothness <= 0.0 {
+            return a.min(b);
+        }
+
+        let h = (0.5 + 0.5 * (b - a) / self.smoothness).clamp(0.0, 1.0);
+        b + (a - b) * h - self.smoothness * h * (1.0 - h)
+    }
+
+    /// The blend lowers the distance by at most a quarter of the smoothness.
+    fn bounding_box(&self) -> Aabb {
+        self.left
+            .bounding_box()
+            .surrounding(&self.right.bounding_box())
+            .expand(self.smoothness.max(0.0) / 4.0)
+    }
+}
@@ -0,0 +1,93 @@
+use std::time::Duration;
+
+use crate::{color::MultipleSamplesColor, film::Film};
+
+pub struct AdaptiveSampling {
+    threshold: f64,
+    max_samples: usize,
+}
+
+impl AdaptiveSampling {
+    pub const fn new(threshold: f64, max_samples: usize) -> Self {
+        Self {
+            threshold,
+            max_samples,
+        }
+    }
+
+    pub fn is_converged(&self, color: &MultipleSamplesColor) -> bool {
+        color.get_samples_count() >= self.max_samples || color.relative_error() < self.threshold
+    }
+
+    pub const fn get_max_samples(&self) -> usize {
+        self.max_samples
+    }
+}
+
+pub struct Checkpoint {
+    path: String,
+    interval: Duration,
+}
+
+impl Checkpoint {
+    pub const fn new(path: String, interval: Duration) -> Self {
+        Self { path, interval }
+    }
+
+    pub const fn get_interval(&self) -> Duration {
+        self.interval
+    }
+
+    pub fn save(&self, film: &Film) {
+        if let Err(error) = film.save_checkpoint(&self.path) {
+            eprintln!("failed to write checkpoint {}: {}", self.path, error);
+        }
+    }
+}
+
+pub struct RenderSettings {
+    samples_per_pixel: usize,
+    adaptive: Option<AdaptiveSampling>,
+    checkpoint: Option<Checkpoint>,
+}
+
+impl RenderSettings {
+    pub const fn new(
+        samples_per_pixel: usize,
+        adaptive: Option<AdaptiveSampling>,
+        checkpoint: Option<Checkpoint>,
+    ) -> Self {
+        Self {
+            samples_per_pixel,
+            adaptive,
+            checkpoint,
+        }
+    }
+
+    /// Whether a pixel still needs samples to reach the target count or, with adaptive
+    /// sampling, to converge after that.
+    pub fn needs_samples(&self, color: &MultipleSamplesColor) -> bool {
+        color.get_samples_count() < self.samples_per_pixel
+            || self
+                .adaptive
+                .as_ref()
+                .is_some_and(|adaptive| !adaptive.is_converged(color))
+    }
+
+    pub const fn get_samples_per_pixel(&self) -> usize {
+        self.samples_per_pixel
+    }
+
+    /// The most samples any pixel can take, which adaptive sampling may stop short of.
+    pub fn get_max_samples_per_pixel(&self) -> usize {
+        self.adaptive
+            .as_ref()
+            .map_or(self.samples_per_pixel, |adaptive| {
+                adaptive.get_max_samples().max(self.samples_per_pixel)
+            })
+    }
+
+    pub const fn get_checkpoint(&self) -> Option<&Checkpoint> {
+        self.checkpoint.as_ref()
+    }
+}
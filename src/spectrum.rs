@@ -0,0 +1,204 @@
+use std::sync::OnceLock;
+
+use crate::{color::Color, color_pipeline::XYZ_TO_SRGB, vec3::Vec3};
+
+pub const WAVELENGTH_SAMPLES: usize = 4;
+pub const LAMBDA_MIN: f64 = 360.0;
+pub const LAMBDA_MAX: f64 = 830.0;
+
+const CIE_Y_INTEGRAL: f64 = 106.856895;
+const SMITS_MIN: f64 = 380.0;
+const SMITS_MAX: f64 = 720.0;
+const SMITS_WHITE: [f64; 10] = [1.0, 1.0, 0.9999, 0.9993, 0.9992, 0.9998, 1.0, 1.0, 1.0, 1.0];
+const SMITS_CYAN: [f64; 10] = [
+    0.9710, 0.9426, 1.0007, 1.0007, 1.0007, 1.0007, 0.1564, 0.0, 0.0, 0.0,
+];
+const SMITS_MAGENTA: [f64; 10] = [
+    1.0, 1.0, 0.9685, 0.2229, 0.0, 0.0458, 0.8369, 1.0, 1.0, 0.9959,
+];
+const SMITS_YELLOW: [f64; 10] = [
+    0.0001, 0.0, 0.1088, 0.6651, 1.0, 1.0, 0.9996, 0.9586, 0.9685, 0.9840,
+];
+const SMITS_RED: [f64; 10] = [
+    0.1012, 0.0515, 0.0, 0.0, 0.0, 0.0, 0.8325, 1.0149, 1.0149, 1.0149,
+];
+const SMITS_GREEN: [f64; 10] = [
+    0.0, 0.0, 0.0273, 0.7937, 1.0, 0.9418, 0.1719, 0.0, 0.0, 0.0025,
+];
+const SMITS_BLUE: [f64; 10] = [
+    1.0, 1.0, 0.8916, 0.3323, 0.0, 0.0, 0.0003, 0.0369, 0.0483, 0.0496,
+];
+
+#[derive(Clone, Copy)]
+pub struct SampledSpectrum {
+    values: [f64; WAVELENGTH_SAMPLES],
+}
+
+impl SampledSpectrum {
+    pub const fn new(value: f64) -> Self {
+        Self {
+            values: [value; WAVELENGTH_SAMPLES],
+        }
+    }
+
+    /// Upsamples a reflectance-like RGB color at the given wavelengths.
+    pub fn from_rgb(color: &Color, wavelengths: &SampledWavelengths) -> Self {
+        let mut values = [0.0; WAVELENGTH_SAMPLES];
+        for (value, lambda) in values.iter_mut().zip(wavelengths.lambda) {
+            *value = rgb_to_spectrum(color, lambda);
+        }
+
+        Self { values }
+    }
+
+    pub fn add(&self, other: &Self) -> Self {
+        let mut values = self.values;
+        for (value, other) in values.iter_mut().zip(other.values) {
+            *value += other;
+        }
+
+        Self { values }
+    }
+
+    pub fn multiply(&self, other: &Self) -> Self {
+        let mut values = self.values;
+        for (value, other) in values.iter_mut().zip(other.values) {
+            *value *= other;
+        }
+
+        Self { values }
+    }
+
+    pub fn is_black(&self) -> bool {
+        self.values.iter().all(|value| *value == 0.0)
+    }
+}
+
+/// Hero wavelength sampling: one wavelength drawn from the visible distribution and the
+/// rest spread at equal offsets over the same primary sample.
+pub struct SampledWavelengths {
+    lambda: [f64; WAVELENGTH_SAMPLES],
+    pdf: [f64; WAVELENGTH_SAMPLES],
+}
+
+impl SampledWavelengths {
+    pub fn sample_visible(u: f64) -> Self {
+        let mut lambda = [0.0; WAVELENGTH_SAMPLES];
+        let mut pdf = [0.0; WAVELENGTH_SAMPLES];
+
+        for i in 0..WAVELENGTH_SAMPLES {
+            let up = (u + i as f64 / WAVELENGTH_SAMPLES as f64).fract();
+            lambda[i] = sample_visible_wavelength(up);
+            pdf[i] = visible_wavelength_pdf(lambda[i]);
+        }
+
+        Self { lambda, pdf }
+    }
+
+    pub const fn get_hero(&self) -> f64 {
+        self.lambda[0]
+    }
+
+    /// Keeps only the hero wavelength, for paths through wavelength-dependent scattering.
+    pub fn terminate_secondary(&mut self) {
+        if self.pdf[1..].iter().all(|pdf| *pdf == 0.0) {
+            return;
+        }
+
+        self.pdf[1..].fill(0.0);
+        self.pdf[0] /= WAVELENGTH_SAMPLES as f64;
+    }
+
+    /// Monte Carlo estimate of the linear sRGB value of `spectrum`, with an equal-energy
+    /// spectrum mapping to white.
+    pub fn to_rgb(&self, spectrum: &SampledSpectrum) -> Color {
+        let mut xyz = Vec3::new(0.0, 0.0, 0.0);
+        for i in 0..WAVELENGTH_SAMPLES {
+            if self.pdf[i] > 0.0 {
+                xyz = xyz.add(
+                    &cie_xyz(self.lambda[i]).multiply_constant(spectrum.values[i] / self.pdf[i]),
+                );
+            }
+        }
+        let xyz = xyz.divide_constant(WAVELENGTH_SAMPLES as f64 * CIE_Y_INTEGRAL);
+
+        XYZ_TO_SRGB
+            .multiply_vector(&xyz)
+            .divide(&equal_energy_rgb())
+    }
+}
+
+fn sample_visible_wavelength(u: f64) -> f64 {
+    538.0 - 138.888889 * (0.85691062 - 1.82750197 * u).atanh()
+}
+
+fn visible_wavelength_pdf(lambda: f64) -> f64 {
+    if !(LAMBDA_MIN..=LAMBDA_MAX).contains(&lambda) {
+        return 0.0;
+    }
+
+    0.0039398042 / (0.0072 * (lambda - 538.0)).cosh().powi(2)
+}
+
+/// Wyman, Sloan and Shirley's multi-lobe Gaussian fit of the CIE 1931 matching functions.
+fn cie_xyz(lambda: f64) -> Vec3 {
+    let lobe = |mu: f64, sigma_low: f64, sigma_high: f64| {
+        let sigma = if lambda < mu { sigma_low } else { sigma_high };
+        let t = (lambda - mu) / sigma;
+        (-0.5 * t * t).exp()
+    };
+
+    Vec3::new(
+        1.056 * lobe(599.8, 37.9, 31.0) + 0.362 * lobe(442.0, 16.0, 26.7)
+            - 0.065 * lobe(501.1, 20.4, 26.2),
+        0.821 * lobe(568.8, 46.9, 40.5) + 0.286 * lobe(530.9, 16.3, 31.1),
+        1.217 * lobe(437.0, 11.8, 36.0) + 0.681 * lobe(459.0, 26.0, 13.8),
+    )
+}
+
+fn equal_energy_rgb() -> Color {
+    static WHITE: OnceLock<Color> = OnceLock::new();
+
+    *WHITE.get_or_init(|| {
+        let xyz = (LAMBDA_MIN as usize..=LAMBDA_MAX as usize)
+            .map(|lambda| cie_xyz(lambda as f64))
+            .fold(Vec3::new(0.0, 0.0, 0.0), |sum, value| sum.add(&value))
+            .divide_constant(CIE_Y_INTEGRAL);
+
+        XYZ_TO_SRGB.multiply_vector(&xyz)
+    })
+}
+
+/// Smits' RGB to reflectance spectrum conversion, evaluated at a single wavelength.
+fn rgb_to_spectrum(color: &Color, lambda: f64) -> f64 {
+    let (r, g, b) = (color.get_x(), color.get_y(), color.get_z());
+    let basis = |table: &[f64; 10]| {
+        let position = ((lambda - SMITS_MIN) / (SMITS_MAX - SMITS_MIN) * 9.0).clamp(0.0, 9.0);
+        let index = (position as usize).min(8);
+        let t = position - index as f64;
+        table[index] * (1.0 - t) + table[index + 1] * t
+    };
+
+    if r <= g && r <= b {
+        r * basis(&SMITS_WHITE)
+            + if g <= b {
+                (g - r) * basis(&SMITS_CYAN) + (b - g) * basis(&SMITS_BLUE)
+            } else {
+                (b - r) * basis(&SMITS_CYAN) + (g - b) * basis(&SMITS_GREEN)
+            }
+    } else if g <= r && g <= b {
+        g * basis(&SMITS_WHITE)
+            + if r <= b {
+                (r - g) * basis(&SMITS_MAGENTA) + (b - r) * basis(&SMITS_BLUE)
+            } else {
+                (b - g) * basis(&SMITS_MAGENTA) + (r - b) * basis(&SMITS_RED)
+            }
+    } else {
+        b * basis(&SMITS_WHITE)
+            + if r <= g {
+                (r - b) * basis(&SMITS_YELLOW) + (g - r) * basis(&SMITS_GREEN)
+            } else {
+                (g - b) * basis(&SMITS_YELLOW) + (r - g) * basis(&SMITS_RED)
+            }
+    }
+}
@@ -1,9 +1,13 @@
 use std::rc::Rc;
 
 use crate::{
-    hittable::{HitRecord, Hittable},
+    aabb::Aabb,
+    constants::PI,
+    hittable::{HitRecord, Hittable, Interval, Solid},
     material::Material,
-    vec3::Point3,
+    ray::Ray,
+    stats,
+    vec3::{Point3, Vec3},
 };
 
 pub struct Sphere {
@@ -22,8 +26,24 @@ impl Sphere {
     }
 }
 
+impl Sphere {
+    fn record(&self, ray: &Ray, t: f64) -> HitRecord {
+        let point = ray.at(t);
+        let outward_normal = point.subtract(&self.center).divide_constant(self.radius);
+        HitRecord::new(
+            point,
+            outward_normal,
+            t,
+            sphere_uv(&outward_normal),
+            ray,
+            Rc::clone(&self.material),
+        )
+    }
+}
+
 impl Hittable for Sphere {
-    fn hit(&self, ray: &crate::ray::Ray, t_min: f64, t_max: f64) -> Option<HitRecord> {
+    fn hit(&self, ray: &Ray, t_min: f64, t_max: f64) -> Option<HitRecord> {
+        stats::record_intersection_test();
         let oc = ray.get_origin().subtract(&self.center);
         let a = ray.get_direction().square();
         let half_b = oc.dot(&ray.get_direction());
@@ -45,11 +65,37 @@ impl Hittable for Sphere {
                 None
             };
 
-            solution.map(|t| {
-                let point = ray.at(t);
-                let outward_normal = point.subtract(&self.center).divide_constant(self.radius);
-                HitRecord::new(point, outward_normal, t, ray, Rc::clone(&self.material))
-            })
+            solution.map(|t| self.record(ray, t))
         }
     }
+
+    fn bounding_box(&self) -> Option<Aabb> {
+        let radius = self.radius.abs();
+        Some(Aabb::around(self.center, Vec3::new(radius, radius, radius)))
+    }
+}
+
+impl Solid for Sphere {
+    fn intervals(&self, ray: &Ray) -> Vec<Interval> {
+        stats::record_intersection_test();
+        let oc = ray.get_origin().subtract(&self.center);
+        let a = ray.get_direction().square();
+        let half_b = oc.dot(&ray.get_direction());
+        let c = oc.dot(&oc) - self.radius * self.radius;
+        let discriminant = half_b * half_b - a * c;
+        if discriminant <= 0.0 {
+            return vec![];
+        }
+
+        let t1 = (-half_b - discriminant.sqrt()) / a;
+        let t2 = (-half_b + discriminant.sqrt()) / a;
+        vec![Interval::new(self.record(ray, t1), self.record(ray, t2))]
+    }
+}
+
+pub fn sphere_uv(point: &Vec3) -> (f64, f64) {
+    let theta = (-point.get_y()).acos();
+    let phi = (-point.get_z()).atan2(point.get_x()) + PI;
+
+    (phi / (2.0 * PI), theta / PI)
 }
@@ -0,0 +1,69 @@
+use std::{
+    sync::atomic::{AtomicU64, Ordering},
+    time::Duration,
+};
+
+static RAYS: AtomicU64 = AtomicU64::new(0);
+static PRIMARY_RAYS: AtomicU64 = AtomicU64::new(0);
+static INTERSECTION_TESTS: AtomicU64 = AtomicU64::new(0);
+static BVH_NODE_VISITS: AtomicU64 = AtomicU64::new(0);
+
+/// Counts a ray traced against the whole scene, camera, bounce and shadow rays alike.
+pub fn record_ray() {
+    RAYS.fetch_add(1, Ordering::Relaxed);
+}
+
+pub fn record_primary_ray() {
+    PRIMARY_RAYS.fetch_add(1, Ordering::Relaxed);
+}
+
+/// Counts a ray tested against a single primitive.
+pub fn record_intersection_test() {
+    INTERSECTION_TESTS.fetch_add(1, Ordering::Relaxed);
+}
+
+pub fn record_bvh_node_visit() {
+    BVH_NODE_VISITS.fetch_add(1, Ordering::Relaxed);
+}
+
+#[derive(Clone, Copy)]
+pub struct Statistics {
+    rays: u64,
+    primary_rays: u64,
+    intersection_tests: u64,
+    bvh_node_visits: u64,
+}
+
+impl Statistics {
+    pub fn snapshot() -> Self {
+        Self {
+            rays: RAYS.load(Ordering::Relaxed),
+            primary_rays: PRIMARY_RAYS.load(Ordering::Relaxed),
+            intersection_tests: INTERSECTION_TESTS.load(Ordering::Relaxed),
+            bvh_node_visits: BVH_NODE_VISITS.load(Ordering::Relaxed),
+        }
+    }
+
+    pub const fn get_rays(&self) -> u64 {
+        self.rays
+    }
+
+    pub fn print_summary(&self, phases: &[(&str, Duration)]) {
+        eprintln!("Render statistics");
+        eprintln!("  Total rays:          {}", self.rays);
+        eprintln!("  Primary rays:        {}", self.primary_rays);
+        eprintln!(
+            "  Secondary rays:      {}",
+            self.rays.saturating_sub(self.primary_rays)
+        );
+        eprintln!("  Intersection tests:  {}", self.intersection_tests);
+        eprintln!("  BVH node visits:     {}", self.bvh_node_visits);
+        for (name, duration) in phases {
+            eprintln!(
+                "  {:<20} {:.3}s",
+                format!("{}:", name),
+                duration.as_secs_f64()
+            );
+        }
+    }
+}
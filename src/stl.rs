@@ -0,0 +1,277 @@
+use std::fs;
+
+use crate::{
+    color::{Color, WHITE},
+    color_pipeline::srgb_eotf,
+    mesh::TriangleMesh,
+    vec3::Point3,
+};
+
+const HEADER_SIZE: usize = 80;
+const FACET_SIZE: usize = 50;
+
+/// Reads a mesh from an ASCII or binary STL file. Every facet gets its own three vertices and
+/// is flat shaded, since the stored facet normals are often unreliable. Facet colors in the
+/// attribute bytes of binary files, as written by VisCAM, SolidView and Materialise Magics,
+/// become vertex colors.
+pub fn load_stl(path: &str) -> Result<TriangleMesh, String> {
+    let data = fs::read(path).map_err(|error| format!("failed to read {}: {}", path, error))?;
+    parse(&data).map_err(|message| format!("{}: {}", path, message))
+}
+
+fn parse(data: &[u8]) -> Result<TriangleMesh, String> {
+    // ASCII files start with `solid`, but so do some binary ones, whose size gives them away.
+    let binary_count = data
+        .get(HEADER_SIZE..HEADER_SIZE + 4)
+        .map(|count| u32::from_le_bytes([count[0], count[1], count[2], count[3]]) as usize);
+    match binary_count {
+        Some(count) if data.len() == HEADER_SIZE + 4 + count * FACET_SIZE => {
+            Ok(parse_binary(data, count))
+        }
+        _ if data.starts_with(b"solid") => parse_ascii(data),
+        _ => Err("not an STL file".to_string()),
+    }
+}
+
+fn parse_binary(data: &[u8], count: usize) -> TriangleMesh {
+    let header = &data[..HEADER_SIZE];
+    // Magics writes a default color into the header and clears bit 15 for facets with their
+    // own color, with red in the low bits. VisCAM and SolidView set bit 15 instead, with blue
+    // in the low bits.
+    let magics_color = header
+        .windows(6)
+        .position(|window| window == b"COLOR=")
+        .and_then(|start| header.get(start + 6..start + 9))
+        .map(|rgb| {
+            let [r, g, b] = [rgb[0], rgb[1], rgb[2]].map(|byte| srgb_eotf(f64::from(byte) / 255.0));
+            Color::new(r, g, b)
+        });
+
+    let mut positions = Vec::with_capacity(count * 3);
+    let mut colors = Vec::new();
+    for facet in data[HEADER_SIZE + 4..].chunks_exact(FACET_SIZE) {
+        let float = |offset: usize| {
+            let bytes = &facet[offset..offset + 4];
+            f64::from(f32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]))
+        };
+        // The facet normal comes first.
+        for vertex in 0..3 {
+            let offset = 12 + vertex * 12;
+            positions.push(Point3::new(
+                float(offset),
+                float(offset + 4),
+                float(offset + 8),
+            ));
+        }
+
+        let attribute = u16::from_le_bytes([facet[48], facet[49]]);
+        let channel = |shift: u16| srgb_eotf(f64::from((attribute >> shift) & 0x1f) / 31.0);
+        let color = match magics_color {
+            Some(default) if attribute & 0x8000 != 0 => Some(default),
+            Some(_) => Some(Color::new(channel(0), channel(5), channel(10))),
+            None if attribute & 0x8000 != 0 => {
+                Some(Color::new(channel(10), channel(5), channel(0)))
+            }
+            None => None,
+        };
+        if let Some(color) = color {
+            // Facets before the first colored one were left white.
+            colors.resize(positions.len() - 3, WHITE);
+            colors.extend([color; 3]);
+        } else if !colors.is_empty() {
+            colors.extend([WHITE; 3]);
+        }
+    }
+
+    let triangles = (0..count)
+        .map(|facet| [3 * facet, 3 * facet + 1, 3 * facet + 2])
+        .collect();
+    let mesh = TriangleMesh::new(positions, triangles);
+    if colors.is_empty() {
+        mesh
+    } else {
+        mesh.with_colors(colors)
+    }
+}
+
+/// Loops of more than three vertices, which some exporters write, are split into triangle
+/// fans.
+fn parse_ascii(data: &[u8]) -> Result<TriangleMesh, String> {
+    let text = String::from_utf8_lossy(data);
+    // Skip the `solid` line, since the name may contain anything.
+    let mut tokens = text.lines().skip(1).flat_map(str::split_whitespace);
+
+    let mut positions = Vec::new();
+    let mut triangles = Vec::new();
+    let mut loop_start = 0;
+    while let Some(token) = tokens.next() {
+        match token {
+            "outer" => loop_start = positions.len(),
+            "vertex" => {
+                let mut coordinate = || {
+                    tokens
+                        .next()
+                        .and_then(|token| token.parse().ok())
+                        .ok_or("invalid vertex")
+                };
+                positions.push(Point3::new(coordinate()?, coordinate()?, coordinate()?));
+            }
+            "endloop" => {
+                for k in loop_start + 1..positions.len().saturating_sub(1) {
+                    triangles.push([loop_start, k, k + 1]);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    // A binary file with trailing bytes whose header happens to start with `solid` ends up
+    // here too.
+    if triangles.is_empty() {
+        return Err("no facets in ASCII STL file".to_string());
+    }
+
+    Ok(TriangleMesh::new(positions, triangles))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::rc::Rc;
+
+    use super::*;
+    use crate::{
+        constants::INFINITY,
+        hittable::{HitRecord, Hittable},
+        material::Lambertian,
+        mesh::Mesh,
+        ray::Ray,
+        vec3::Vec3,
+    };
+
+    const TRIANGLES: [[[f32; 3]; 3]; 2] = [
+        [[0.0, 0.0, 0.0], [1.0, 0.0, 0.0], [1.0, 1.0, 0.0]],
+        [[0.0, 0.0, 0.0], [1.0, 1.0, 0.0], [0.0, 1.0, 0.0]],
+    ];
+
+    fn binary(header: &[u8], attributes: [u16; 2]) -> Vec<u8> {
+        let mut data = header.to_vec();
+        data.resize(HEADER_SIZE, b' ');
+        data.extend(2u32.to_le_bytes());
+        for (triangle, attribute) in TRIANGLES.iter().zip(attributes) {
+            data.extend([0.0f32, 0.0, 1.0].map(f32::to_le_bytes).concat());
+            for vertex in triangle {
+                data.extend(vertex.map(f32::to_le_bytes).concat());
+            }
+            data.extend(attribute.to_le_bytes());
+        }
+        data
+    }
+
+    /// The hits on `mesh` by rays down the z axis through each half of the square.
+    fn hits(mesh: TriangleMesh) -> [Option<HitRecord>; 2] {
+        let mesh = Mesh::new(mesh, Rc::new(Lambertian::new(WHITE)));
+        [(0.75, 0.25), (0.25, 0.75)].map(|(x, y)| {
+            let ray = Ray::new(Point3::new(x, y, 1.0), Vec3::new(0.0, 0.0, -1.0));
+            mesh.hit(&ray, 0.001, INFINITY)
+        })
+    }
+
+    fn check_square(mesh: TriangleMesh) {
+        for record in hits(mesh) {
+            let record = record.unwrap();
+            assert!((record.get_t() - 1.0).abs() < 1e-9);
+            assert_eq!(record.get_normal().get_z(), 1.0);
+            assert!(record.get_color().is_none());
+        }
+    }
+
+    #[test]
+    fn ascii_square() {
+        let text = "solid square
+facet normal 0 0 1
+  outer loop
+    vertex 0 0 0
+    vertex 1 0 0
+    vertex 1 1 0
+  endloop
+endfacet
+facet normal 0 0 1
+  outer loop
+    vertex 0 0 0
+    vertex 1 1 0
+    vertex 0 1 0
+  endloop
+endfacet
+endsolid square
+";
+        check_square(parse(text.as_bytes()).unwrap());
+    }
+
+    #[test]
+    fn ascii_polygon() {
+        let text = "solid
+facet normal 0 0 1
+outer loop
+vertex 0 0 0
+vertex 1 0 0
+vertex 1 1 0
+vertex 0 1 0
+endloop
+endfacet
+endsolid
+";
+        check_square(parse(text.as_bytes()).unwrap());
+    }
+
+    #[test]
+    fn invalid_ascii() {
+        assert!(parse(b"solid\nfacet normal 0 0 1\nouter loop\nvertex 0 x 0\n").is_err());
+        assert!(parse(b"solid empty\nendsolid empty\n").is_err());
+        assert!(parse(b"not an stl file").is_err());
+    }
+
+    #[test]
+    fn binary_square() {
+        check_square(parse(&binary(b"binary square", [0, 0])).unwrap());
+
+        // Binary files whose header starts with `solid` are told apart by their size.
+        check_square(parse(&binary(b"solid square", [0, 0])).unwrap());
+    }
+
+    #[test]
+    fn trailing_bytes() {
+        let mut data = binary(b"solid square", [0, 0]);
+        data.push(0);
+        assert!(parse(&data).is_err());
+
+        let mut data = binary(b"binary square", [0, 0]);
+        data.push(0);
+        assert!(parse(&data).is_err());
+        assert!(parse(&data[..90]).is_err());
+    }
+
+    #[test]
+    fn viscam_colors() {
+        // Blue in the low bits, with bit 15 set for facets that have a color.
+        let [first, second] = hits(parse(&binary(b"binary square", [0, 0x8000 | 0x1f])).unwrap())
+            .map(|record| record.unwrap().get_color().unwrap());
+        assert_eq!(first.get_x(), 1.0);
+        assert_eq!(first.get_z(), 1.0);
+        assert_eq!(second.get_x(), 0.0);
+        assert_eq!(second.get_z(), 1.0);
+    }
+
+    #[test]
+    fn magics_colors() {
+        // The default color in the header applies where bit 15 is set, and red is in the low
+        // bits otherwise.
+        let mut header = b"COLOR=".to_vec();
+        header.extend([0, 255, 0, 255]);
+        let [first, second] = hits(parse(&binary(&header, [0x8000, 0x1f])).unwrap())
+            .map(|record| record.unwrap().get_color().unwrap());
+        assert_eq!(first.get_x(), 0.0);
+        assert_eq!(first.get_y(), 1.0);
+        assert_eq!(second.get_x(), 1.0);
+        assert_eq!(second.get_y(), 0.0);
+    }
+}
@@ -0,0 +1,145 @@
+use std::collections::HashMap;
+
+use crate::{mesh::TriangleMesh, vec3::Point3};
+
+/// A mesh of polygons, usually quads, as the control cage for Catmull-Clark subdivision.
+pub struct PolygonMesh {
+    positions: Vec<Point3>,
+    faces: Vec<Vec<usize>>,
+}
+
+struct Edge {
+    faces: Vec<usize>,
+    point: usize,
+}
+
+impl PolygonMesh {
+    pub const fn new(positions: Vec<Point3>, faces: Vec<Vec<usize>>) -> Self {
+        Self { positions, faces }
+    }
+
+    /// One step of Catmull-Clark subdivision, which turns every face into quads. Open
+    /// boundaries follow the cubic B-spline through the boundary vertices.
+    fn catmull_clark(&self) -> Self {
+        let average = |points: &mut dyn Iterator<Item = Point3>| {
+            let (sum, count) = points.fold((Point3::new(0.0, 0.0, 0.0), 0), |(sum, count), p| {
+                (sum.add(&p), count + 1)
+            });
+            sum.divide_constant(count as f64)
+        };
+
+        let face_points: Vec<Point3> = self
+            .faces
+            .iter()
+            .map(|face| average(&mut face.iter().map(|index| self.positions[*index])))
+            .collect();
+
+        let key = |a: usize, b: usize| (a.min(b), a.max(b));
+        let mut edges: HashMap<(usize, usize), Edge> = HashMap::new();
+        let first_edge_point = self.positions.len() + self.faces.len();
+        for (face_index, face) in self.faces.iter().enumerate() {
+            for (k, a) in face.iter().enumerate() {
+                let b = face[(k + 1) % face.len()];
+                let count = edges.len();
+                edges
+                    .entry(key(*a, b))
+                    .or_insert_with(|| Edge {
+                        faces: Vec::new(),
+                        point: first_edge_point + count,
+                    })
+                    .faces
+                    .push(face_index);
+            }
+        }
+
+        let mut positions = vec![Point3::new(0.0, 0.0, 0.0); first_edge_point + edges.len()];
+        positions[self.positions.len()..first_edge_point].copy_from_slice(&face_points);
+
+        let mut vertex_faces = vec![Vec::new(); self.positions.len()];
+        for (face_index, face) in self.faces.iter().enumerate() {
+            for index in face {
+                vertex_faces[*index].push(face_index);
+            }
+        }
+        let mut vertex_edges = vec![Vec::new(); self.positions.len()];
+        for (&(a, b), edge) in &edges {
+            let midpoint = self.positions[a]
+                .add(&self.positions[b])
+                .divide_constant(2.0);
+            positions[edge.point] = if let [f0, f1] = edge.faces[..] {
+                midpoint
+                    .add(&face_points[f0].add(&face_points[f1]).divide_constant(2.0))
+                    .divide_constant(2.0)
+            } else {
+                midpoint
+            };
+            vertex_edges[a].push((b, edge.faces.len() < 2));
+            vertex_edges[b].push((a, edge.faces.len() < 2));
+        }
+
+        for (index, position) in self.positions.iter().enumerate() {
+            let boundary: Vec<Point3> = vertex_edges[index]
+                .iter()
+                .filter(|(_, boundary)| *boundary)
+                .map(|(other, _)| self.positions[*other])
+                .collect();
+
+            positions[index] = if let [a, b] = boundary[..] {
+                position
+                    .multiply_constant(6.0)
+                    .add(&a)
+                    .add(&b)
+                    .divide_constant(8.0)
+            } else if !boundary.is_empty() || vertex_faces[index].is_empty() {
+                *position
+            } else {
+                let n = vertex_faces[index].len() as f64;
+                let faces = average(&mut vertex_faces[index].iter().map(|f| face_points[*f]));
+                let edges =
+                    average(&mut vertex_edges[index].iter().map(|(other, _)| {
+                        position.add(&self.positions[*other]).divide_constant(2.0)
+                    }));
+                faces
+                    .add(&edges.multiply_constant(2.0))
+                    .add(&position.multiply_constant(n - 3.0))
+                    .divide_constant(n)
+            };
+        }
+
+        let faces = self
+            .faces
+            .iter()
+            .enumerate()
+            .flat_map(|(face_index, face)| {
+                let edges = &edges;
+                (0..face.len()).map(move |k| {
+                    let previous = face[(k + face.len() - 1) % face.len()];
+                    let current = face[k];
+                    let next = face[(k + 1) % face.len()];
+                    vec![
+                        current,
+                        edges[&key(current, next)].point,
+                        self.positions.len() + face_index,
+                        edges[&key(previous, current)].point,
+                    ]
+                })
+            })
+            .collect();
+
+        Self { positions, faces }
+    }
+
+    /// Subdivides `levels` times and splits the faces into triangles with smooth normals.
+    pub fn subdivide(self, levels: usize) -> TriangleMesh {
+        let mesh = (0..levels).fold(self, |mesh, _| mesh.catmull_clark());
+        let triangles = mesh
+            .faces
+            .iter()
+            .flat_map(|face| {
+                (1..face.len().saturating_sub(1)).map(|k| [face[0], face[k], face[k + 1]])
+            })
+            .collect();
+
+        TriangleMesh::new(mesh.positions, triangles).with_smooth_normals()
+    }
+}
@@ -0,0 +1,30 @@
+use crate::{color::Color, matrix::Matrix3};
+
+use super::ToneMapper;
+
+const INPUT: Matrix3 = Matrix3::new([
+    [0.59719, 0.35458, 0.04823],
+    [0.07600, 0.90834, 0.01566],
+    [0.02840, 0.13383, 0.83777],
+]);
+const OUTPUT: Matrix3 = Matrix3::new([
+    [1.60475, -0.53108, -0.07367],
+    [-0.10208, 1.10813, -0.00605],
+    [-0.00327, -0.07276, 1.07602],
+]);
+
+/// Stephen Hill's fit of the ACES reference rendering and sRGB output transforms.
+pub struct AcesToneMapper;
+
+impl ToneMapper for AcesToneMapper {
+    fn map(&self, color: &Color) -> Color {
+        let v = INPUT.multiply_vector(color);
+        let fit = |v: f64| {
+            let a = v * (v + 0.0245786) - 0.000090537;
+            let b = v * (0.983729 * v + 0.4329510) + 0.238081;
+            a / b
+        };
+
+        OUTPUT.multiply_vector(&Color::new(fit(v.get_x()), fit(v.get_y()), fit(v.get_z())))
+    }
+}
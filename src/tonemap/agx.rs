@@ -0,0 +1,44 @@
+use crate::{color::Color, matrix::Matrix3};
+
+use super::ToneMapper;
+
+const MIN_EV: f64 = -12.47393;
+const MAX_EV: f64 = 4.026069;
+const INSET: Matrix3 = Matrix3::new([
+    [0.842479062253094, 0.0784335999999992, 0.0792237451477643],
+    [0.0423282422610123, 0.878468636469772, 0.0791661274605434],
+    [0.0423756549057051, 0.0784336, 0.879142973793104],
+]);
+const OUTSET: Matrix3 = Matrix3::new([
+    [1.19687900512017, -0.0980208811401368, -0.0990297440797205],
+    [-0.0528968517574562, 1.15190312990417, -0.0989611768448433],
+    [-0.0529716355144438, -0.0980434501171241, 1.15107367264116],
+]);
+
+/// Troy Sobotka's AgX base look, using the polynomial fit of its sigmoid.
+pub struct AgxToneMapper;
+
+impl ToneMapper for AgxToneMapper {
+    fn map(&self, color: &Color) -> Color {
+        let inset = INSET.multiply_vector(&color.max_constant(1e-10));
+        let curve = |value: f64| {
+            let x = (value.log2().clamp(MIN_EV, MAX_EV) - MIN_EV) / (MAX_EV - MIN_EV);
+            let x2 = x * x;
+            let x4 = x2 * x2;
+            15.5 * x4 * x2 - 40.14 * x4 * x + 31.96 * x4 - 6.868 * x2 * x + 0.4298 * x2 + 0.1191 * x
+                - 0.00232
+        };
+        let outset = OUTSET.multiply_vector(&Color::new(
+            curve(inset.get_x()),
+            curve(inset.get_y()),
+            curve(inset.get_z()),
+        ));
+        let linearize = |value: f64| value.max(0.0).powf(2.2);
+
+        Color::new(
+            linearize(outset.get_x()),
+            linearize(outset.get_y()),
+            linearize(outset.get_z()),
+        )
+    }
+}
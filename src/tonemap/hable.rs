@@ -0,0 +1,36 @@
+use crate::color::Color;
+
+use super::ToneMapper;
+
+const A: f64 = 0.15;
+const B: f64 = 0.50;
+const C: f64 = 0.10;
+const D: f64 = 0.20;
+const E: f64 = 0.02;
+const F: f64 = 0.30;
+
+/// John Hable's filmic curve from Uncharted 2.
+pub struct HableToneMapper {
+    white_scale: f64,
+    exposure_bias: f64,
+}
+
+impl HableToneMapper {
+    pub fn new(white: f64, exposure_bias: f64) -> Self {
+        Self {
+            white_scale: 1.0 / curve(white),
+            exposure_bias,
+        }
+    }
+}
+
+impl ToneMapper for HableToneMapper {
+    fn map(&self, color: &Color) -> Color {
+        let map = |value: f64| curve(value * self.exposure_bias) * self.white_scale;
+        Color::new(map(color.get_x()), map(color.get_y()), map(color.get_z()))
+    }
+}
+
+fn curve(x: f64) -> f64 {
+    (x * (A * x + C * B) + D * E) / (x * (A * x + B) + D * F) - E / F
+}
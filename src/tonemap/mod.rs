@@ -0,0 +1,36 @@
+use crate::color::Color;
+pub use aces::AcesToneMapper;
+pub use agx::AgxToneMapper;
+pub use hable::HableToneMapper;
+pub use reinhard::ReinhardToneMapper;
+
+mod aces;
+mod agx;
+mod hable;
+mod reinhard;
+
+/// Maps scene-referred linear radiance to display-referred linear values in [0, 1].
+pub trait ToneMapper {
+    fn map(&self, color: &Color) -> Color;
+}
+
+pub struct LinearToneMapper;
+
+impl ToneMapper for LinearToneMapper {
+    fn map(&self, color: &Color) -> Color {
+        *color
+    }
+}
+
+pub fn from_name(name: &str) -> Option<Box<dyn ToneMapper>> {
+    let tone_mapper: Box<dyn ToneMapper> = match name {
+        "linear" => Box::new(LinearToneMapper),
+        "reinhard" => Box::new(ReinhardToneMapper::new(4.0)),
+        "aces" => Box::new(AcesToneMapper),
+        "agx" => Box::new(AgxToneMapper),
+        "hable" => Box::new(HableToneMapper::new(11.2, 2.0)),
+        _ => return None,
+    };
+
+    Some(tone_mapper)
+}
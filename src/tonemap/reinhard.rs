@@ -0,0 +1,26 @@
+use crate::color::{luminance, Color, BLACK};
+
+use super::ToneMapper;
+
+/// Extended Reinhard on luminance, mapping `white` and above to 1.
+pub struct ReinhardToneMapper {
+    white: f64,
+}
+
+impl ReinhardToneMapper {
+    pub const fn new(white: f64) -> Self {
+        Self { white }
+    }
+}
+
+impl ToneMapper for ReinhardToneMapper {
+    fn map(&self, color: &Color) -> Color {
+        let luminance = luminance(color);
+        if luminance <= 0.0 {
+            return BLACK;
+        }
+
+        let mapped = luminance * (1.0 + luminance / (self.white * self.white)) / (1.0 + luminance);
+        color.multiply_constant(mapped / luminance)
+    }
+}
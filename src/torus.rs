@@ -0,0 +1,179 @@
+use std::rc::Rc;
+
+use crate::{
+    aabb::Aabb,
+    constants::PI,
+    frame::{azimuth, Frame},
+    hittable::{first_in_range, pair_intervals, HitRecord, Hittable, Interval, Solid},
+    material::Material,
+    polynomial::solve_quartic,
+    ray::Ray,
+    stats,
+    vec3::{Point3, Vec3},
+};
+
+/// A ring around `axis` through `center`. u goes around the axis and v around the tube.
+pub struct Torus {
+    frame: Frame,
+    major_radius: f64,
+    minor_radius: f64,
+    material: Rc<dyn Material>,
+}
+
+impl Torus {
+    pub fn new(
+        center: Point3,
+        axis: Vec3,
+        major_radius: f64,
+        minor_radius: f64,
+        material: Rc<dyn Material>,
+    ) -> Self {
+        Self {
+            frame: Frame::new(center, &axis),
+            major_radius,
+            minor_radius,
+            material,
+        }
+    }
+
+    /// Every intersection, sorted along the whole ray. The quartic is solved for a unit
+    /// direction, which keeps its coefficients well conditioned.
+    fn records(&self, ray: &Ray) -> Vec<HitRecord> {
+        let origin = self.frame.to_local_point(&ray.get_origin());
+        let direction = self.frame.to_local_vector(&ray.get_direction());
+        let length = direction.length();
+        let direction = direction.divide_constant(length);
+
+        let major2 = self.major_radius * self.major_radius;
+        let minor2 = self.minor_radius * self.minor_radius;
+        let e = origin.square() - major2 - minor2;
+        let f = origin.dot(&direction);
+        let roots = solve_quartic(
+            1.0,
+            4.0 * f,
+            2.0 * e + 4.0 * f * f + 4.0 * major2 * direction.get_y() * direction.get_y(),
+            4.0 * f * e + 8.0 * major2 * origin.get_y() * direction.get_y(),
+            e * e - 4.0 * major2 * (minor2 - origin.get_y() * origin.get_y()),
+        );
+
+        roots
+            .into_iter()
+            .map(|distance| {
+                let local = origin.add(&direction.multiply_constant(distance));
+                let t = distance / length;
+
+                let sum = local.square() + major2 - minor2;
+                let normal = local.multiply_constant(sum).subtract(
+                    &Vec3::new(local.get_x(), 0.0, local.get_z()).multiply_constant(2.0 * major2),
+                );
+                let ring_distance = local.get_x().hypot(local.get_z()) - self.major_radius;
+                let tube = (local.get_y().atan2(ring_distance) + PI) / (2.0 * PI);
+
+                HitRecord::new(
+                    ray.at(t),
+                    self.frame.to_world_vector(&normal).unit_vector(),
+                    t,
+                    (azimuth(&local), tube),
+                    ray,
+                    Rc::clone(&self.material),
+                )
+            })
+            .collect()
+    }
+}
+
+impl Hittable for Torus {
+    fn hit(&self, ray: &Ray, t_min: f64, t_max: f64) -> Option<HitRecord> {
+        stats::record_intersection_test();
+        first_in_range(self.records(ray), t_min, t_max)
+    }
+
+    fn bounding_box(&self) -> Option<Aabb> {
+        let axis = self.frame.get_axis();
+        let extent = |component: f64| {
+            (self.major_radius + self.minor_radius) * (1.0 - component * component).max(0.0).sqrt()
+                + self.minor_radius * component.abs()
+        };
+
+        Some(Aabb::around(
+            self.frame.get_origin(),
+            Vec3::new(
+                extent(axis.get_x()),
+                extent(axis.get_y()),
+                extent(axis.get_z()),
+            ),
+        ))
+    }
+}
+
+impl Solid for Torus {
+    fn intervals(&self, ray: &Ray) -> Vec<Interval> {
+        stats::record_intersection_test();
+        pair_intervals(self.records(ray))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{color::Color, constants::INFINITY, material::Lambertian};
+
+    fn torus() -> Torus {
+        Torus::new(
+            Point3::new(0.0, 0.0, 0.0),
+            Vec3::new(0.0, 1.0, 0.0),
+            2.0,
+            0.5,
+            Rc::new(Lambertian::new(Color::new(0.5, 0.5, 0.5))),
+        )
+    }
+
+    fn assert_near(actual: f64, expected: f64) {
+        assert!(
+            (actual - expected).abs() < 1e-6,
+            "{} != {}",
+            actual,
+            expected
+        );
+    }
+
+    #[test]
+    fn hit_along_axis() {
+        let ray = Ray::new(Point3::new(2.0, 5.0, 0.0), Vec3::new(0.0, -2.0, 0.0));
+        let record = torus().hit(&ray, 0.0, INFINITY).unwrap();
+        assert_near(record.get_t(), 2.25);
+        assert_near(record.get_point().get_y(), 0.5);
+        assert_near(record.get_normal().get_y(), 1.0);
+
+        let intervals: Vec<_> = torus()
+            .intervals(&ray)
+            .into_iter()
+            .map(Interval::into_records)
+            .collect();
+        assert_eq!(intervals.len(), 1);
+        assert_near(intervals[0].0.get_t(), 2.25);
+        assert_near(intervals[0].1.get_t(), 2.75);
+    }
+
+    #[test]
+    fn ray_through_hole() {
+        let down_axis = Ray::new(Point3::new(0.0, 5.0, 0.0), Vec3::new(0.0, -1.0, 0.0));
+        assert!(torus().hit(&down_axis, 0.0, INFINITY).is_none());
+
+        let across = Ray::new(Point3::new(-5.0, 0.0, 0.0), Vec3::new(1.0, 0.0, 0.0));
+        let ts: Vec<_> = torus()
+            .records(&across)
+            .iter()
+            .map(HitRecord::get_t)
+            .collect();
+        assert_eq!(ts.len(), 4);
+        for (t, expected) in ts.iter().zip([2.5, 3.5, 6.5, 7.5]) {
+            assert_near(*t, expected);
+        }
+
+        let from_hole = Ray::new(Point3::new(0.0, 0.0, 0.0), Vec3::new(0.0, 0.0, 1.0));
+        let record = torus().hit(&from_hole, 0.001, INFINITY).unwrap();
+        assert_near(record.get_t(), 1.5);
+        assert_near(record.get_normal().get_z(), -1.0);
+    }
+}
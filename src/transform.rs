@@ -0,0 +1,171 @@
+use std::rc::Rc;
+
+use crate::{
+    aabb::Aabb,
+    hittable::{HitRecord, Hittable},
+    material::Material,
+    matrix::Matrix3,
+    ray::Ray,
+    utils::degrees_to_radians,
+    vec3::{Point3, Vec3},
+};
+
+/// Scales and rotates about the origin, then translates.
+#[derive(Clone, Copy)]
+pub struct Transform {
+    rotation: Matrix3,
+    scale: f64,
+    translation: Vec3,
+}
+
+impl Transform {
+    /// `rotation` holds angles in degrees about the x, y and z axes, applied in that order.
+    pub fn new(translation: Vec3, rotation: &Vec3, scale: f64) -> Self {
+        let (sin_x, cos_x) = degrees_to_radians(rotation.get_x()).sin_cos();
+        let (sin_y, cos_y) = degrees_to_radians(rotation.get_y()).sin_cos();
+        let (sin_z, cos_z) = degrees_to_radians(rotation.get_z()).sin_cos();
+        let x = Matrix3::new([[1.0, 0.0, 0.0], [0.0, cos_x, -sin_x], [0.0, sin_x, cos_x]]);
+        let y = Matrix3::new([[cos_y, 0.0, sin_y], [0.0, 1.0, 0.0], [-sin_y, 0.0, cos_y]]);
+        let z = Matrix3::new([[cos_z, -sin_z, 0.0], [sin_z, cos_z, 0.0], [0.0, 0.0, 1.0]]);
+
+        Self {
+            rotation: z.multiply(&y).multiply(&x),
+            scale,
+            translation,
+        }
+    }
+
+    pub fn transform_point(&self, point: &Point3) -> Point3 {
+        self.transform_vector(point).add(&self.translation)
+    }
+
+    pub fn transform_vector(&self, vector: &Vec3) -> Vec3 {
+        self.rotation
+            .multiply_vector(vector)
+            .multiply_constant(self.scale)
+    }
+
+    fn inverse_point(&self, point: &Point3) -> Point3 {
+        self.inverse_vector(&point.subtract(&self.translation))
+    }
+
+    fn inverse_vector(&self, vector: &Vec3) -> Vec3 {
+        self.rotation
+            .transpose()
+            .multiply_vector(vector)
+            .divide_constant(self.scale)
+    }
+}
+
+/// An object placed by a transform. Rays are taken into the object's own space to hit it, so
+/// one object can be shared by several placements.
+pub struct Transformed {
+    object: Rc<dyn Hittable>,
+    transform: Transform,
+    material: Option<Rc<dyn Material>>,
+}
+
+impl Transformed {
+    pub const fn new(object: Rc<dyn Hittable>, transform: Transform) -> Self {
+        Self {
+            object,
+            transform,
+            material: None,
+        }
+    }
+
+    /// Replaces the material of every hit, for shared objects whose material is animated.
+    pub fn with_material(self, material: Rc<dyn Material>) -> Self {
+        Self {
+            material: Some(material),
+            ..self
+        }
+    }
+}
+
+impl Hittable for Transformed {
+    fn hit(&self, ray: &Ray, t_min: f64, t_max: f64) -> Option<HitRecord> {
+        // The direction isn't renormalized, so `t` is the same in both spaces.
+        let mut local = Ray::new(
+            self.transform.inverse_point(&ray.get_origin()),
+            self.transform.inverse_vector(&ray.get_direction()),
+        );
+        if let Some(wavelength) = ray.get_wavelength() {
+            local = local.with_wavelength(wavelength);
+        }
+
+        let record = self.object.hit(&local, t_min, t_max)?;
+        let point = self.transform.transform_point(&record.get_point());
+        // With a uniform scale, normals transform like any other direction.
+        let normal = self
+            .transform
+            .transform_vector(&record.get_normal())
+            .unit_vector();
+        let tangent = record
+            .get_tangent()
+            .map(|tangent| self.transform.transform_vector(&tangent).unit_vector());
+        let mut record = record.transformed(point, normal);
+        if let Some(tangent) = tangent {
+            record = record.with_tangent(tangent);
+        }
+        Some(match &self.material {
+            Some(material) => record.with_material(Rc::clone(material)),
+            None => record,
+        })
+    }
+
+    /// The box around the transformed corners of the object's own box.
+    fn bounding_box(&self) -> Option<Aabb> {
+        let local = self.object.bounding_box()?;
+        let (minimum, maximum) = (local.get_minimum(), local.get_maximum());
+        (0..8)
+            .map(|corner| {
+                let pick = |bit: usize, low: f64, high: f64| {
+                    if corner & bit == 0 {
+                        low
+                    } else {
+                        high
+                    }
+                };
+                let point = Point3::new(
+                    pick(1, minimum.get_x(), maximum.get_x()),
+                    pick(2, minimum.get_y(), maximum.get_y()),
+                    pick(4, minimum.get_z(), maximum.get_z()),
+                );
+                let point = self.transform.transform_point(&point);
+                Aabb::new(point, point)
+            })
+            .reduce(|a, b| a.surrounding(&b))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{color::Color, constants::INFINITY, material::Lambertian, sphere::Sphere};
+
+    #[test]
+    fn hits_in_object_space() {
+        // A sphere off the local origin, turned a quarter around z so it sits above the pivot.
+        let sphere = Sphere::new(
+            Point3::new(1.0, 0.0, 0.0),
+            0.5,
+            Rc::new(Lambertian::new(Color::new(0.5, 0.5, 0.5))),
+        );
+        let transform = Transform::new(Vec3::new(0.0, 0.0, -5.0), &Vec3::new(0.0, 0.0, 90.0), 2.0);
+        let object = Transformed::new(Rc::new(sphere), transform);
+
+        let ray = Ray::new(Point3::new(0.0, 2.0, 0.0), Vec3::new(0.0, 0.0, -1.0));
+        let record = object.hit(&ray, 0.001, INFINITY).unwrap();
+        assert!((record.get_t() - 4.0).abs() < 1e-9, "{}", record.get_t());
+        assert!((record.get_normal().get_z() - 1.0).abs() < 1e-9);
+        assert!(record.get_front_face());
+
+        let miss = Ray::new(Point3::new(2.0, 0.0, 0.0), Vec3::new(0.0, 0.0, -1.0));
+        assert!(object.hit(&miss, 0.001, INFINITY).is_none());
+
+        let bounds = object.bounding_box().unwrap();
+        assert!(bounds.contains(&Point3::new(0.0, 2.9, -5.0)));
+        assert!(!bounds.contains(&Point3::new(0.0, 0.5, -5.0)));
+    }
+}
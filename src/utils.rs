@@ -29,40 +29,55 @@ pub fn random_vec_in_range(min: f64, max: f64) -> Vec3 {
     )
 }
 
-pub fn random_unit_vector() -> Vec3 {
-    random_in_unit_sphere().unit_vector()
-}
+pub fn sample_unit_disk((u1, u2): (f64, f64)) -> Vec3 {
+    let x = 2.0 * u1 - 1.0;
+    let y = 2.0 * u2 - 1.0;
+    if x == 0.0 && y == 0.0 {
+        return Vec3::new(0.0, 0.0, 0.0);
+    }
 
-pub fn random_in_hemisphere(normal: &Vec3) -> Vec3 {
-    let in_unit_sphere = random_in_unit_sphere();
-    if in_unit_sphere.dot(normal) > 0.0 {
-        in_unit_sphere
+    let (r, theta) = if x.abs() > y.abs() {
+        (x, PI / 4.0 * (y / x))
     } else {
-        in_unit_sphere.negative()
-    }
+        (y, PI / 2.0 - PI / 4.0 * (x / y))
+    };
+    Vec3::new(r * theta.cos(), r * theta.sin(), 0.0)
 }
 
-pub fn random_in_unit_sphere() -> Vec3 {
-    loop {
-        let vec3 = Vec3::new(
-            random_in_range(-1.0, 1.0),
-            random_in_range(-1.0, 1.0),
-            random_in_range(-1.0, 1.0),
-        );
-
-        if vec3.length() < 1.0 {
-            return vec3;
-        }
-    }
+pub fn sample_unit_sphere((u1, u2): (f64, f64)) -> Vec3 {
+    let z = 1.0 - 2.0 * u1;
+    let r = (1.0 - z * z).max(0.0).sqrt();
+    let phi = 2.0 * PI * u2;
+    Vec3::new(r * phi.cos(), r * phi.sin(), z)
 }
 
-pub fn random_in_unit_disk() -> Vec3 {
-    loop {
-        let vec3 = Vec3::new(random_in_range(-1.0, 1.0), random_in_range(-1.0, 1.0), 0.0);
-        if vec3.length() < 1.0 {
-            return vec3;
-        }
-    }
+pub fn sample_in_unit_sphere(u: (f64, f64), u_radius: f64) -> Vec3 {
+    sample_unit_sphere(u).multiply_constant(u_radius.cbrt())
+}
+
+/// Two unit vectors that form an orthonormal basis with the unit vector `w`.
+pub fn orthonormal_basis(w: &Vec3) -> (Vec3, Vec3) {
+    let a = if w.get_x().abs() > 0.9 {
+        Vec3::new(0.0, 1.0, 0.0)
+    } else {
+        Vec3::new(1.0, 0.0, 0.0)
+    };
+    let v = w.cross(&a).unit_vector();
+    let u = w.cross(&v);
+
+    (u, v)
+}
+
+pub fn sample_cosine_direction(normal: &Vec3, u: (f64, f64)) -> Vec3 {
+    let disk = sample_unit_disk(u);
+    let z = (1.0 - disk.square()).max(0.0).sqrt();
+
+    let w = normal.unit_vector();
+    let (u, v) = orthonormal_basis(&w);
+
+    u.multiply_constant(disk.get_x())
+        .add(&v.multiply_constant(disk.get_y()))
+        .add(&w.multiply_constant(z))
 }
 
 pub fn refract(unit_direction: &Vec3, normal: &Vec3, refraction_ratio: f64) -> Vec3 {
@@ -75,5 +90,5 @@ pub fn refract(unit_direction: &Vec3, normal: &Vec3, refraction_ratio: f64) -> V
 }
 
 pub fn reflect(v: &Vec3, n: &Vec3) -> Vec3 {
-    v.subtract(&n.multiply_constant(v.dot(&n) * 2.0))
+    v.subtract(&n.multiply_constant(v.dot(n) * 2.0))
 }
@@ -44,6 +44,10 @@ impl Vec3 {
         Self::new(self.x * other.x, self.y * other.y, self.z * other.z)
     }
 
+    pub fn divide(&self, other: &Self) -> Self {
+        Self::new(self.x / other.x, self.y / other.y, self.z / other.z)
+    }
+
     pub fn add_constant(&self, value: f64) -> Self {
         Self::new(self.x + value, self.y + value, self.z + value)
     }
@@ -56,6 +60,10 @@ impl Vec3 {
         self.multiply_constant(1.0 / value)
     }
 
+    pub fn max_constant(&self, value: f64) -> Self {
+        Self::new(self.x.max(value), self.y.max(value), self.z.max(value))
+    }
+
     pub fn dot(&self, other: &Self) -> f64 {
         self.x * other.x + self.y * other.y + self.z * other.z
     }